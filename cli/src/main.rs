@@ -0,0 +1,315 @@
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+use words::{
+    definitions,
+    spelling_bee::{
+        normalize_letters, SpellingBee, SpellingBeeHintedParams, SpellingBeeSimpleParams,
+    },
+    WordsDict, WordsOverlay, WordsShortcuts,
+};
+
+use ocr::{Glyphs, HiveReader};
+use output::Format;
+
+mod output;
+mod tui;
+
+/// Solve word puzzles from the terminal.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    /// Dictionary to use instead of the bundled one,
+    /// either a word list with one word per line or a binary built with `dict build`.
+    #[arg(long, global = true)]
+    dict: Option<PathBuf>,
+
+    /// Word list added to the dictionary.
+    #[arg(long, global = true)]
+    allow: Option<PathBuf>,
+
+    /// Word list removed from the dictionary, wins over `--allow`.
+    #[arg(long, global = true)]
+    block: Option<PathBuf>,
+
+    /// Output format.
+    #[arg(long, short, value_enum, default_value_t = Format::Plain, global = true)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Spelling Bee solvers.
+    #[command(subcommand)]
+    SpellingBee(SpellingBeeCommand),
+    /// Words starting with the prefix.
+    Search { prefix: String },
+    /// Interactive terminal UI for Spelling Bee.
+    Tui,
+    /// Dictionary files.
+    #[command(subcommand)]
+    Dict(DictCommand),
+    /// Reading hive letters from screenshots.
+    #[command(subcommand)]
+    Ocr(OcrCommand),
+}
+
+#[derive(Subcommand)]
+enum DictCommand {
+    /// Encode a word list into the binary format, loaded without parsing.
+    Build {
+        /// Word list with one word per line.
+        input: PathBuf,
+        output: PathBuf,
+    },
+    /// Convert WordNet data files into a definitions file for the server,
+    /// keeping only the words of the dictionary.
+    Definitions {
+        /// WordNet `dict` directory with the `data.*` files.
+        wordnet: PathBuf,
+        output: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum OcrCommand {
+    /// Print the letters of a PNG or JPEG screenshot, center letter first.
+    Read {
+        image: PathBuf,
+        /// Glyph templates to use instead of the bundled ones.
+        #[arg(long)]
+        glyphs: Option<PathBuf>,
+    },
+    /// Add the letters of screenshots to a glyph templates file, creating it when missing.
+    /// Each screenshot is named after its letters: center first, then clockwise from the top,
+    /// like `lacerst.png`.
+    Train {
+        output: PathBuf,
+        #[arg(required = true)]
+        images: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SpellingBeeCommand {
+    /// Solve with letters only.
+    Simple {
+        /// Hive letters, first one is the required one.
+        letters: String,
+    },
+    /// Solve with hints from the puzzle hints page.
+    Hinted {
+        /// Hive letters, first one is the required one.
+        letters: String,
+        /// File with the letter/length matrix, `-` to read from stdin.
+        #[arg(long)]
+        matrix: Option<PathBuf>,
+        /// File with the two letter list, `-` to read from stdin.
+        #[arg(long)]
+        list: Option<PathBuf>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    if let Command::Dict(DictCommand::Build { input, output }) = &cli.command {
+        let dict = WordsDict::open(input)?;
+        // Renamed into place, so a watching server never reads half of it
+        dict.save(output)?;
+        eprintln!("Encoded {} words into {}", dict.size(), output.display());
+        return Ok(());
+    }
+
+    if let Command::Ocr(command) = &cli.command {
+        return run_ocr(command);
+    }
+
+    let dict = match &cli.dict {
+        Some(path) => WordsDict::open(path)?,
+        None => WordsDict::load(),
+    };
+    let read_list = |path: &Option<PathBuf>| match path {
+        Some(path) => fs::read_to_string(path),
+        None => Ok(String::new()),
+    };
+    let dict = WordsOverlay::new(&read_list(&cli.allow)?, &read_list(&cli.block)?).apply(&dict);
+    if dict.size() == 0 {
+        bail!("dictionary has no words");
+    }
+    let shortcuts = WordsShortcuts::new(&dict);
+
+    match cli.command {
+        Command::SpellingBee(SpellingBeeCommand::Simple { letters }) => {
+            let game = SpellingBeeSimpleParams::new(&normalize_letters(&letters))?;
+            output::print_solution(cli.format, &game.solve(&dict, &shortcuts))
+        }
+        Command::SpellingBee(SpellingBeeCommand::Hinted {
+            letters,
+            matrix,
+            list,
+        }) => {
+            if matrix.as_deref().is_some_and(is_stdin) && list.as_deref().is_some_and(is_stdin) {
+                bail!("only one of the hints can be read from stdin");
+            }
+            let matrix = matrix.as_deref().map(read_input).transpose()?;
+            let list = list.as_deref().map(read_input).transpose()?;
+            let game = SpellingBeeHintedParams::from_hints(
+                &normalize_letters(&letters),
+                matrix.as_deref(),
+                list.as_deref(),
+            )?;
+            output::print_solution(cli.format, &game.solve(&dict, &shortcuts))
+        }
+        Command::Search { prefix } => {
+            let prefix = prefix.to_lowercase();
+            let words = match shortcuts.search_range(prefix.as_bytes()) {
+                Some(range) => dict
+                    .search_range(range, prefix.as_bytes())
+                    .map(|word| String::from_utf8_lossy(word).to_string())
+                    .collect(),
+                None => vec![],
+            };
+            output::print_words(cli.format, &words)
+        }
+        Command::Tui => tui::run(&dict, &shortcuts),
+        Command::Dict(DictCommand::Definitions { wordnet, output }) => {
+            let words: HashSet<&[u8]> = dict.iter().collect();
+            let mut definitions = vec![];
+            for name in ["data.noun", "data.verb", "data.adj", "data.adv"] {
+                let text = fs::read_to_string(wordnet.join(name))?;
+                definitions.extend(
+                    definitions::parse_wordnet_data(&text)
+                        .into_iter()
+                        .filter(|(word, _)| words.contains(word.as_bytes())),
+                );
+            }
+            definitions.sort_by(|a, b| a.0.cmp(&b.0));
+            fs::write(&output, definitions::to_tsv(&definitions))?;
+            eprintln!(
+                "Wrote {} definitions into {}",
+                definitions.len(),
+                output.display()
+            );
+            Ok(())
+        }
+        Command::Dict(DictCommand::Build { .. }) => {
+            unreachable!("dictionary is built before loading the dictionary")
+        }
+        Command::Ocr(_) => unreachable!("screenshots are read before loading the dictionary"),
+    }
+}
+
+fn run_ocr(command: &OcrCommand) -> Result<()> {
+    match command {
+        OcrCommand::Read { image, glyphs } => {
+            let reader = match glyphs {
+                Some(path) => HiveReader::new(Glyphs::from_text(&fs::read_to_string(path)?)?),
+                None => HiveReader::load(),
+            };
+            println!("{}", reader.read(&fs::read(image)?)?.letters());
+        }
+        OcrCommand::Train { output, images } => {
+            let mut glyphs = match fs::read_to_string(output) {
+                Ok(text) => Glyphs::from_text(&text)?,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Glyphs::default(),
+                Err(err) => return Err(err.into()),
+            };
+            for path in images {
+                let letters = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .map(normalize_letters)
+                    .unwrap_or_default();
+                let tiles = ocr::hive::read_glyphs(&ocr::decode(&fs::read(path)?)?)?;
+                if letters.len() != tiles.len() {
+                    bail!(
+                        "{} must be named after its {} letters",
+                        path.display(),
+                        tiles.len()
+                    );
+                }
+                for (letter, glyph) in letters.chars().zip(tiles) {
+                    glyphs.train(letter, glyph);
+                }
+            }
+            let header =
+                "# Letter templates of the hive reader, written by `word_puzzles ocr train`.\n";
+            fs::write(output, format!("{}{}", header, glyphs.to_text()))?;
+            eprintln!("Wrote {} templates into {}", glyphs.len(), output.display());
+        }
+    }
+    Ok(())
+}
+
+fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+fn read_input(path: &Path) -> Result<String> {
+    if is_stdin(path) {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        Ok(fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn arguments_are_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn parses_global_options_after_the_command() {
+        let cli = Cli::try_parse_from([
+            "word_puzzles",
+            "spelling-bee",
+            "hinted",
+            "zwieslt",
+            "--list",
+            "-",
+            "--format",
+            "tsv",
+            "--dict",
+            "words.bin",
+        ])
+        .unwrap();
+        assert!(matches!(cli.format, Format::Tsv));
+        assert_eq!(cli.dict, Some(PathBuf::from("words.bin")));
+        let Command::SpellingBee(SpellingBeeCommand::Hinted {
+            letters,
+            matrix,
+            list,
+        }) = cli.command
+        else {
+            panic!("hinted solver expected");
+        };
+        assert_eq!(letters, "zwieslt");
+        assert_eq!(matrix, None);
+        assert!(list.as_deref().is_some_and(is_stdin));
+    }
+
+    #[test]
+    fn rejects_unknown_format_and_missing_letters() {
+        assert!(Cli::try_parse_from(["word_puzzles", "-f", "xml", "search", "ab"]).is_err());
+        assert!(Cli::try_parse_from(["word_puzzles", "spelling-bee", "simple"]).is_err());
+        let cli = Cli::try_parse_from(["word_puzzles", "search", "ab"]).unwrap();
+        assert!(matches!(cli.format, Format::Plain));
+        assert!(matches!(cli.command, Command::Search { prefix } if prefix == "ab"));
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::{self, Write};
+use words::spelling_bee::SolvedWord;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// One word per line.
+    Plain,
+    Json,
+    /// Tab separated values with a header row.
+    Tsv,
+}
+
+#[derive(Serialize)]
+struct JsonSolvedWord<'a> {
+    word: &'a str,
+    score: usize,
+    pangram: bool,
+}
+
+#[derive(Serialize)]
+struct JsonSolution<'a> {
+    words: Vec<JsonSolvedWord<'a>>,
+    pangrams: Vec<&'a str>,
+    total_score: usize,
+}
+
+pub fn print_words(format: Format, words: &[String]) -> Result<()> {
+    write_words(&mut io::stdout().lock(), format, words)
+}
+
+pub fn print_solution(format: Format, solution: &[SolvedWord]) -> Result<()> {
+    write_solution(&mut io::stdout().lock(), format, solution)
+}
+
+fn write_words(mut out: impl Write, format: Format, words: &[String]) -> Result<()> {
+    match format {
+        Format::Plain => {
+            for word in words {
+                writeln!(out, "{}", word)?;
+            }
+        }
+        Format::Json => {
+            serde_json::to_writer(&mut out, words)?;
+            writeln!(out)?;
+        }
+        Format::Tsv => {
+            writeln!(out, "word")?;
+            for word in words {
+                writeln!(out, "{}", word)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_solution(mut out: impl Write, format: Format, solution: &[SolvedWord]) -> Result<()> {
+    match format {
+        Format::Plain => {
+            for solved in solution {
+                writeln!(out, "{}", solved.word)?;
+            }
+        }
+        Format::Json => {
+            let json = JsonSolution {
+                words: solution
+                    .iter()
+                    .map(|solved| JsonSolvedWord {
+                        word: &solved.word,
+                        score: solved.score,
+                        pangram: solved.pangram,
+                    })
+                    .collect(),
+                pangrams: solution
+                    .iter()
+                    .filter(|solved| solved.pangram)
+                    .map(|solved| solved.word.as_str())
+                    .collect(),
+                total_score: solution.iter().map(|solved| solved.score).sum(),
+            };
+            serde_json::to_writer(&mut out, &json)?;
+            writeln!(out)?;
+        }
+        Format::Tsv => {
+            writeln!(out, "word\tscore\tpangram")?;
+            for solved in solution {
+                writeln!(out, "{}\t{}\t{}", solved.word, solved.score, solved.pangram)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved(word: &str, score: usize, pangram: bool) -> SolvedWord {
+        SolvedWord {
+            word: word.to_owned(),
+            score,
+            pangram,
+        }
+    }
+
+    fn solution(format: Format) -> String {
+        let solution = [solved("zest", 1, false), solved("swizzlet", 15, true)];
+        let mut out = vec![];
+        write_solution(&mut out, format, &solution).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn words(format: Format) -> String {
+        let mut out = vec![];
+        write_words(&mut out, format, &["zest".to_owned(), "zesty".to_owned()]).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn solution_formats() {
+        assert_eq!(solution(Format::Plain), "zest\nswizzlet\n");
+        assert_eq!(
+            solution(Format::Tsv),
+            "word\tscore\tpangram\nzest\t1\tfalse\nswizzlet\t15\ttrue\n"
+        );
+        let json: serde_json::Value = serde_json::from_str(&solution(Format::Json)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "words": [
+                    {"word": "zest", "score": 1, "pangram": false},
+                    {"word": "swizzlet", "score": 15, "pangram": true},
+                ],
+                "pangrams": ["swizzlet"],
+                "total_score": 16,
+            })
+        );
+    }
+
+    #[test]
+    fn words_formats() {
+        assert_eq!(words(Format::Plain), "zest\nzesty\n");
+        assert_eq!(words(Format::Tsv), "word\nzest\nzesty\n");
+        assert_eq!(words(Format::Json), "[\"zest\",\"zesty\"]\n");
+    }
+}
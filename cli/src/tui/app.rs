@@ -0,0 +1,290 @@
+use std::collections::HashSet;
+
+use words::{
+    spelling_bee::{
+        normalize_letters, SolvedWord, SpellingBee, SpellingBeeHintedParams,
+        SpellingBeeSimpleParams,
+    },
+    WordsDict, WordsShortcuts,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Simple,
+    Hinted,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Letters,
+    Matrix,
+    List,
+    Results,
+}
+
+pub struct App<'a> {
+    dict: &'a WordsDict,
+    shortcuts: &'a WordsShortcuts,
+    pub mode: Mode,
+    pub focus: Focus,
+    pub letters: String,
+    pub letter_matrix: String,
+    pub letter_list: String,
+    pub solution: Vec<SolvedWord>,
+    pub error: Option<String>,
+    pub found: HashSet<String>,
+    pub selected: usize,
+    pub should_quit: bool,
+    /// Letters of the puzzle `found` belongs to.
+    found_letters: String,
+}
+
+impl<'a> App<'a> {
+    pub fn new(dict: &'a WordsDict, shortcuts: &'a WordsShortcuts) -> Self {
+        Self {
+            dict,
+            shortcuts,
+            mode: Mode::Simple,
+            focus: Focus::Letters,
+            letters: String::new(),
+            letter_matrix: String::new(),
+            letter_list: String::new(),
+            solution: vec![],
+            error: None,
+            found: HashSet::new(),
+            selected: 0,
+            should_quit: false,
+            found_letters: String::new(),
+        }
+    }
+
+    /// Reruns the solver for the current inputs, same as the web form does on every keystroke.
+    /// Words found so far are kept while only the hints change.
+    pub fn solve(&mut self) {
+        let letters = normalize_letters(&self.letters);
+        if letters != self.found_letters {
+            self.found.clear();
+            self.found_letters.clone_from(&letters);
+        }
+        if letters.is_empty() {
+            self.solution = vec![];
+            self.error = None;
+            return;
+        }
+        let solution = match self.mode {
+            Mode::Simple => SpellingBeeSimpleParams::new(&letters)
+                .map(|game| game.solve(self.dict, self.shortcuts)),
+            Mode::Hinted => SpellingBeeHintedParams::from_hints(
+                &letters,
+                Some(&self.letter_matrix),
+                Some(&self.letter_list),
+            )
+            .map(|game| game.solve(self.dict, self.shortcuts)),
+        };
+        match solution {
+            Ok(solution) => {
+                self.solution = solution;
+                self.error = None;
+            }
+            Err(err) => {
+                self.solution = vec![];
+                self.error = Some(err.to_string());
+            }
+        }
+        self.selected = self.selected.min(self.solution.len().saturating_sub(1));
+    }
+
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            Mode::Simple => Mode::Hinted,
+            Mode::Hinted => Mode::Simple,
+        };
+        if !self.focus_order().contains(&self.focus) {
+            self.focus = Focus::Letters;
+        }
+        self.solve();
+    }
+
+    pub fn next_focus(&mut self) {
+        let order = self.focus_order();
+        let i = order.iter().position(|f| *f == self.focus).unwrap_or(0);
+        self.focus = order[(i + 1) % order.len()];
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.push_str(c.encode_utf8(&mut [0; 4]));
+    }
+
+    pub fn push_str(&mut self, text: &str) {
+        let letters_only = self.focus == Focus::Letters;
+        let Some(input) = self.focused_input() else {
+            return;
+        };
+        input.extend(
+            text.chars()
+                .filter(|c| !letters_only || c.is_ascii_alphabetic()),
+        );
+        self.solve();
+    }
+
+    pub fn pop_char(&mut self) {
+        let Some(input) = self.focused_input() else {
+            return;
+        };
+        input.pop();
+        self.solve();
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.solution.len() {
+            self.selected += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Marks selected word as found or returns it back to the list of remaining ones.
+    pub fn toggle_found(&mut self) {
+        let Some(solved) = self.solution.get(self.selected) else {
+            return;
+        };
+        if !self.found.remove(&solved.word) {
+            self.found.insert(solved.word.clone());
+        }
+    }
+
+    pub fn is_found(&self, word: &str) -> bool {
+        self.found.contains(word)
+    }
+
+    /// Found words of the current solution, hints may have left some of them out.
+    pub fn found_count(&self) -> usize {
+        self.solution
+            .iter()
+            .filter(|solved| self.is_found(&solved.word))
+            .count()
+    }
+
+    pub fn found_score(&self) -> usize {
+        self.solution
+            .iter()
+            .filter(|solved| self.is_found(&solved.word))
+            .map(|solved| solved.score)
+            .sum()
+    }
+
+    pub fn total_score(&self) -> usize {
+        self.solution.iter().map(|solved| solved.score).sum()
+    }
+
+    fn focused_input(&mut self) -> Option<&mut String> {
+        match self.focus {
+            Focus::Letters => Some(&mut self.letters),
+            Focus::Matrix => Some(&mut self.letter_matrix),
+            Focus::List => Some(&mut self.letter_list),
+            Focus::Results => None,
+        }
+    }
+
+    fn focus_order(&self) -> &'static [Focus] {
+        match self.mode {
+            Mode::Simple => &[Focus::Letters, Focus::Results],
+            Mode::Hinted => &[Focus::Letters, Focus::Matrix, Focus::List, Focus::Results],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_app(test: impl FnOnce(&mut App)) {
+        let dict = WordsDict::from_text(b"zest\nzeal\nwise\nzeste\nswizzle\nswizzlet\n");
+        let shortcuts = WordsShortcuts::new(&dict);
+        let mut app = App::new(&dict, &shortcuts);
+        test(&mut app);
+    }
+
+    fn words<'a>(app: &'a App) -> Vec<&'a str> {
+        app.solution
+            .iter()
+            .map(|solved| solved.word.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn typing_letters_solves() {
+        with_app(|app| {
+            app.push_str("zwie sl1t");
+            assert_eq!(app.letters, "zwieslt");
+            assert_eq!(words(app), ["swizzle", "swizzlet", "zest", "zeste"]);
+            assert_eq!(app.error, None);
+            app.pop_char();
+            assert!(app.solution.is_empty());
+            assert!(app.error.is_some());
+        });
+    }
+
+    #[test]
+    fn found_words_are_toggled_and_counted() {
+        with_app(|app| {
+            app.push_str("zwieslt");
+            app.toggle_found();
+            app.select_next();
+            app.toggle_found();
+            assert_eq!((app.found_count(), app.found_score()), (2, 22));
+            app.toggle_found();
+            assert_eq!((app.found_count(), app.found_score()), (1, 7));
+            assert!(app.is_found("swizzle"));
+
+            // Hints narrow the solution, words found out of it are not counted
+            app.toggle_mode();
+            app.focus = Focus::List;
+            app.push_str("ze-2");
+            assert_eq!(words(app), ["zest", "zeste"]);
+            assert_eq!(app.found_count(), 0);
+            app.letter_list.clear();
+            app.solve();
+            assert_eq!(app.found_count(), 1);
+
+            // Another puzzle starts afresh
+            app.focus = Focus::Letters;
+            app.pop_char();
+            app.push_char('l');
+            assert!(app.found.is_empty());
+            assert_eq!(app.found_count(), 0);
+        });
+    }
+
+    #[test]
+    fn focus_cycles_through_the_inputs_of_the_mode() {
+        with_app(|app| {
+            app.next_focus();
+            assert_eq!(app.focus, Focus::Results);
+            app.next_focus();
+            assert_eq!(app.focus, Focus::Letters);
+
+            app.toggle_mode();
+            assert_eq!(app.mode, Mode::Hinted);
+            let mut order = vec![];
+            for _ in 0..4 {
+                app.next_focus();
+                order.push(app.focus);
+            }
+            assert_eq!(
+                order,
+                [Focus::Matrix, Focus::List, Focus::Results, Focus::Letters]
+            );
+
+            app.next_focus();
+            app.toggle_mode();
+            assert_eq!(app.focus, Focus::Letters);
+            app.push_char('q');
+            app.focus = Focus::Results;
+            app.push_char('q');
+            assert_eq!(app.letters, "q");
+        });
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use ratatui::{
+    crossterm::{
+        event::{
+            self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEventKind,
+            KeyModifiers,
+        },
+        execute,
+    },
+    DefaultTerminal,
+};
+use std::io::stdout;
+use words::{WordsDict, WordsShortcuts};
+
+use app::{App, Focus};
+
+mod app;
+mod ui;
+
+pub fn run(dict: &WordsDict, shortcuts: &WordsShortcuts) -> Result<()> {
+    let mut terminal = ratatui::init();
+    execute!(stdout(), EnableBracketedPaste)?;
+    let result = run_app(&mut terminal, App::new(dict, shortcuts));
+    execute!(stdout(), DisableBracketedPaste)?;
+    ratatui::restore();
+    result
+}
+
+fn run_app(terminal: &mut DefaultTerminal, mut app: App) -> Result<()> {
+    while !app.should_quit {
+        terminal.draw(|frame| ui::draw(frame, &app))?;
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                match key.code {
+                    KeyCode::Esc => app.should_quit = true,
+                    KeyCode::Char('c') if ctrl => app.should_quit = true,
+                    KeyCode::Char('t') if ctrl => app.toggle_mode(),
+                    KeyCode::Tab => app.next_focus(),
+                    KeyCode::Up if app.focus == Focus::Results => app.select_previous(),
+                    KeyCode::Down if app.focus == Focus::Results => app.select_next(),
+                    KeyCode::Char(' ') | KeyCode::Enter if app.focus == Focus::Results => {
+                        app.toggle_found()
+                    }
+                    KeyCode::Enter => app.push_char('\n'),
+                    KeyCode::Backspace => app.pop_char(),
+                    KeyCode::Char(c) => app.push_char(c),
+                    _ => {}
+                }
+            }
+            Event::Paste(text) => app.push_str(&text),
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::app::{App, Focus, Mode};
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [inputs, results] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(body);
+
+    let mode = match app.mode {
+        Mode::Simple => "Only letters",
+        Mode::Hinted => "Advanced hints",
+    };
+    frame.render_widget(
+        Line::from(vec![
+            Span::from("Spelling bee").bold(),
+            Span::from(format!(" | {}", mode)),
+        ]),
+        header,
+    );
+    frame.render_widget(
+        Line::from("Tab: next field  Ctrl+T: switch mode  Space: mark found  Esc: quit").dim(),
+        footer,
+    );
+
+    match app.mode {
+        Mode::Simple => {
+            let [letters, error] =
+                Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(inputs);
+            draw_input(frame, app, Focus::Letters, "Letters", &app.letters, letters);
+            draw_error(frame, app, error);
+        }
+        Mode::Hinted => {
+            let [letters, error, matrix, list] = Layout::vertical([
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Percentage(60),
+                Constraint::Min(0),
+            ])
+            .areas(inputs);
+            draw_input(frame, app, Focus::Letters, "Letters", &app.letters, letters);
+            draw_error(frame, app, error);
+            draw_input(
+                frame,
+                app,
+                Focus::Matrix,
+                "Start matrix",
+                &app.letter_matrix,
+                matrix,
+            );
+            draw_input(
+                frame,
+                app,
+                Focus::List,
+                "Two letter list",
+                &app.letter_list,
+                list,
+            );
+        }
+    }
+
+    draw_results(frame, app, results);
+}
+
+fn block(app: &App, focus: Focus, title: String) -> Block<'static> {
+    let block = Block::bordered().title(title);
+    if app.focus == focus {
+        block.border_style(Style::new().fg(Color::Yellow))
+    } else {
+        block
+    }
+}
+
+fn draw_input(frame: &mut Frame, app: &App, focus: Focus, title: &str, value: &str, area: Rect) {
+    let paragraph = Paragraph::new(value.to_owned()).block(block(app, focus, title.to_owned()));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_error(frame: &mut Frame, app: &App, area: Rect) {
+    if let Some(error) = &app.error {
+        frame.render_widget(Paragraph::new(error.as_str()).red(), area);
+    }
+}
+
+fn draw_results(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .solution
+        .iter()
+        .map(|solved| {
+            let found = app.is_found(&solved.word);
+            let mut style = Style::new();
+            if found {
+                style = style.add_modifier(Modifier::CROSSED_OUT).dim();
+            }
+            if solved.pangram {
+                style = style.fg(Color::Yellow);
+            }
+            ListItem::new(Line::from(vec![
+                Span::from(if found { "[x] " } else { "[ ] " }),
+                Span::styled(solved.word.clone(), style),
+                Span::from(format!(" {}", solved.score)).dim(),
+            ]))
+        })
+        .collect();
+    let title = format!(
+        "Words {}/{} | Score {}/{}",
+        app.found_count(),
+        app.solution.len(),
+        app.found_score(),
+        app.total_score()
+    );
+    let list = List::new(items)
+        .block(block(app, Focus::Results, title))
+        .highlight_style(Style::new().reversed());
+    let mut state = ListState::default();
+    if app.focus == Focus::Results && !app.solution.is_empty() {
+        state.select(Some(app.selected));
+    }
+    frame.render_stateful_widget(list, area, &mut state);
+}
@@ -0,0 +1,120 @@
+use anyhow::{bail, Context, Result};
+use std::fmt::Write;
+
+/// Glyphs are compared on a square grid of this many cells per side.
+pub const GLYPH_SIZE: usize = 16;
+/// Coverage of a cell is kept in this many levels, each is a character of the templates file.
+const LEVELS: &[u8] = b" .:-=+*o%@";
+/// Letters of different proportions, like `I` and `L`, differ more than their grids show.
+const ASPECT_WEIGHT: f32 = 24.0;
+
+/// Templates bundled with the crate, written by `word_puzzles ocr train`.
+pub const TEMPLATES: &str = include_str!("../data/glyphs.txt");
+
+/// Letter shape scaled into the grid with its center kept, cells hold the ink coverage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyph {
+    /// Width over height of the letter.
+    pub aspect: f32,
+    pub cells: [f32; GLYPH_SIZE * GLYPH_SIZE],
+}
+
+impl Glyph {
+    fn distance(&self, other: &Glyph) -> f32 {
+        let cells: f32 = self
+            .cells
+            .iter()
+            .zip(other.cells.iter())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum();
+        cells + ASPECT_WEIGHT * (self.aspect / other.aspect).ln().powi(2)
+    }
+}
+
+/// Nearest template classifier, a letter may have several templates.
+#[derive(Debug, Clone, Default)]
+pub struct Glyphs {
+    templates: Vec<(char, Glyph)>,
+}
+
+impl Glyphs {
+    pub fn load() -> Self {
+        Self::from_text(TEMPLATES).expect("bundled glyph templates are valid")
+    }
+
+    /// Each template is a `letter aspect` line followed by a line of cells per row,
+    /// lines starting with `#` are comments.
+    pub fn from_text(text: &str) -> Result<Self> {
+        let mut lines = text.lines().filter(|line| !line.starts_with('#'));
+        let mut templates = vec![];
+        while let Some(line) = lines.next() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (letter, aspect) = line
+                .split_once(' ')
+                .with_context(|| format!("invalid glyph template header {:?}", line))?;
+            let letter = match letter.as_bytes() {
+                [letter] if letter.is_ascii_lowercase() => *letter as char,
+                _ => bail!("invalid glyph template letter {:?}", letter),
+            };
+            let mut cells = [0.0; GLYPH_SIZE * GLYPH_SIZE];
+            for y in 0..GLYPH_SIZE {
+                let row = lines
+                    .next()
+                    .with_context(|| format!("truncated glyph template {}", letter))?;
+                for (x, level) in row.bytes().take(GLYPH_SIZE).enumerate() {
+                    let level = LEVELS.iter().position(|&l| l == level).unwrap_or(0);
+                    cells[y * GLYPH_SIZE + x] = level as f32 / (LEVELS.len() - 1) as f32;
+                }
+            }
+            templates.push((
+                letter,
+                Glyph {
+                    aspect: aspect.trim().parse()?,
+                    cells,
+                },
+            ));
+        }
+        Ok(Self { templates })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (letter, glyph) in &self.templates {
+            let _ = writeln!(text, "{} {:.3}", letter, glyph.aspect);
+            for row in glyph.cells.chunks(GLYPH_SIZE) {
+                let row: String = row
+                    .iter()
+                    .map(|cell| {
+                        let level = (cell * (LEVELS.len() - 1) as f32).round() as usize;
+                        LEVELS[level.min(LEVELS.len() - 1)] as char
+                    })
+                    .collect();
+                let _ = writeln!(text, "{}", row.trim_end());
+            }
+        }
+        text
+    }
+
+    pub fn train(&mut self, letter: char, glyph: Glyph) {
+        self.templates.push((letter.to_ascii_lowercase(), glyph));
+    }
+
+    /// Letter of the nearest template, `None` without templates.
+    pub fn classify(&self, glyph: &Glyph) -> Option<char> {
+        self.templates
+            .iter()
+            .map(|(letter, template)| (letter, template.distance(glyph)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(letter, _)| *letter)
+    }
+
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+}
@@ -0,0 +1,259 @@
+use anyhow::{bail, Result};
+use std::{collections::HashMap, f32::consts::TAU};
+
+use crate::{
+    glyphs::{Glyph, GLYPH_SIZE},
+    image::{luma, RgbImage},
+};
+
+/// Pixels differing from the background by more than this, summed over channels, are drawn.
+const BACKGROUND_DISTANCE: u32 = 40;
+/// Smallest tile worth reading, in pixels.
+const MIN_TILE_AREA: usize = 150;
+/// Hexagons cover three quarters of their bounding box.
+const TILE_FILL: std::ops::RangeInclusive<f32> = 0.6..=0.9;
+const TILE_ASPECT: std::ops::RangeInclusive<f32> = 0.7..=1.5;
+/// Distance between the centers of neighbour tiles over the tile size.
+const NEIGHBOUR_DISTANCE: std::ops::RangeInclusive<f32> = 0.75..=1.45;
+/// Letters are read inside this fraction of the inscribed circle, away from antialiased edges.
+const LETTER_RADIUS: f32 = 0.8;
+
+#[derive(Debug, Clone)]
+struct Region {
+    label: u32,
+    area: usize,
+    min: (usize, usize),
+    max: (usize, usize),
+    sum: (f64, f64),
+}
+
+impl Region {
+    fn width(&self) -> f32 {
+        (self.max.0 - self.min.0 + 1) as f32
+    }
+
+    fn height(&self) -> f32 {
+        (self.max.1 - self.min.1 + 1) as f32
+    }
+
+    fn center(&self) -> (f32, f32) {
+        (
+            (self.sum.0 / self.area as f64) as f32,
+            (self.sum.1 / self.area as f64) as f32,
+        )
+    }
+
+    fn is_tile(&self) -> bool {
+        let fill = self.area as f32 / (self.width() * self.height());
+        self.area >= MIN_TILE_AREA
+            && TILE_FILL.contains(&fill)
+            && TILE_ASPECT.contains(&(self.width() / self.height()))
+    }
+
+    fn is_neighbour(&self, other: &Region) -> bool {
+        let (x, y) = self.center();
+        let (other_x, other_y) = other.center();
+        let distance = (x - other_x).hypot(y - other_y) / self.width().max(self.height());
+        let area = other.area as f32 / self.area as f32;
+        NEIGHBOUR_DISTANCE.contains(&distance) && (0.6..=1.6).contains(&area)
+    }
+}
+
+/// Finds the seven tiles of the hive and the letter drawn on each one.
+/// The center tile comes first, then the others clockwise from the top.
+pub fn read_glyphs(image: &RgbImage) -> Result<Vec<Glyph>> {
+    let (labels, regions) = label_regions(image);
+    let tiles: Vec<&Region> = regions.iter().filter(|r| r.is_tile()).collect();
+    let center = tiles
+        .iter()
+        .map(|tile| {
+            let neighbours: Vec<&Region> = tiles
+                .iter()
+                .filter(|other| other.label != tile.label && tile.is_neighbour(other))
+                .copied()
+                .collect();
+            (*tile, neighbours)
+        })
+        .filter(|(_, neighbours)| neighbours.len() >= 6)
+        .max_by_key(|(tile, neighbours)| (neighbours.len() == 6, tile.area));
+    let Some((center, mut outer)) = center else {
+        bail!("no hive of seven tiles found in the image");
+    };
+    let (center_x, center_y) = center.center();
+    let distance = |tile: &Region| {
+        let (x, y) = tile.center();
+        (x - center_x).hypot(y - center_y)
+    };
+    outer.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+    outer.truncate(6);
+    // Clockwise angle from the top, y grows downwards. Turned by half a tile so the top
+    // one comes first even when it leans a little to the left.
+    let angle = |tile: &Region| {
+        let (x, y) = tile.center();
+        ((x - center_x).atan2(center_y - y) + TAU / 12.0).rem_euclid(TAU)
+    };
+    outer.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+    std::iter::once(center)
+        .chain(outer)
+        .map(|tile| tile_glyph(image, &labels, tile))
+        .collect()
+}
+
+/// Connected regions of pixels that differ from the background, which is the most common
+/// color of the image border.
+fn label_regions(image: &RgbImage) -> (Vec<u32>, Vec<Region>) {
+    let (width, height) = (image.width, image.height);
+    let background = border_color(image);
+    let drawn: Vec<bool> = image
+        .pixels
+        .iter()
+        .map(|pixel| {
+            let distance: u32 = pixel
+                .iter()
+                .zip(background)
+                .map(|(&a, b)| a.abs_diff(b) as u32)
+                .sum();
+            distance > BACKGROUND_DISTANCE
+        })
+        .collect();
+    let mut labels = vec![0u32; width * height];
+    let mut regions = vec![];
+    let mut stack = vec![];
+    for start in 0..width * height {
+        if !drawn[start] || labels[start] != 0 {
+            continue;
+        }
+        let label = regions.len() as u32 + 1;
+        let mut region = Region {
+            label,
+            area: 0,
+            min: (usize::MAX, usize::MAX),
+            max: (0, 0),
+            sum: (0.0, 0.0),
+        };
+        labels[start] = label;
+        stack.push(start);
+        while let Some(index) = stack.pop() {
+            let (x, y) = (index % width, index / width);
+            region.area += 1;
+            region.min = (region.min.0.min(x), region.min.1.min(y));
+            region.max = (region.max.0.max(x), region.max.1.max(y));
+            region.sum = (region.sum.0 + x as f64, region.sum.1 + y as f64);
+            let neighbours = [
+                (x > 0).then(|| index - 1),
+                (x + 1 < width).then(|| index + 1),
+                (y > 0).then(|| index - width),
+                (y + 1 < height).then(|| index + width),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if drawn[next] && labels[next] == 0 {
+                    labels[next] = label;
+                    stack.push(next);
+                }
+            }
+        }
+        regions.push(region);
+    }
+    (labels, regions)
+}
+
+fn border_color(image: &RgbImage) -> [u8; 3] {
+    let (width, height) = (image.width, image.height);
+    let border = (0..width)
+        .flat_map(|x| [(x, 0), (x, height - 1)])
+        .chain((0..height).flat_map(|y| [(0, y), (width - 1, y)]));
+    let mut counts: HashMap<[u8; 3], usize> = HashMap::new();
+    for (x, y) in border {
+        *counts.entry(image.pixel(x, y)).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(color, count)| (*count, *color))
+        .map(|(color, _)| color)
+        .unwrap_or([255; 3])
+}
+
+/// Ink of the letter is whatever differs from the tile color, so dark and light themes
+/// read the same.
+fn tile_glyph(image: &RgbImage, labels: &[u32], tile: &Region) -> Result<Glyph> {
+    let (center_x, center_y) = tile.center();
+    let radius = LETTER_RADIUS * tile.width().min(tile.height()) / 2.0;
+    let mut inside = vec![];
+    for y in tile.min.1..=tile.max.1 {
+        for x in tile.min.0..=tile.max.0 {
+            let is_inside = labels[y * image.width + x] == tile.label
+                && (x as f32 - center_x).hypot(y as f32 - center_y) <= radius;
+            if is_inside {
+                inside.push((x, y, luma(image.pixel(x, y))));
+            }
+        }
+    }
+    // Tile color is the most common brightness, the letter covers less than half of the tile
+    let mut histogram = [0usize; 256];
+    for &(_, _, value) in &inside {
+        histogram[value as usize] += 1;
+    }
+    let fill = (0..256).max_by_key(|&v| histogram[v]).unwrap_or_default() as f32;
+    let contrast = inside
+        .iter()
+        .map(|&(_, _, value)| (value - fill).abs())
+        .fold(0.0, f32::max);
+    if contrast < 64.0 {
+        bail!("a hive tile has no letter");
+    }
+    let ink = |value: f32| ((value - fill).abs() / contrast).clamp(0.0, 1.0);
+    let (mut min, mut max) = ((usize::MAX, usize::MAX), (0, 0));
+    let mut coverage: HashMap<(usize, usize), f32> = HashMap::new();
+    for &(x, y, value) in &inside {
+        let ink = ink(value);
+        if ink > 0.5 {
+            min = (min.0.min(x), min.1.min(y));
+            max = (max.0.max(x), max.1.max(y));
+        }
+        coverage.insert((x, y), ink);
+    }
+    let (width, height) = ((max.0 - min.0 + 1) as f32, (max.1 - min.1 + 1) as f32);
+    Ok(Glyph {
+        aspect: width / height,
+        cells: grid_cells(&coverage, min, width, height),
+    })
+}
+
+/// Samples the letter box, made square around its center, on the glyph grid.
+fn grid_cells(
+    coverage: &HashMap<(usize, usize), f32>,
+    min: (usize, usize),
+    width: f32,
+    height: f32,
+) -> [f32; GLYPH_SIZE * GLYPH_SIZE] {
+    const SAMPLES: usize = 4;
+    let side = width.max(height);
+    let origin = (
+        min.0 as f32 + (width - side) / 2.0,
+        min.1 as f32 + (height - side) / 2.0,
+    );
+    let at = |x: f32, y: f32| {
+        if x < 0.0 || y < 0.0 {
+            return 0.0;
+        }
+        coverage
+            .get(&(x as usize, y as usize))
+            .copied()
+            .unwrap_or_default()
+    };
+    let mut cells = [0.0; GLYPH_SIZE * GLYPH_SIZE];
+    for (index, cell) in cells.iter_mut().enumerate() {
+        let (cell_x, cell_y) = ((index % GLYPH_SIZE) as f32, (index / GLYPH_SIZE) as f32);
+        let mut sum = 0.0;
+        for sample in 0..SAMPLES * SAMPLES {
+            let offset_x = ((sample % SAMPLES) as f32 + 0.5) / SAMPLES as f32;
+            let offset_y = ((sample / SAMPLES) as f32 + 0.5) / SAMPLES as f32;
+            sum += at(
+                origin.0 + (cell_x + offset_x) * side / GLYPH_SIZE as f32,
+                origin.1 + (cell_y + offset_y) * side / GLYPH_SIZE as f32,
+            );
+        }
+        *cell = sum / (SAMPLES * SAMPLES) as f32;
+    }
+    cells
+}
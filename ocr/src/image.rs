@@ -0,0 +1,55 @@
+use anyhow::{bail, Result};
+
+use crate::{jpeg, png};
+
+/// Images larger than this are refused before decoding, screenshots are far smaller.
+pub const MAX_PIXELS: usize = 40_000_000;
+
+/// Decoded image with 8 bit RGB pixels in rows from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl RgbImage {
+    pub fn new(width: usize, height: usize, pixels: Vec<[u8; 3]>) -> Self {
+        debug_assert_eq!(pixels.len(), width * height);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+}
+
+/// Perceived brightness, 0 to 255.
+pub fn luma([r, g, b]: [u8; 3]) -> f32 {
+    0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32
+}
+
+/// Decodes a PNG or JPEG, the format is recognized from the content.
+pub fn decode(bytes: &[u8]) -> Result<RgbImage> {
+    if bytes.starts_with(png::SIGNATURE) {
+        png::decode(bytes)
+    } else if bytes.starts_with(&[0xff, 0xd8]) {
+        jpeg::decode(bytes)
+    } else {
+        bail!("image is neither a PNG nor a JPEG")
+    }
+}
+
+pub(crate) fn check_size(width: usize, height: usize) -> Result<()> {
+    if width == 0 || height == 0 {
+        bail!("image is empty");
+    }
+    if width.saturating_mul(height) > MAX_PIXELS {
+        bail!("image is too large, {}x{} pixels", width, height);
+    }
+    Ok(())
+}
@@ -0,0 +1,529 @@
+use anyhow::{bail, Context, Result};
+
+use crate::image::{check_size, RgbImage};
+
+/// Position in a block of each coefficient in the order they are stored.
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20,
+    13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59,
+    52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// Coefficients of 12 bit JPEGs take up to 15 bits, a larger size is a corrupt table.
+const MAX_COEFFICIENT_BITS: u8 = 15;
+
+#[derive(Debug, Clone, Default)]
+struct Huffman {
+    /// Codes of each length are consecutive, these are the first code and symbol index per length.
+    first_code: [i32; 17],
+    first_index: [usize; 17],
+    counts: [u16; 17],
+    symbols: Vec<u8>,
+}
+
+impl Huffman {
+    fn new(counts: &[u8], symbols: &[u8]) -> Self {
+        let mut table = Self {
+            symbols: symbols.to_vec(),
+            ..Default::default()
+        };
+        let (mut code, mut index) = (0, 0);
+        for length in 1..=16 {
+            table.counts[length] = counts[length - 1] as u16;
+            table.first_code[length] = code;
+            table.first_index[length] = index;
+            code = (code + counts[length - 1] as i32) << 1;
+            index += counts[length - 1] as usize;
+        }
+        table
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> Result<u8> {
+        let mut code = 0;
+        for length in 1..=16 {
+            code = (code << 1) | bits.bit() as i32;
+            let offset = code - self.first_code[length];
+            if offset >= 0 && offset < self.counts[length] as i32 {
+                return self
+                    .symbols
+                    .get(self.first_index[length] + offset as usize)
+                    .copied()
+                    .context("invalid JPEG huffman table");
+            }
+        }
+        bail!("invalid JPEG huffman code")
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Component {
+    id: u8,
+    h: usize,
+    v: usize,
+    quantization: usize,
+    dc_table: usize,
+    ac_table: usize,
+    /// Samples in blocks covering whole MCUs.
+    stride: usize,
+    plane: Vec<u8>,
+    dc_predictor: i32,
+}
+
+/// Entropy coded data reader, stops at markers and feeds zeros past them.
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bit(&mut self) -> u32 {
+        if self.count == 0 {
+            let byte = match self.data.get(self.position) {
+                Some(0xff) if self.data.get(self.position + 1) == Some(&0) => {
+                    self.position += 2;
+                    0xff
+                }
+                Some(0xff) | None => 0,
+                Some(&byte) => {
+                    self.position += 1;
+                    byte
+                }
+            };
+            self.buffer = byte as u32;
+            self.count = 8;
+        }
+        self.count -= 1;
+        (self.buffer >> self.count) & 1
+    }
+
+    fn bits(&mut self, count: u8) -> i32 {
+        (0..count).fold(0, |value, _| (value << 1) | self.bit() as i32)
+    }
+
+    /// Value of `count` bits with the sign encoding of coefficients.
+    fn signed(&mut self, count: u8) -> Result<i32> {
+        if count == 0 {
+            return Ok(0);
+        }
+        if count > MAX_COEFFICIENT_BITS {
+            bail!("invalid JPEG coefficient size {}", count);
+        }
+        let value = self.bits(count);
+        Ok(if value < 1 << (count - 1) {
+            value - (1 << count) + 1
+        } else {
+            value
+        })
+    }
+
+    /// Skips to the restart marker expected after `interval` MCUs.
+    fn restart(&mut self) {
+        self.count = 0;
+        while self.position + 1 < self.data.len() {
+            let is_restart = self.data[self.position] == 0xff
+                && (0xd0..=0xd7).contains(&self.data[self.position + 1]);
+            self.position += 1;
+            if is_restart {
+                self.position += 1;
+                return;
+            }
+        }
+    }
+}
+
+struct Decoder {
+    width: usize,
+    height: usize,
+    components: Vec<Component>,
+    quantization: [[u16; 64]; 4],
+    dc_tables: [Huffman; 4],
+    ac_tables: [Huffman; 4],
+    restart_interval: usize,
+    /// Adobe marker flag, `Some(0)` means the components are RGB rather than YCbCr.
+    adobe_transform: Option<u8>,
+    idct: [[f32; 8]; 8],
+}
+
+/// Decodes baseline and extended sequential JPEGs with one or three components.
+pub fn decode(bytes: &[u8]) -> Result<RgbImage> {
+    let mut decoder = Decoder::new();
+    let mut position = 2;
+    let mut frame_seen = false;
+    loop {
+        while bytes.get(position) == Some(&0xff) && bytes.get(position + 1) == Some(&0xff) {
+            position += 1;
+        }
+        let (Some(0xff), Some(&marker)) = (bytes.get(position), bytes.get(position + 1)) else {
+            bail!("truncated JPEG");
+        };
+        position += 2;
+        if marker == 0xd9 {
+            break;
+        }
+        let length = bytes
+            .get(position..position + 2)
+            .map(|l| u16::from_be_bytes([l[0], l[1]]) as usize)
+            .context("truncated JPEG segment")?;
+        let segment = bytes
+            .get(position + 2..position + length)
+            .context("truncated JPEG segment")?;
+        position += length;
+        match marker {
+            0xc0 | 0xc1 => {
+                decoder.read_frame(segment)?;
+                frame_seen = true;
+            }
+            0xc2 | 0xc6 | 0xca | 0xce => bail!("progressive JPEGs are not supported"),
+            0xc3 | 0xc5 | 0xc7 | 0xc9..=0xcb | 0xcd | 0xcf => {
+                bail!("unsupported JPEG encoding")
+            }
+            0xc4 => decoder.read_huffman(segment)?,
+            0xdb => decoder.read_quantization(segment)?,
+            0xdd => {
+                let interval = segment
+                    .get(0..2)
+                    .context("truncated JPEG restart interval")?;
+                decoder.restart_interval = u16::from_be_bytes([interval[0], interval[1]]) as usize;
+            }
+            0xee if segment.starts_with(b"Adobe") && segment.len() >= 12 => {
+                decoder.adobe_transform = Some(segment[11]);
+            }
+            0xda => {
+                if !frame_seen {
+                    bail!("JPEG scan before the frame header");
+                }
+                position += decoder.read_scan(segment, &bytes[position..])?;
+            }
+            _ => {}
+        }
+    }
+    if !frame_seen {
+        bail!("JPEG has no frame");
+    }
+    Ok(decoder.to_rgb())
+}
+
+impl Decoder {
+    fn new() -> Self {
+        let mut idct = [[0.0; 8]; 8];
+        for (u, row) in idct.iter_mut().enumerate() {
+            let scale = if u == 0 { 0.5f32.sqrt() } else { 1.0 };
+            for (x, value) in row.iter_mut().enumerate() {
+                let angle = (2 * x + 1) as f32 * u as f32 * std::f32::consts::PI / 16.0;
+                *value = scale * angle.cos() / 2.0;
+            }
+        }
+        Self {
+            width: 0,
+            height: 0,
+            components: vec![],
+            quantization: [[0; 64]; 4],
+            dc_tables: Default::default(),
+            ac_tables: Default::default(),
+            restart_interval: 0,
+            adobe_transform: None,
+            idct,
+        }
+    }
+
+    fn read_frame(&mut self, segment: &[u8]) -> Result<()> {
+        if segment.len() < 6 || segment[0] != 8 {
+            bail!("only 8 bit JPEGs are supported");
+        }
+        self.height = u16::from_be_bytes([segment[1], segment[2]]) as usize;
+        self.width = u16::from_be_bytes([segment[3], segment[4]]) as usize;
+        check_size(self.width, self.height)?;
+        let count = segment[5] as usize;
+        if count != 1 && count != 3 {
+            bail!("JPEGs with {} components are not supported", count);
+        }
+        let specs = segment
+            .get(6..6 + count * 3)
+            .context("truncated JPEG frame")?;
+        self.components = specs
+            .chunks_exact(3)
+            .map(|spec| Component {
+                id: spec[0],
+                h: (spec[1] >> 4).clamp(1, 4) as usize,
+                v: (spec[1] & 15).clamp(1, 4) as usize,
+                quantization: (spec[2] & 3) as usize,
+                dc_table: 0,
+                ac_table: 0,
+                stride: 0,
+                plane: vec![],
+                dc_predictor: 0,
+            })
+            .collect();
+        let (h_max, v_max) = self.max_sampling();
+        let (mcus_x, mcus_y) = (
+            self.width.div_ceil(8 * h_max),
+            self.height.div_ceil(8 * v_max),
+        );
+        for component in self.components.iter_mut() {
+            component.stride = mcus_x * component.h * 8;
+            component.plane = vec![0; component.stride * mcus_y * component.v * 8];
+        }
+        Ok(())
+    }
+
+    fn max_sampling(&self) -> (usize, usize) {
+        self.components
+            .iter()
+            .fold((1, 1), |(h, v), c| (h.max(c.h), v.max(c.v)))
+    }
+
+    fn read_huffman(&mut self, mut segment: &[u8]) -> Result<()> {
+        while segment.len() >= 17 {
+            let (class, index) = (segment[0] >> 4, (segment[0] & 3) as usize);
+            let counts = &segment[1..17];
+            let total: usize = counts.iter().map(|&c| c as usize).sum();
+            let symbols = segment
+                .get(17..17 + total)
+                .context("truncated JPEG huffman table")?;
+            let table = Huffman::new(counts, symbols);
+            if class == 0 {
+                self.dc_tables[index] = table;
+            } else {
+                self.ac_tables[index] = table;
+            }
+            segment = &segment[17 + total..];
+        }
+        Ok(())
+    }
+
+    fn read_quantization(&mut self, mut segment: &[u8]) -> Result<()> {
+        while !segment.is_empty() {
+            let (precision, index) = (segment[0] >> 4, (segment[0] & 3) as usize);
+            let size = if precision == 0 { 64 } else { 128 };
+            let values = segment
+                .get(1..1 + size)
+                .context("truncated JPEG quantization table")?;
+            for (k, &position) in ZIGZAG.iter().enumerate() {
+                self.quantization[index][position] = if precision == 0 {
+                    values[k] as u16
+                } else {
+                    u16::from_be_bytes([values[2 * k], values[2 * k + 1]])
+                };
+            }
+            segment = &segment[1 + size..];
+        }
+        Ok(())
+    }
+
+    /// Decodes the entropy coded data after the scan header, returns its length.
+    fn read_scan(&mut self, header: &[u8], data: &[u8]) -> Result<usize> {
+        let count = *header.first().context("truncated JPEG scan")? as usize;
+        let specs = header
+            .get(1..1 + count * 2)
+            .context("truncated JPEG scan")?;
+        let mut scan = vec![];
+        for spec in specs.chunks_exact(2) {
+            let index = self
+                .components
+                .iter()
+                .position(|c| c.id == spec[0])
+                .context("JPEG scan of an unknown component")?;
+            let component = &mut self.components[index];
+            component.dc_table = (spec[1] >> 4) as usize & 3;
+            component.ac_table = spec[1] as usize & 3;
+            component.dc_predictor = 0;
+            scan.push(index);
+        }
+        let mut bits = BitReader::new(data);
+        let (h_max, v_max) = self.max_sampling();
+        // Single component scans are not interleaved, their MCU is one block
+        let (mcus_x, mcus_y) = if let [index] = scan[..] {
+            let component = &self.components[index];
+            (
+                (self.width * component.h).div_ceil(8 * h_max),
+                (self.height * component.v).div_ceil(8 * v_max),
+            )
+        } else {
+            (
+                self.width.div_ceil(8 * h_max),
+                self.height.div_ceil(8 * v_max),
+            )
+        };
+        for mcu in 0..mcus_x * mcus_y {
+            if self.restart_interval > 0 && mcu > 0 && mcu % self.restart_interval == 0 {
+                bits.restart();
+                for &index in &scan {
+                    self.components[index].dc_predictor = 0;
+                }
+            }
+            let (mcu_x, mcu_y) = (mcu % mcus_x, mcu / mcus_x);
+            for &index in &scan {
+                let (h, v) = if scan.len() == 1 {
+                    (1, 1)
+                } else {
+                    (self.components[index].h, self.components[index].v)
+                };
+                for block_y in 0..v {
+                    for block_x in 0..h {
+                        let block = self.decode_block(index, &mut bits)?;
+                        let x = (mcu_x * h + block_x) * 8;
+                        let y = (mcu_y * v + block_y) * 8;
+                        self.store_block(index, x, y, &block);
+                    }
+                }
+            }
+        }
+        // Scan ends at the next marker that is not a restart
+        let mut end = bits.position;
+        while end + 1 < data.len()
+            && !(data[end] == 0xff && data[end + 1] != 0 && !(0xd0..=0xd7).contains(&data[end + 1]))
+        {
+            end += 1;
+        }
+        Ok(end)
+    }
+
+    fn decode_block(&mut self, index: usize, bits: &mut BitReader) -> Result<[f32; 64]> {
+        let component = &self.components[index];
+        let quantization = &self.quantization[component.quantization];
+        let mut coefficients = [0f32; 64];
+        let size = self.dc_tables[component.dc_table].decode(bits)?;
+        // Corrupt data can't overflow the predictor, it only makes a wrong image
+        let dc = component.dc_predictor.saturating_add(bits.signed(size)?);
+        coefficients[0] = dc as f32 * quantization[0] as f32;
+        let ac_table = &self.ac_tables[component.ac_table];
+        let mut k = 1;
+        while k < 64 {
+            let symbol = ac_table.decode(bits)?;
+            let (run, size) = ((symbol >> 4) as usize, symbol & 15);
+            if size == 0 {
+                if run == 15 {
+                    k += 16;
+                    continue;
+                }
+                break;
+            }
+            k += run;
+            if k >= 64 {
+                break;
+            }
+            let position = ZIGZAG[k];
+            coefficients[position] = bits.signed(size)? as f32 * quantization[position] as f32;
+            k += 1;
+        }
+        self.components[index].dc_predictor = dc;
+        Ok(self.inverse_dct(&coefficients))
+    }
+
+    fn inverse_dct(&self, coefficients: &[f32; 64]) -> [f32; 64] {
+        let mut rows = [0f32; 64];
+        for v in 0..8 {
+            for x in 0..8 {
+                rows[v * 8 + x] = (0..8)
+                    .map(|u| self.idct[u][x] * coefficients[v * 8 + u])
+                    .sum();
+            }
+        }
+        let mut block = [0f32; 64];
+        for y in 0..8 {
+            for x in 0..8 {
+                block[y * 8 + x] = (0..8).map(|v| self.idct[v][y] * rows[v * 8 + x]).sum();
+            }
+        }
+        block
+    }
+
+    fn store_block(&mut self, index: usize, x: usize, y: usize, block: &[f32; 64]) {
+        let component = &mut self.components[index];
+        let rows = component.plane.len() / component.stride;
+        for dy in 0..8 {
+            for dx in 0..8 {
+                if x + dx < component.stride && y + dy < rows {
+                    component.plane[(y + dy) * component.stride + x + dx] =
+                        (block[dy * 8 + dx] + 128.0).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+    }
+
+    /// Upsamples the chroma by repeating samples and converts to RGB.
+    fn to_rgb(&self) -> RgbImage {
+        let (h_max, v_max) = self.max_sampling();
+        let sample = |component: &Component, x: usize, y: usize| {
+            let (cx, cy) = (x * component.h / h_max, y * component.v / v_max);
+            component.plane[cy * component.stride + cx] as f32
+        };
+        let mut pixels = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pixel = match &self.components[..] {
+                    [gray] => [sample(gray, x, y) as u8; 3],
+                    [first, second, third] if self.adobe_transform == Some(0) => [
+                        sample(first, x, y) as u8,
+                        sample(second, x, y) as u8,
+                        sample(third, x, y) as u8,
+                    ],
+                    [luma, cb, cr] => {
+                        let (l, cb, cr) = (
+                            sample(luma, x, y),
+                            sample(cb, x, y) - 128.0,
+                            sample(cr, x, y) - 128.0,
+                        );
+                        [
+                            l + 1.402 * cr,
+                            l - 0.344_136 * cb - 0.714_136 * cr,
+                            l + 1.772 * cb,
+                        ]
+                        .map(|v| v.round().clamp(0.0, 255.0) as u8)
+                    }
+                    _ => [0; 3],
+                };
+                pixels.push(pixel);
+            }
+        }
+        RgbImage::new(self.width, self.height, pixels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_subsampled_with_restarts() {
+        // 4:2:0 chroma and a restart marker every 7 MCUs
+        let image = decode(include_bytes!("../fixtures/dark_zwieslt.jpg")).unwrap();
+        assert_eq!((image.width, image.height), (300, 360));
+        let near = |a: [u8; 3], b: [u8; 3]| a.iter().zip(b).all(|(&a, b)| a.abs_diff(b) <= 12);
+        assert!(near(image.pixel(5, 200), [18, 18, 18]));
+        assert!(near(image.pixel(150 - 24, 180), [247, 218, 33]));
+        assert!(decode(&[0xff, 0xd8, 0xff]).is_err());
+    }
+
+    #[test]
+    fn malformed_input_is_an_error() {
+        // Restart interval segment without its value
+        assert!(decode(&[0xff, 0xd8, 0xff, 0xdd, 0x00, 0x02, 0xff, 0xd9]).is_err());
+        let mut bits = BitReader::new(&[0xe0]);
+        assert!(bits.signed(255).is_err());
+        assert_eq!(bits.signed(3).unwrap(), 7);
+
+        // Cut short or overwritten anywhere, the image is an error or a wrong image
+        let fixture = include_bytes!("../fixtures/dark_zwieslt.jpg");
+        for end in (0..fixture.len()).step_by(fixture.len() / 20) {
+            let _ = decode(&fixture[..end]);
+        }
+        for start in (0..fixture.len()).step_by(fixture.len() / 20) {
+            let mut garbage = fixture.to_vec();
+            let end = (start + 16).min(garbage.len());
+            garbage[start..end].fill(0xf7);
+            let _ = decode(&garbage);
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Reads the letters of a Spelling Bee hive from a screenshot, without any native dependency:
+//! the image is decoded here, the tiles are found by their shape and each letter is matched
+//! against glyph templates trained on screenshots with `word_puzzles ocr train`.
+
+pub use glyphs::{Glyph, Glyphs};
+pub use image::{decode, RgbImage};
+pub mod glyphs;
+pub mod hive;
+pub mod image;
+pub mod jpeg;
+pub mod png;
+
+use anyhow::{bail, Result};
+
+/// Letters of a hive, the center one is the required letter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiveLetters {
+    pub center: char,
+    /// Clockwise from the top.
+    pub outer: Vec<char>,
+}
+
+impl HiveLetters {
+    /// Center letter first, as the solvers expect them.
+    pub fn letters(&self) -> String {
+        std::iter::once(self.center)
+            .chain(self.outer.iter().copied())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HiveReader {
+    glyphs: Glyphs,
+}
+
+impl HiveReader {
+    /// Reader with the bundled templates.
+    pub fn load() -> Self {
+        Self::new(Glyphs::load())
+    }
+
+    pub fn new(glyphs: Glyphs) -> Self {
+        Self { glyphs }
+    }
+
+    /// Reads a PNG or JPEG screenshot.
+    pub fn read(&self, image: &[u8]) -> Result<HiveLetters> {
+        let glyphs = hive::read_glyphs(&decode(image)?)?;
+        let mut letters = glyphs
+            .iter()
+            .filter_map(|glyph| self.glyphs.classify(glyph));
+        let Some(center) = letters.next() else {
+            bail!("no glyph templates to read the letters with");
+        };
+        Ok(HiveLetters {
+            center,
+            outer: letters.collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_light_png() {
+        let letters = HiveReader::load()
+            .read(include_bytes!("../fixtures/light_lacerst.png"))
+            .unwrap();
+        assert_eq!(letters.center, 'l');
+        assert_eq!(letters.letters(), "lacerst");
+    }
+
+    #[test]
+    fn it_reads_dark_jpeg() {
+        let letters = HiveReader::load()
+            .read(include_bytes!("../fixtures/dark_zwieslt.jpg"))
+            .unwrap();
+        assert_eq!(letters.letters(), "zwieslt");
+    }
+
+    #[test]
+    fn it_reads_training_screenshots() {
+        let reader = HiveReader::load();
+        let fixtures: [(&str, &[u8]); 4] = [
+            (
+                "abcdefg",
+                include_bytes!("../fixtures/training/abcdefg.png"),
+            ),
+            (
+                "hijklmn",
+                include_bytes!("../fixtures/training/hijklmn.png"),
+            ),
+            (
+                "opqrstu",
+                include_bytes!("../fixtures/training/opqrstu.png"),
+            ),
+            (
+                "vwxyzae",
+                include_bytes!("../fixtures/training/vwxyzae.png"),
+            ),
+        ];
+        for (letters, image) in fixtures {
+            assert_eq!(reader.read(image).unwrap().letters(), letters);
+        }
+    }
+
+    #[test]
+    fn it_needs_a_hive() {
+        let blank = RgbImage::new(40, 40, vec![[255; 3]; 1600]);
+        assert!(hive::read_glyphs(&blank).is_err());
+        assert!(HiveReader::load().read(b"GIF89a").is_err());
+    }
+}
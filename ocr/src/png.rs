@@ -0,0 +1,218 @@
+use anyhow::{bail, Context, Result};
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
+
+use crate::image::{check_size, RgbImage};
+
+pub const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+#[derive(Debug, Clone, Copy)]
+struct Header {
+    width: usize,
+    height: usize,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color_type {
+            0 | 3 => 1,
+            4 => 2,
+            2 => 3,
+            _ => 4,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.bit_depth as usize
+    }
+
+    fn stride(&self) -> usize {
+        (self.width * self.bits_per_pixel()).div_ceil(8)
+    }
+}
+
+/// Decodes non interlaced PNGs of any color type, transparency is blended over white.
+pub fn decode(bytes: &[u8]) -> Result<RgbImage> {
+    let mut rest = bytes
+        .strip_prefix(SIGNATURE)
+        .context("missing PNG signature")?;
+    let mut header = None;
+    let mut palette: &[u8] = &[];
+    let mut data = vec![];
+    while rest.len() >= 12 {
+        let length = u32::from_be_bytes(rest[..4].try_into()?) as usize;
+        let kind = &rest[4..8];
+        let Some(chunk) = rest.get(8..8 + length) else {
+            bail!("truncated PNG chunk");
+        };
+        match kind {
+            b"IHDR" => header = Some(parse_header(chunk)?),
+            b"PLTE" => palette = chunk,
+            b"IDAT" => data.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+        // Chunk CRC is not checked, a corrupt image fails to inflate or to be read anyway
+        rest = &rest[(12 + length).min(rest.len())..];
+    }
+    let header = header.context("missing PNG header")?;
+    let limit = (header.stride() + 1) * header.height;
+    let mut raw = decompress_to_vec_zlib_with_limit(&data, limit)
+        .map_err(|err| anyhow::anyhow!("unable to inflate PNG data: {:?}", err.status))?;
+    if raw.len() < limit {
+        bail!("truncated PNG data");
+    }
+    unfilter(&header, &mut raw)?;
+    Ok(to_rgb(&header, &raw, palette))
+}
+
+fn parse_header(chunk: &[u8]) -> Result<Header> {
+    if chunk.len() < 13 {
+        bail!("truncated PNG header");
+    }
+    let header = Header {
+        width: u32::from_be_bytes(chunk[0..4].try_into()?) as usize,
+        height: u32::from_be_bytes(chunk[4..8].try_into()?) as usize,
+        bit_depth: chunk[8],
+        color_type: chunk[9],
+    };
+    check_size(header.width, header.height)?;
+    let valid_depth = match header.color_type {
+        0 => [1, 2, 4, 8, 16].contains(&header.bit_depth),
+        3 => [1, 2, 4, 8].contains(&header.bit_depth),
+        2 | 4 | 6 => [8, 16].contains(&header.bit_depth),
+        _ => false,
+    };
+    if !valid_depth {
+        bail!(
+            "unsupported PNG color type {} with bit depth {}",
+            header.color_type,
+            header.bit_depth
+        );
+    }
+    if chunk[12] != 0 {
+        bail!("interlaced PNGs are not supported");
+    }
+    Ok(header)
+}
+
+/// Reverses the per row filters in place, leaving the filter bytes in front of the rows.
+fn unfilter(header: &Header, raw: &mut [u8]) -> Result<()> {
+    let stride = header.stride();
+    let bpp = header.bits_per_pixel().div_ceil(8);
+    for y in 0..header.height {
+        let start = y * (stride + 1);
+        let (done, row) = raw.split_at_mut(start + 1);
+        let filter = done[start];
+        let row = &mut row[..stride];
+        let previous = if y == 0 {
+            None
+        } else {
+            Some(&done[start - stride..start])
+        };
+        for i in 0..stride {
+            let a = if i >= bpp { row[i - bpp] } else { 0 };
+            let b = previous.map_or(0, |p| p[i]);
+            let c = match previous {
+                Some(p) if i >= bpp => p[i - bpp],
+                _ => 0,
+            };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => bail!("unknown PNG filter {}", filter),
+            };
+            row[i] = row[i].wrapping_add(predicted);
+        }
+    }
+    Ok(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn to_rgb(header: &Header, raw: &[u8], palette: &[u8]) -> RgbImage {
+    let stride = header.stride();
+    let channels = header.channels();
+    let depth = header.bit_depth as usize;
+    let max = ((1u32 << depth.min(8)) - 1) as f32;
+    let mut pixels = Vec::with_capacity(header.width * header.height);
+    for y in 0..header.height {
+        let row = &raw[y * (stride + 1) + 1..][..stride];
+        // Samples scaled to 8 bits, 16 bit ones keep their high byte
+        let sample = |index: usize| -> u8 {
+            match depth {
+                16 => row[index * 2],
+                8 => row[index],
+                _ => {
+                    let bit = index * depth;
+                    let value = (row[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1);
+                    if header.color_type == 3 {
+                        value
+                    } else {
+                        (value as f32 * 255.0 / max).round() as u8
+                    }
+                }
+            }
+        };
+        for x in 0..header.width {
+            let base = x * channels;
+            let (rgb, alpha) = match header.color_type {
+                0 => ([sample(base); 3], 255),
+                4 => ([sample(base); 3], sample(base + 1)),
+                2 => ([sample(base), sample(base + 1), sample(base + 2)], 255),
+                3 => {
+                    let index = sample(base) as usize * 3;
+                    match palette.get(index..index + 3) {
+                        Some(color) => ([color[0], color[1], color[2]], 255),
+                        None => ([0; 3], 255),
+                    }
+                }
+                _ => (
+                    [sample(base), sample(base + 1), sample(base + 2)],
+                    sample(base + 3),
+                ),
+            };
+            pixels.push(rgb.map(|v| over_white(v, alpha)));
+        }
+    }
+    RgbImage::new(header.width, header.height, pixels)
+}
+
+fn over_white(value: u8, alpha: u8) -> u8 {
+    let alpha = alpha as u32;
+    ((value as u32 * alpha + 255 * (255 - alpha) + 127) / 255) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_all_filters() {
+        // Rows of the fixture cycle through the five filter types
+        let image = decode(include_bytes!("../fixtures/light_lacerst.png")).unwrap();
+        assert_eq!((image.width, image.height), (390, 700));
+        assert_eq!(image.pixel(0, 0), [255, 255, 255]);
+        // Center tile is yellow, the top left corner of the hive is empty
+        assert_eq!(image.pixel(195 - 40, 330), [247, 218, 33]);
+        assert!(decode(&SIGNATURE[..4]).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+function schemaName(schema) {
+  if (!schema) {
+    return '';
+  }
+  if (schema.$ref) {
+    return schema.$ref.split('/').pop();
+  }
+  if (schema.type === 'array') {
+    return schemaName(schema.items) + '[]';
+  }
+  return schema.type || '';
+}
+
+function bodySchema(content) {
+  const json = content && (content['application/json'] || content['text/plain']);
+  return json ? schemaName(json.schema) : '';
+}
+
+function element(tag, text, className) {
+  const node = document.createElement(tag);
+  if (text) {
+    node.textContent = text;
+  }
+  if (className) {
+    node.className = className;
+  }
+  return node;
+}
+
+function renderOperation(path, method, operation) {
+  const section = element('section', null, 'api-operation');
+  section.appendChild(element('h3', method.toUpperCase() + ' ' + path));
+  if (operation.description || operation.summary) {
+    section.appendChild(element('p', operation.description || operation.summary));
+  }
+
+  const params = operation.parameters || [];
+  if (params.length) {
+    const list = element('ul');
+    for (const param of params) {
+      const text = param.name + ' (' + param.in + ', ' + schemaName(param.schema) + ')';
+      list.appendChild(element('li', param.description ? text + ': ' + param.description : text));
+    }
+    section.appendChild(element('h4', 'Parameters'));
+    section.appendChild(list);
+  }
+
+  if (operation.requestBody) {
+    section.appendChild(element('h4', 'Request body'));
+    section.appendChild(element('code', bodySchema(operation.requestBody.content)));
+  }
+
+  const responses = element('ul');
+  for (const [status, response] of Object.entries(operation.responses || {})) {
+    const schema = bodySchema(response.content);
+    const text = status + ' ' + (response.description || '') + (schema ? ': ' + schema : '');
+    responses.appendChild(element('li', text));
+  }
+  section.appendChild(element('h4', 'Responses'));
+  section.appendChild(responses);
+  return section;
+}
+
+function renderSchemas(schemas) {
+  const section = element('section', null, 'api-schemas');
+  section.appendChild(element('h2', 'Schemas'));
+  for (const [name, schema] of Object.entries(schemas)) {
+    section.appendChild(element('h3', name));
+    section.appendChild(element('pre', JSON.stringify(schema, null, 2)));
+  }
+  return section;
+}
+
+async function renderApiDocs(root) {
+  const response = await fetch(root.dataset.specUrl);
+  const spec = await response.json();
+  root.textContent = '';
+  root.appendChild(element('p', spec.info.title + ' ' + spec.info.version));
+  for (const [path, item] of Object.entries(spec.paths)) {
+    for (const [method, operation] of Object.entries(item)) {
+      root.appendChild(renderOperation(path, method, operation));
+    }
+  }
+  if (spec.components && spec.components.schemas) {
+    root.appendChild(renderSchemas(spec.components.schemas));
+  }
+}
+
+renderApiDocs(document.getElementById('api-docs'));
@@ -5,3 +5,15 @@ function onlyLetters(value) {
 function compressLetterList(value) {
   return value.replaceAll(/-\d[ |\n]/g, ', ').replaceAll(/-\d/g, '');
 }
+
+function typeLetter(button) {
+  const input = document.getElementById('play-word');
+  input.value += button.value;
+  input.focus();
+}
+
+function deleteLetter() {
+  const input = document.getElementById('play-word');
+  input.value = input.value.slice(0, -1);
+  input.focus();
+}
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use argon2::{
+    password_hash::{rand_core::OsRng, SaltString},
+    Argon2, PasswordHash, PasswordHasher, PasswordVerifier,
+};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::request::Parts,
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+use chrono::{Duration, Utc};
+
+use super::LOGIN_URL;
+use crate::{
+    app_state::SharedAppState,
+    storage::{User, UserId},
+    utils::random_token::random_token,
+};
+
+pub const SESSION_COOKIE: &str = "session";
+const SESSION_DAYS: i64 = 30;
+
+/// Logged in user of the session cookie.
+/// Pages that work for everyone take `Option<CurrentUser>`, others send to the login page.
+#[derive(Debug, Clone)]
+pub struct CurrentUser(pub User);
+
+#[async_trait]
+impl FromRequestParts<SharedAppState> for CurrentUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &SharedAppState,
+    ) -> Result<Self, Self::Rejection> {
+        let jar = CookieJar::from_headers(&parts.headers);
+        let user = match jar.get(SESSION_COOKIE) {
+            Some(cookie) => {
+                let token = cookie.value().to_owned();
+                app_state
+                    .with_storage(move |storage| storage.session_user(&token, Utc::now()))
+                    .await
+                    .inspect_err(|err| log::error!("Unable to read session: {:#}", err))
+                    .ok()
+                    .flatten()
+            }
+            None => None,
+        };
+        user.map(CurrentUser)
+            .ok_or_else(|| Redirect::to(LOGIN_URL).into_response())
+    }
+}
+
+/// Id of the optional user, as the storage takes it.
+pub fn user_id(user: &Option<CurrentUser>) -> Option<UserId> {
+    user.as_ref().map(|CurrentUser(user)| user.id)
+}
+
+/// User of the optional session, for the layout.
+pub fn user(user: &Option<CurrentUser>) -> Option<&User> {
+    user.as_ref().map(|CurrentUser(user)| user)
+}
+
+/// Stores a new session for the user and returns its cookie.
+pub async fn start_session(app_state: &SharedAppState, user: &User) -> Result<Cookie<'static>> {
+    let token = random_token(32);
+    let expires = Utc::now() + Duration::days(SESSION_DAYS);
+    let (session, id) = (token.clone(), user.id);
+    app_state
+        .with_storage(move |storage| storage.create_session(&session, id, expires))
+        .await?;
+    Ok(Cookie::build((SESSION_COOKIE, token))
+        .path("/")
+        .http_only(true)
+        .secure(!app_state.is_dev)
+        .same_site(SameSite::Lax)
+        .max_age(time::Duration::days(SESSION_DAYS))
+        .build())
+}
+
+pub fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map_err(|err| anyhow!("unable to hash password: {}", err))?;
+    Ok(hash.to_string())
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .and_then(|hash| Argon2::default().verify_password(password.as_bytes(), &hash))
+        .is_ok()
+}
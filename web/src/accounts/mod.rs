@@ -0,0 +1,396 @@
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header::RETRY_AFTER, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use axum_extra::extract::{cookie::Cookie, CookieJar};
+use maud::Markup;
+use serde::Deserialize;
+use std::{
+    net::SocketAddr,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    app_state::SharedAppState,
+    rate_limit::retry_after_seconds,
+    storage::User,
+    templates::{
+        accounts::{account_form, AccountForm},
+        layout::layout,
+    },
+    utils::same_origin::SameOrigin,
+};
+
+mod current_user;
+
+use current_user::{hash_password, start_session, verify_password, SESSION_COOKIE};
+pub use current_user::{user, user_id, CurrentUser};
+
+pub const ACCOUNT_PATH: &str = "/account";
+pub const LOGIN_URL: &str = "/account/login";
+pub const REGISTER_URL: &str = "/account/register";
+pub const LOGOUT_URL: &str = "/account/logout";
+
+const USERNAME_LENGTH: std::ops::RangeInclusive<usize> = 3..=32;
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Verified when the username is unknown, so both failures take the same time.
+static DUMMY_HASH: LazyLock<String> =
+    LazyLock::new(|| hash_password("dummy password").unwrap_or_default());
+
+pub fn create_router() -> Router<SharedAppState> {
+    Router::new()
+        .route("/login", get(login_page_route).post(login_route))
+        .route("/register", get(register_page_route).post(register_route))
+        .route("/logout", post(logout_route))
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CredentialsForm {
+    username: String,
+    password: String,
+}
+
+/// Usernames are lowercase, so `Alice` and `alice` are the same account.
+fn normalize_username(username: &str) -> String {
+    username.trim().to_lowercase()
+}
+
+async fn login_page_route(user: Option<CurrentUser>) -> Response {
+    if user.is_some() {
+        return Redirect::to("/").into_response();
+    }
+    page(AccountForm::Login, "", "").into_response()
+}
+
+async fn register_page_route(user: Option<CurrentUser>) -> Response {
+    if user.is_some() {
+        return Redirect::to("/").into_response();
+    }
+    page(AccountForm::Register, "", "").into_response()
+}
+
+/// Form errors are answered with the form and `200 OK`, so boosted forms swap them in.
+/// Attempts are limited per address and per username, each one costs a password hash.
+async fn login_route(
+    _: SameOrigin,
+    State(app_state): State<SharedAppState>,
+    headers: HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    jar: CookieJar,
+    Form(form): Form<CredentialsForm>,
+) -> Response {
+    let username = normalize_username(&form.username);
+    let peer = peer.map(|ConnectInfo(addr)| addr.ip());
+    let admitted =
+        app_state
+            .rate_limiter
+            .take_account(&headers, peer, Some(&username), Instant::now());
+    if let Err(retry_after) = admitted {
+        return too_many_attempts(AccountForm::Login, &username, retry_after);
+    }
+    let name = username.clone();
+    let credentials = match app_state
+        .with_storage(move |storage| storage.user_credentials(&name))
+        .await
+    {
+        Ok(credentials) => credentials,
+        Err(err) => return account_error(AccountForm::Login, &username, err),
+    };
+    let (user, hash) = credentials.unzip();
+    let password = form.password;
+    let verified = tokio::task::spawn_blocking(move || {
+        verify_password(&password, hash.as_deref().unwrap_or(&DUMMY_HASH))
+    })
+    .await
+    .unwrap_or(false);
+    match user.filter(|_| verified) {
+        Some(user) => logged_in(&app_state, jar, &user).await,
+        None => page(AccountForm::Login, &username, "Wrong username or password").into_response(),
+    }
+}
+
+async fn register_route(
+    _: SameOrigin,
+    State(app_state): State<SharedAppState>,
+    headers: HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
+    jar: CookieJar,
+    Form(form): Form<CredentialsForm>,
+) -> Response {
+    let username = normalize_username(&form.username);
+    let peer = peer.map(|ConnectInfo(addr)| addr.ip());
+    let admitted = app_state
+        .rate_limiter
+        .take_account(&headers, peer, None, Instant::now());
+    if let Err(retry_after) = admitted {
+        return too_many_attempts(AccountForm::Register, &username, retry_after);
+    }
+    let is_valid_username = USERNAME_LENGTH.contains(&username.len())
+        && username
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-');
+    if !is_valid_username {
+        let error = format!(
+            "Usernames have {} to {} letters, digits, `_` or `-`",
+            USERNAME_LENGTH.start(),
+            USERNAME_LENGTH.end()
+        );
+        return page(AccountForm::Register, &username, &error).into_response();
+    }
+    if form.password.chars().count() < MIN_PASSWORD_LENGTH {
+        let error = format!("Passwords have at least {} characters", MIN_PASSWORD_LENGTH);
+        return page(AccountForm::Register, &username, &error).into_response();
+    }
+    let password = form.password;
+    let hash = match tokio::task::spawn_blocking(move || hash_password(&password)).await {
+        Ok(Ok(hash)) => hash,
+        Ok(Err(err)) => return account_error(AccountForm::Register, &username, err),
+        Err(err) => return account_error(AccountForm::Register, &username, err.into()),
+    };
+    let name = username.clone();
+    match app_state
+        .with_storage(move |storage| storage.create_user(&name, &hash))
+        .await
+    {
+        Ok(Some(user)) => {
+            log::info!("Registered user {}", user.username);
+            logged_in(&app_state, jar, &user).await
+        }
+        Ok(None) => page(
+            AccountForm::Register,
+            &username,
+            "Username is already taken",
+        )
+        .into_response(),
+        Err(err) => account_error(AccountForm::Register, &username, err),
+    }
+}
+
+async fn logout_route(
+    _: SameOrigin,
+    State(app_state): State<SharedAppState>,
+    jar: CookieJar,
+) -> Response {
+    if let Some(cookie) = jar.get(SESSION_COOKIE) {
+        let token = cookie.value().to_owned();
+        let deleted = app_state
+            .with_storage(move |storage| storage.delete_session(&token))
+            .await;
+        if let Err(err) = deleted {
+            log::error!("Unable to delete session: {:#}", err);
+        }
+    }
+    let jar = jar.remove(Cookie::build(SESSION_COOKIE).path("/"));
+    (jar, Redirect::to("/")).into_response()
+}
+
+async fn logged_in(app_state: &SharedAppState, jar: CookieJar, user: &User) -> Response {
+    match start_session(app_state, user).await {
+        Ok(cookie) => (jar.add(cookie), Redirect::to("/")).into_response(),
+        Err(err) => account_error(AccountForm::Login, &user.username, err),
+    }
+}
+
+fn account_error(form: AccountForm, username: &str, err: anyhow::Error) -> Response {
+    log::error!("Accounts are unavailable: {:#}", err);
+    page(form, username, "Accounts are unavailable, try again later").into_response()
+}
+
+/// Answered with `429` rather than as a form error, the layout swaps it in all the same.
+fn too_many_attempts(form: AccountForm, username: &str, retry_after: Duration) -> Response {
+    let seconds = retry_after_seconds(retry_after);
+    let error = format!("Too many attempts, try again in {} seconds", seconds);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(RETRY_AFTER, seconds)],
+        page(form, username, &error),
+    )
+        .into_response()
+}
+
+fn page(form: AccountForm, username: &str, error: &str) -> Markup {
+    layout(
+        account_form(form, username, error),
+        Some(form.title()),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app_state::AppState, config::AppConfig, rate_limit::ApiQuotas, routes};
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let config = AppConfig::for_tests("accounts_routes");
+        let app_state = AppState::new(&config).unwrap().shared();
+        Router::new()
+            .merge(routes::create_router())
+            .nest(ACCOUNT_PATH, create_router())
+            .with_state(app_state)
+    }
+
+    /// Form posted from a page of the site.
+    fn post(uri: &str, body: &str, cookie: Option<&str>) -> Request<Body> {
+        let request = Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::HOST, "puzzles.example")
+            .header(header::ORIGIN, "https://puzzles.example");
+        let request = match cookie {
+            Some(cookie) => request.header(header::COOKIE, cookie),
+            None => request,
+        };
+        request.body(Body::from(body.to_owned())).unwrap()
+    }
+
+    async fn get_text(app: &Router, uri: &str, cookie: Option<&str>) -> String {
+        let request = Request::get(uri);
+        let request = match cookie {
+            Some(cookie) => request.header(header::COOKIE, cookie),
+            None => request,
+        };
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    /// `name=value` of the session cookie set by the response.
+    fn session_cookie(response: &Response) -> String {
+        let set_cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        set_cookie.split(';').next().unwrap().to_owned()
+    }
+
+    #[tokio::test]
+    async fn users_keep_their_own_history() {
+        let app = app();
+        let register = "username=Alice&password=correct+horse";
+        let response = app
+            .clone()
+            .oneshot(post("/account/register", register, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let cookie = session_cookie(&response);
+        assert!(cookie.starts_with("session="));
+
+        let response = app
+            .clone()
+            .oneshot(post("/account/register", register, None))
+            .await
+            .unwrap();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(String::from_utf8_lossy(&bytes).contains("Username is already taken"));
+
+        let solve = "/games/spelling_bee/solve_simple";
+        let response = app
+            .clone()
+            .oneshot(post(solve, "letters=zwieslt", Some(&cookie)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let history = "/games/spelling_bee/history";
+        let body = get_text(&app, history, Some(&cookie)).await;
+        assert!(body.contains("alice"));
+        assert!(body.contains(">zwieslt</a>"));
+        assert!(body.contains("Current streak 1 days"));
+        let body = get_text(&app, history, None).await;
+        assert!(body.contains("No puzzles solved yet."));
+        assert!(body.contains("Log in"));
+
+        let response = app
+            .clone()
+            .oneshot(post("/account/logout", "", Some(&cookie)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let body = get_text(&app, history, Some(&cookie)).await;
+        assert!(!body.contains("alice"));
+    }
+
+    #[tokio::test]
+    async fn login_checks_password() {
+        let app = app();
+        let register = "username=bob&password=correct+horse";
+        app.clone()
+            .oneshot(post("/account/register", register, None))
+            .await
+            .unwrap();
+
+        let wrong = "username=bob&password=wrong+horse";
+        let response = app
+            .clone()
+            .oneshot(post("/account/login", wrong, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::SET_COOKIE));
+
+        let right = "username=Bob&password=correct+horse";
+        let response = app
+            .clone()
+            .oneshot(post("/account/login", right, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        let cookie = session_cookie(&response);
+        assert!(get_text(&app, "/", Some(&cookie)).await.contains("bob"));
+    }
+
+    #[tokio::test]
+    async fn cross_origin_forms_are_refused() {
+        let mut request = post(
+            "/account/register",
+            "username=eve&password=correct+horse",
+            None,
+        );
+        request
+            .headers_mut()
+            .insert(header::ORIGIN, "https://evil.example".parse().unwrap());
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let mut request = post("/account/login", "username=eve&password=wrong", None);
+        request.headers_mut().remove(header::ORIGIN);
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn login_attempts_are_limited() {
+        let app = app();
+        let wrong = "username=carol&password=wrong+horse";
+        for _ in 0..ApiQuotas::default().accounts {
+            let response = app
+                .clone()
+                .oneshot(post("/account/login", wrong, None))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = app
+            .clone()
+            .oneshot(post("/account/login", wrong, None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(String::from_utf8_lossy(&bytes).contains("Too many attempts"));
+    }
+}
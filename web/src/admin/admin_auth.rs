@@ -0,0 +1,128 @@
+use crate::{app_state::SharedAppState, utils::same_origin::is_same_origin};
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{
+        header::{AUTHORIZATION, WWW_AUTHENTICATE},
+        request::Parts,
+        StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+/// Admin access, granted by `ADMIN_TOKEN` sent as a bearer token
+/// or as the password of basic auth, so browsers can prompt for it.
+/// Browsers send cached basic credentials along with forms posted from other sites,
+/// so with basic auth only same-origin requests may change anything.
+pub struct AdminAuth;
+
+#[async_trait]
+impl FromRequestParts<SharedAppState> for AdminAuth {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &SharedAppState,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(admin_token) = &app_state.admin_token else {
+            return Err(StatusCode::NOT_FOUND.into_response());
+        };
+        authorize(parts, admin_token).map_err(|status| match status {
+            StatusCode::UNAUTHORIZED => {
+                (status, [(WWW_AUTHENTICATE, "Basic realm=\"admin\"")]).into_response()
+            }
+            _ => (status, "Cross-origin admin request").into_response(),
+        })
+    }
+}
+
+fn authorize(parts: &Parts, admin_token: &str) -> Result<AdminAuth, StatusCode> {
+    let authorization = parts
+        .headers
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or_default();
+    let (token, is_basic) = if let Some(token) = authorization.strip_prefix("Bearer ") {
+        (Some(token.to_owned()), false)
+    } else if let Some(credentials) = authorization.strip_prefix("Basic ") {
+        let password = STANDARD
+            .decode(credentials)
+            .ok()
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .and_then(|decoded| decoded.split_once(':').map(|(_, p)| p.to_owned()));
+        (password, true)
+    } else {
+        (None, false)
+    };
+    if !token.is_some_and(|token| constant_time_eq(token.as_bytes(), admin_token.as_bytes())) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    if is_basic && !parts.method.is_safe() && !is_same_origin(&parts.headers) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(AdminAuth)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{header::HOST, Request};
+
+    fn authorize_request(method: &str, headers: &[(&str, &str)]) -> StatusCode {
+        let mut request = Request::builder()
+            .method(method)
+            .uri("/admin/reload")
+            .header(HOST, "puzzles.example:8080");
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let (parts, _) = request.body(()).unwrap().into_parts();
+        match authorize(&parts, "secret") {
+            Ok(AdminAuth) => StatusCode::OK,
+            Err(status) => status,
+        }
+    }
+
+    #[test]
+    fn basic_auth_posts_must_be_same_origin() {
+        let basic = format!("Basic {}", STANDARD.encode("admin:secret"));
+        let basic = ("authorization", basic.as_str());
+        let bearer = ("authorization", "Bearer secret");
+
+        assert_eq!(authorize_request("GET", &[basic]), StatusCode::OK);
+        assert_eq!(authorize_request("POST", &[bearer]), StatusCode::OK);
+        assert_eq!(
+            authorize_request("POST", &[basic, ("origin", "http://puzzles.example:8080")]),
+            StatusCode::OK
+        );
+        assert_eq!(
+            authorize_request(
+                "POST",
+                &[
+                    basic,
+                    ("referer", "http://puzzles.example:8080/admin/words")
+                ]
+            ),
+            StatusCode::OK
+        );
+
+        assert_eq!(authorize_request("POST", &[basic]), StatusCode::FORBIDDEN);
+        assert_eq!(
+            authorize_request("POST", &[basic, ("origin", "https://evil.example")]),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            authorize_request("POST", &[basic, ("origin", "http://puzzles.example")]),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            authorize_request("POST", &[("authorization", "Bearer guess")]),
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
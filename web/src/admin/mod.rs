@@ -0,0 +1,48 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+
+use crate::{app_state::SharedAppState, metrics};
+
+mod admin_auth;
+mod words_overlay;
+
+pub use admin_auth::AdminAuth;
+
+pub fn create_router() -> Router<SharedAppState> {
+    Router::new()
+        .route("/reload", post(reload_route))
+        .route(
+            "/words",
+            get(words_overlay::words_overlay_route).post(words_overlay::save_words_overlay_route),
+        )
+        .route("/cache", get(cache_route))
+        .route("/metrics", get(metrics_route))
+}
+
+/// Same as the private `/metrics`, for scrapers sending the admin token.
+async fn metrics_route(_: AdminAuth, State(app_state): State<SharedAppState>) -> Response {
+    metrics::metrics_route(State(app_state)).await
+}
+
+/// Solution cache of the current dictionary, with the hits and misses since startup.
+async fn cache_route(_: AdminAuth, State(app_state): State<SharedAppState>) -> Response {
+    Json(app_state.words().solutions.stats()).into_response()
+}
+
+async fn reload_route(_: AdminAuth, State(app_state): State<SharedAppState>) -> Response {
+    match app_state.reload_words().await {
+        Ok(size) => {
+            log::info!("Dictionary reloaded by admin, {} words", size);
+            format!("Dictionary reloaded, {} words", size).into_response()
+        }
+        Err(err) => {
+            log::error!("Dictionary reload by admin failed: {}", err);
+            (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()
+        }
+    }
+}
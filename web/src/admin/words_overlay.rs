@@ -0,0 +1,69 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Form};
+use maud::Markup;
+use serde::Deserialize;
+use words::{words_overlay::list_to_text, WordsOverlay};
+
+use super::AdminAuth;
+use crate::{
+    accounts::{user, CurrentUser},
+    app_state::SharedAppState,
+    templates::{admin::words_overlay, layout::layout},
+};
+
+const TITLE: &str = "Dictionary overlays";
+
+pub async fn words_overlay_route(
+    _: AdminAuth,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+) -> Markup {
+    let overlay = app_state.words_overlay();
+    page(&app_state, &current_user, &overlay, "")
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WordsOverlayForm {
+    allow: String,
+    block: String,
+}
+
+pub async fn save_words_overlay_route(
+    _: AdminAuth,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Form(form): Form<WordsOverlayForm>,
+) -> impl IntoResponse {
+    let overlay = WordsOverlay::new(&form.allow, &form.block);
+    match app_state.update_words_overlay(overlay.clone()).await {
+        Ok(size) => {
+            log::info!("Dictionary overlays updated by admin, {} words", size);
+            (
+                StatusCode::OK,
+                page(&app_state, &current_user, &overlay, "Saved"),
+            )
+        }
+        Err(err) => {
+            log::error!("Dictionary overlays update by admin failed: {:#}", err);
+            let status = format!("Unable to save: {:#}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                page(&app_state, &current_user, &overlay, &status),
+            )
+        }
+    }
+}
+
+fn page(
+    app_state: &SharedAppState,
+    current_user: &Option<CurrentUser>,
+    overlay: &WordsOverlay,
+    status: &str,
+) -> Markup {
+    let template = words_overlay(
+        &list_to_text(overlay.allow()),
+        &list_to_text(overlay.block()),
+        app_state.words().dict.size(),
+        status,
+    );
+    layout(template, Some(TITLE), user(current_user))
+}
@@ -0,0 +1,113 @@
+use axum::{
+    extract::rejection::JsonRejection,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use words::spelling_bee::SpellingBeeError;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiErrorBody {
+    /// Machine-readable error code.
+    #[schema(example = "letters_count")]
+    pub code: &'static str,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiError {
+    #[serde(skip)]
+    pub status: StatusCode,
+    pub error: ApiErrorBody,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            error: ApiErrorBody {
+                code,
+                message: message.into(),
+            },
+        }
+    }
+}
+
+/// Bodies that aren't valid JSON, or not the expected one, are `422`,
+/// a body over the size limit keeps its `413` and a missing JSON content type its `415`.
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let status = match rejection.status() {
+            status @ (StatusCode::PAYLOAD_TOO_LARGE | StatusCode::UNSUPPORTED_MEDIA_TYPE) => status,
+            _ => StatusCode::UNPROCESSABLE_ENTITY,
+        };
+        Self::new(status, "invalid_body", rejection.body_text())
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast_ref::<SpellingBeeError>() {
+            Some(game_err) => Self::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                game_err.code(),
+                game_err.to_string(),
+            ),
+            None => {
+                // Details stay in the log, they may name files or queries
+                log::error!("API request failed: {:#}", err);
+                Self::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal",
+                    "internal server error",
+                )
+            }
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, extract::FromRequest, http::Request};
+
+    async fn rejection(content_type: &str, body: &'static str) -> ApiError {
+        let request = Request::post("/")
+            .header("content-type", content_type)
+            .body(Body::from(body))
+            .unwrap();
+        let rejection = Json::<serde_json::Value>::from_request(request, &())
+            .await
+            .unwrap_err();
+        ApiError::from(rejection)
+    }
+
+    #[tokio::test]
+    async fn invalid_bodies_are_unprocessable() {
+        for (content_type, body) in [
+            ("application/json", "{\"letters\":"),
+            ("application/json", "[1, 2"),
+        ] {
+            let err = rejection(content_type, body).await;
+            assert_eq!(err.status, StatusCode::UNPROCESSABLE_ENTITY, "{}", body);
+            assert_eq!(err.error.code, "invalid_body");
+        }
+        let err = rejection("text/plain", "{}").await;
+        assert_eq!(err.status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[test]
+    fn internal_errors_are_not_disclosed() {
+        let err = ApiError::from(anyhow::anyhow!("unable to open /var/lib/words.db"));
+        assert_eq!(err.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(err.error.message, "internal server error");
+    }
+}
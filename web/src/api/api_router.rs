@@ -0,0 +1,45 @@
+use axum::{routing::MethodRouter, Router};
+
+use crate::app_state::SharedAppState;
+
+/// Thin wrapper over [`Router`] that remembers every registered path,
+/// so the OpenAPI spec can be checked against what is actually served.
+pub struct ApiRouter {
+    router: Router<SharedAppState>,
+    paths: Vec<String>,
+}
+
+impl ApiRouter {
+    pub fn new() -> Self {
+        Self {
+            router: Router::new(),
+            paths: vec![],
+        }
+    }
+
+    pub fn route(mut self, path: &str, method_router: MethodRouter<SharedAppState>) -> Self {
+        self.router = self.router.route(path, method_router);
+        self.paths.push(path.to_owned());
+        self
+    }
+
+    pub fn nest(mut self, prefix: &str, other: ApiRouter) -> Self {
+        self.router = self.router.nest(prefix, other.router);
+        self.paths.extend(
+            other
+                .paths
+                .into_iter()
+                .map(|path| format!("{}{}", prefix, path)),
+        );
+        self
+    }
+
+    #[cfg(test)]
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    pub fn into_router(self) -> Router<SharedAppState> {
+        self.router
+    }
+}
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::ApiError;
+use crate::{
+    app_state::SharedAppState,
+    rate_limit::{DefineQuota, RateLimit},
+};
+
+#[derive(Deserialize, IntoParams)]
+pub struct DefineQuery {
+    /// Word to look up, case-insensitive.
+    pub w: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WordDefinition {
+    #[schema(example = "noun")]
+    pub part_of_speech: String,
+    pub definition: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DefineResponse {
+    pub word: String,
+    pub definitions: Vec<WordDefinition>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/define",
+    params(DefineQuery),
+    responses(
+        (status = 200, description = "Meanings of the word", body = DefineResponse),
+        (status = 404, description = "Word has no definitions", body = ApiError),
+        (status = 503, description = "Definitions are not configured", body = ApiError),
+        (status = 429, description = "Quota used up, retry after `Retry-After` seconds", body = ApiError),
+    ),
+    tag = "words"
+)]
+pub async fn define_route(
+    _: RateLimit<DefineQuota>,
+    State(app_state): State<SharedAppState>,
+    Query(query): Query<DefineQuery>,
+) -> Result<Json<DefineResponse>, ApiError> {
+    let Some(definitions) = &app_state.definitions else {
+        return Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "definitions_unavailable",
+            "definitions are not configured",
+        ));
+    };
+    let word = query.w.trim().to_lowercase();
+    let found = definitions.get(&word);
+    if found.is_empty() {
+        return Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            "not_found",
+            format!("no definitions for {}", word),
+        ));
+    }
+    Ok(Json(DefineResponse {
+        definitions: found
+            .iter()
+            .map(|d| WordDefinition {
+                part_of_speech: d.part_of_speech.clone(),
+                definition: d.text.clone(),
+            })
+            .collect(),
+        word,
+    }))
+}
@@ -5,48 +5,204 @@ use axum::{
     routing::get,
     Json, Router,
 };
-use serde::Deserialize;
-// use simple_server_timing_header::Timer;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
-use crate::app_state::SharedAppState;
+use crate::{
+    app_state::SharedAppState,
+    rate_limit::{RateLimit, SearchQuota},
+    telemetry::server_timing::stage,
+};
+
+mod api_error;
+mod api_router;
+mod definitions;
+mod openapi;
+mod spelling_bee;
+
+pub use api_error::ApiError;
+pub use api_router::ApiRouter;
+
+/// Label of the word search among the solver metrics.
+const SEARCH: &str = "search";
+/// Words in a page of search results, at most and by default.
+const SEARCH_PAGE_SIZE: usize = 1000;
+
+/// Routes described by the OpenAPI spec.
+fn api_routes() -> ApiRouter {
+    ApiRouter::new()
+        .route("/search", get(search_route))
+        .route("/define", get(definitions::define_route))
+        .nest("/spelling_bee", spelling_bee::create_router())
+}
 
 pub fn create_router() -> Router<SharedAppState> {
-    Router::new().route("/search", get(search_route))
+    api_routes()
+        .into_router()
+        .route("/openapi.json", get(openapi::openapi_route))
+        .route("/docs", get(openapi::docs_route))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 struct SearchQuery {
+    /// Word prefix, case-insensitive, required.
     pub q: Option<String>,
+    /// Words to skip, for the next pages.
+    pub offset: Option<usize>,
+    /// Words in the page, 1000 at most.
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct SearchPage {
+    words: Vec<String>,
+    /// Words starting with the query, on every page.
+    total: usize,
+    /// Offset of the next page, `null` on the last one.
+    next_offset: Option<usize>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Page of the words starting with the query", body = SearchPage),
+        (status = 404, description = "No words found", body = String),
+        (status = 422, description = "Empty query", body = ApiError),
+        (status = 429, description = "Quota used up, retry after `Retry-After` seconds", body = ApiError),
+    ),
+    tag = "words"
+)]
 async fn search_route(
+    _: RateLimit<SearchQuota>,
     State(app_state): State<SharedAppState>,
     Query(query): Query<SearchQuery>,
 ) -> Response {
-    // let mut timer = Timer::new();
-
-    let search: String = query.q.map(|s| s.to_lowercase()).unwrap_or_default();
+    let search: String = stage("parse", || {
+        query.q.map(|s| s.to_lowercase()).unwrap_or_default()
+    });
+    if search.is_empty() {
+        let message = "a prefix of at least one letter is required";
+        return ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, "empty_query", message)
+            .into_response();
+    }
     let search_bytes = search.as_bytes();
-    // timer.add("parsed_query");
+    let offset = query.offset.unwrap_or_default();
+    let limit = query
+        .limit
+        .unwrap_or(SEARCH_PAGE_SIZE)
+        .min(SEARCH_PAGE_SIZE);
 
-    let Some(range) = app_state.words_shortcuts.search_range(search_bytes) else {
+    let snapshot = app_state.words();
+    let Some(range) = snapshot.shortcuts.search_range(search_bytes) else {
         return (StatusCode::NOT_FOUND, "No words found").into_response();
     };
-    let words = if search.len() > 2 {
-        app_state.words_dict.search_range(range, search_bytes)
-    } else {
-        app_state
-            .words_dict
-            .iter_range(range)
-            .map(|b| String::from_utf8_lossy(b).to_string())
-            .collect()
+    let mut total = 0;
+    let words: Vec<String> = stage("search", || {
+        app_state.metrics.observe_solver(SEARCH, || {
+            // The range is exact up to the two letters of the prefix index
+            total = if search.len() > 2 {
+                snapshot
+                    .dict
+                    .search_range(range.clone(), search_bytes)
+                    .count()
+            } else {
+                range.len()
+            };
+            snapshot
+                .dict
+                .search_range(range, search_bytes)
+                .skip(offset)
+                .take(limit)
+                .map(|b| String::from_utf8_lossy(b).to_string())
+                .collect()
+        })
+    });
+    let next_offset =
+        Some(offset.saturating_add(words.len())).filter(|&next| next < total && !words.is_empty());
+    let page = SearchPage {
+        words,
+        total,
+        next_offset,
     };
-    // timer.add("search");
-
-    // let mut res = Json(words).into_response();
-    // timer.add("response_prep");
-    // res.headers_mut()
-    //     .append("server-timing", timer.header_value().parse().unwrap());
-    // res
-    Json(words).into_response()
+    stage("serialize", || Json(page).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+    use utoipa::OpenApi;
+
+    use super::{
+        api_routes, create_router,
+        openapi::{ApiDoc, API_PREFIX},
+        StatusCode,
+    };
+    use crate::{app_state::AppState, config::AppConfig};
+
+    #[test]
+    fn spec_covers_every_route() {
+        let spec = ApiDoc::openapi();
+        for path in api_routes().paths() {
+            let full_path = format!("{}{}", API_PREFIX, path);
+            assert!(
+                spec.paths.paths.contains_key(&full_path),
+                "{} is missing from the OpenAPI spec",
+                full_path
+            );
+        }
+    }
+
+    #[test]
+    fn spec_has_no_unknown_routes() {
+        let paths: Vec<String> = api_routes()
+            .paths()
+            .iter()
+            .map(|path| format!("{}{}", API_PREFIX, path))
+            .collect();
+        for path in ApiDoc::openapi().paths.paths.keys() {
+            assert!(paths.contains(path), "{} is not served by the router", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn search_needs_a_prefix_and_pages_the_words() {
+        let app_state = AppState::new(&AppConfig::for_tests("api_routes"))
+            .unwrap()
+            .shared();
+        let app = create_router().with_state(app_state);
+        let search = |url: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::get(url).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = response.into_body().collect().await.unwrap().to_bytes();
+                (status, serde_json::from_slice(&bytes).unwrap_or_default())
+            }
+        };
+
+        let (status, _) = search("/search?q=").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, first): (_, serde_json::Value) = search("/search?q=Z").await;
+        assert_eq!(status, StatusCode::OK);
+        let words = first["words"].as_array().unwrap();
+        assert_eq!(words.len(), 1000);
+        let total = first["total"].as_u64().unwrap();
+        assert!(total > 1000);
+        assert_eq!(first["next_offset"], 1000);
+        let (_, page): (_, serde_json::Value) = search("/search?q=z&offset=1&limit=2").await;
+        assert_eq!(page["words"].as_array().unwrap()[..], words[1..3]);
+        assert_eq!(page["total"], total);
+        assert_eq!(page["next_offset"], 3);
+        let last = format!("/search?q=z&offset={}", total - 1).leak();
+        let (_, page): (_, serde_json::Value) = search(last).await;
+        assert_eq!(page["words"].as_array().unwrap().len(), 1);
+        assert!(page["next_offset"].is_null());
+        let (_, page): (_, serde_json::Value) = search("/search?q=zebr").await;
+        assert_eq!(page["total"], page["words"].as_array().unwrap().len());
+    }
 }
@@ -0,0 +1,54 @@
+use axum::{response::IntoResponse, Json};
+use maud::{html, Markup};
+use utoipa::OpenApi;
+
+use crate::{
+    accounts::{user, CurrentUser},
+    assets::ASSETS_PREFIX,
+    templates::layout::layout,
+};
+
+pub const API_PREFIX: &str = "/api";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Word Puzzles API"),
+    paths(
+        super::search_route,
+        super::definitions::define_route,
+        super::spelling_bee::solve_simple_route,
+        super::spelling_bee::solve_hinted_route,
+    ),
+    components(schemas(
+        super::SearchPage,
+        super::ApiError,
+        super::api_error::ApiErrorBody,
+        super::definitions::WordDefinition,
+        super::definitions::DefineResponse,
+        super::spelling_bee::SimpleSolveRequest,
+        super::spelling_bee::HintedSolveRequest,
+        super::spelling_bee::SolvedWord,
+        super::spelling_bee::SolveResponse,
+        crate::solution_options::HideInflections,
+    )),
+    tags(
+        (name = "words", description = "Dictionary lookups"),
+        (name = "spelling_bee", description = "Spelling Bee solvers"),
+    )
+)]
+pub struct ApiDoc;
+
+pub async fn openapi_route() -> impl IntoResponse {
+    Json(ApiDoc::openapi())
+}
+
+pub async fn docs_route(current_user: Option<CurrentUser>) -> Markup {
+    let template = html!(
+        h1 { "Word Puzzles API" };
+        div id="api-docs" data-spec-url={(API_PREFIX) "/openapi.json"} {
+            "Loading specification..."
+        }
+        script src={(ASSETS_PREFIX) "/api_docs.js"} {};
+    );
+    layout(template, Some("Word Puzzles API"), user(&current_user))
+}
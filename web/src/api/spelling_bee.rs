@@ -0,0 +1,196 @@
+use axum::{
+    extract::{rejection::JsonRejection, DefaultBodyLimit, State},
+    http::StatusCode,
+    routing::post,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::Span;
+use utoipa::ToSchema;
+use words::spelling_bee::{self, normalize_letters};
+
+use super::{ApiError, ApiRouter};
+use crate::{
+    app_state::SharedAppState,
+    rate_limit::{RateLimit, SolveQuota},
+    routes::games::{spelling_bee::solve, spelling_bee_permalink::SpellingBeePermalink},
+    solution_options::HideInflections,
+    telemetry::server_timing::stage_async,
+    templates::spelling_bee::ContainerGameMode,
+};
+
+/// Letters and hints fit in far less.
+const SOLVE_BODY_LIMIT: usize = 16 * 1024;
+/// Longer solves are answered with `503`, the solver finishes on its own thread.
+const SOLVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn create_router() -> ApiRouter {
+    ApiRouter::new()
+        .route(
+            "/simple",
+            post(solve_simple_route).layer(DefaultBodyLimit::max(SOLVE_BODY_LIMIT)),
+        )
+        .route(
+            "/hinted",
+            post(solve_hinted_route).layer(DefaultBodyLimit::max(SOLVE_BODY_LIMIT)),
+        )
+}
+
+/// Solves on a blocking thread, so a slow puzzle doesn't hold up the others
+/// and can be given up on.
+async fn solve_in_time(
+    app_state: &SharedAppState,
+    permalink: SpellingBeePermalink,
+    mode: ContainerGameMode,
+) -> Result<Vec<spelling_bee::SolvedWord>, ApiError> {
+    let app_state = app_state.clone();
+    let span = Span::current();
+    let task =
+        tokio::task::spawn_blocking(move || span.in_scope(|| solve(&app_state, &permalink, mode)));
+    match stage_async("solve", tokio::time::timeout(SOLVE_TIMEOUT, task)).await {
+        Ok(Ok(solved)) => Ok(solved?),
+        Ok(Err(err)) => Err(anyhow::Error::from(err).into()),
+        Err(_) => Err(ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "timeout",
+            format!("not solved within {} seconds", SOLVE_TIMEOUT.as_secs()),
+        )),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SimpleSolveRequest {
+    /// First letter is the required one.
+    #[schema(example = "zwieslt")]
+    pub letters: String,
+    /// Inflected words to leave out of `words`.
+    #[serde(default)]
+    pub hide: HideInflections,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct HintedSolveRequest {
+    /// First letter is the required one.
+    pub letters: String,
+    /// Letter/length matrix as shown on the hints page.
+    pub letter_matrix: Option<String>,
+    /// Two letter list as shown on the hints page.
+    pub letter_list: Option<String>,
+    /// Inflected words to leave out of `words`.
+    #[serde(default)]
+    pub hide: HideInflections,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SolvedWord {
+    pub word: String,
+    pub score: usize,
+    pub pangram: bool,
+    /// Base form, the word itself when it is not an inflection.
+    pub base: String,
+    pub is_inflection: bool,
+    /// One of `plural`, `past`, `progressive`, `comparative`, `superlative`.
+    pub inflection: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SolveResponse {
+    pub words: Vec<SolvedWord>,
+    pub pangrams: Vec<String>,
+    /// Score of the whole solution, hidden words included.
+    pub total_score: usize,
+}
+
+impl SolveResponse {
+    fn new(
+        solved: Vec<spelling_bee::SolvedWord>,
+        app_state: &SharedAppState,
+        hide: HideInflections,
+    ) -> Self {
+        let snapshot = app_state.words();
+        let total_score = solved.iter().map(|w| w.score).sum();
+        let pangrams = solved
+            .iter()
+            .filter(|w| w.pangram)
+            .map(|w| w.word.clone())
+            .collect();
+        let words = solved
+            .into_iter()
+            .filter_map(|solved| {
+                let lemma = app_state.lemmatizer.lemma(&solved.word, &snapshot.dict);
+                let inflection = lemma.as_ref().map(|l| l.inflection);
+                if hide.hides(inflection) {
+                    return None;
+                }
+                Some(SolvedWord {
+                    base: lemma.map_or_else(|| solved.word.clone(), |l| l.base),
+                    is_inflection: inflection.is_some(),
+                    inflection: inflection.map(|i| i.as_str().to_owned()),
+                    word: solved.word,
+                    score: solved.score,
+                    pangram: solved.pangram,
+                })
+            })
+            .collect();
+        Self {
+            words,
+            pangrams,
+            total_score,
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/spelling_bee/simple",
+    request_body = SimpleSolveRequest,
+    responses(
+        (status = 200, description = "Words found for the letters", body = SolveResponse),
+        (status = 413, description = "Body is too large", body = ApiError),
+        (status = 415, description = "Body is not JSON", body = ApiError),
+        (status = 422, description = "Invalid letters or body", body = ApiError),
+        (status = 429, description = "Quota used up, retry after `Retry-After` seconds", body = ApiError),
+        (status = 503, description = "Puzzle took too long to solve", body = ApiError),
+    ),
+    tag = "spelling_bee"
+)]
+async fn solve_simple_route(
+    _: RateLimit<SolveQuota>,
+    State(app_state): State<SharedAppState>,
+    body: Result<Json<SimpleSolveRequest>, JsonRejection>,
+) -> Result<Json<SolveResponse>, ApiError> {
+    let Json(data) = body?;
+    let permalink = SpellingBeePermalink::from_hints(&normalize_letters(&data.letters), None, None);
+    let solved = solve_in_time(&app_state, permalink, ContainerGameMode::Simple).await?;
+    Ok(Json(SolveResponse::new(solved, &app_state, data.hide)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/spelling_bee/hinted",
+    request_body = HintedSolveRequest,
+    responses(
+        (status = 200, description = "Words found for the letters and hints", body = SolveResponse),
+        (status = 413, description = "Body is too large", body = ApiError),
+        (status = 415, description = "Body is not JSON", body = ApiError),
+        (status = 422, description = "Invalid letters or body", body = ApiError),
+        (status = 429, description = "Quota used up, retry after `Retry-After` seconds", body = ApiError),
+        (status = 503, description = "Puzzle took too long to solve", body = ApiError),
+    ),
+    tag = "spelling_bee"
+)]
+async fn solve_hinted_route(
+    _: RateLimit<SolveQuota>,
+    State(app_state): State<SharedAppState>,
+    body: Result<Json<HintedSolveRequest>, JsonRejection>,
+) -> Result<Json<SolveResponse>, ApiError> {
+    let Json(data) = body?;
+    let permalink = SpellingBeePermalink::from_hints(
+        &normalize_letters(&data.letters),
+        data.letter_matrix.as_deref(),
+        data.letter_list.as_deref(),
+    );
+    let solved = solve_in_time(&app_state, permalink, ContainerGameMode::Hinted).await?;
+    Ok(Json(SolveResponse::new(solved, &app_state, data.hide)))
+}
@@ -1,33 +1,298 @@
-use words::{WordsDict, WordsShortcuts};
+use anyhow::{Context, Result};
+use arc_swap::ArcSwap;
+use ocr::HiveReader;
+use words::{
+    words_overlay, Definitions, Frequencies, Lemmatizer, WordsDict, WordsOverlay, WordsShortcuts,
+};
 
-use crate::assets::AssetsMetadataStore;
-use std::sync::Arc;
+use crate::{
+    assets::AssetsMetadataStore,
+    config::AppConfig,
+    daily::Daily,
+    metrics::Metrics,
+    plays::Plays,
+    rate_limit::RateLimiter,
+    rooms::Rooms,
+    solve_cache::{SolveCache, SolveCacheCounters, SOLVE_CACHE_BYTES},
+    storage::{self, Storage},
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
-#[derive(Debug, Clone)]
+const ALLOW_FILE: &str = "allow.txt";
+const BLOCK_FILE: &str = "block.txt";
+
+/// Dictionary with its indexes, swapped as a whole on reload.
+#[derive(Debug)]
+pub struct Words {
+    pub dict: WordsDict,
+    pub shortcuts: WordsShortcuts,
+    /// Solutions found with this dictionary.
+    pub solutions: SolveCache,
+}
+
+impl Words {
+    /// `counters` carry on from the previous snapshot.
+    pub fn new(dict: WordsDict, counters: SolveCacheCounters) -> Self {
+        let shortcuts = WordsShortcuts::new(&dict);
+        Self {
+            dict,
+            shortcuts,
+            solutions: SolveCache::new(SOLVE_CACHE_BYTES, counters),
+        }
+    }
+}
+
+/// What the effective dictionary is resolved from.
+#[derive(Debug)]
+struct WordsSources {
+    base: WordsDict,
+    overlay: WordsOverlay,
+}
+
+#[derive(Debug)]
 pub struct AppState {
     pub is_dev: bool,
     pub assets_metadata: AssetsMetadataStore,
-    pub words_dict: WordsDict,
-    pub words_shortcuts: WordsShortcuts,
+    pub admin_token: Option<String>,
+    pub words_dict_path: Option<PathBuf>,
+    pub words_overlay_dir: PathBuf,
+    pub storage: Box<dyn Storage>,
+    pub definitions: Option<Definitions>,
+    pub lemmatizer: Lemmatizer,
+    /// Empty when no frequencies file is configured.
+    pub frequencies: Frequencies,
+    pub hive_reader: HiveReader,
+    pub rooms: Rooms,
+    pub plays: Plays,
+    pub daily: Daily,
+    pub metrics: Metrics,
+    pub rate_limiter: RateLimiter,
+    /// Locked for the whole rebuild, so concurrent edits and reloads don't lose each other.
+    sources: Mutex<WordsSources>,
+    words: ArcSwap<Words>,
 }
 
 pub type SharedAppState = Arc<AppState>;
 
 impl AppState {
-    pub fn new(is_dev: bool) -> Self {
-        let assets_metadata = AssetsMetadataStore::new(is_dev);
-        let words_dict = WordsDict::load();
-        let words_shortcuts = WordsShortcuts::new(&words_dict);
+    pub fn new(config: &AppConfig) -> Result<Self> {
+        let assets_metadata = AssetsMetadataStore::new(config.is_dev);
+        let metrics = Metrics::default();
+        let start = Instant::now();
+        let sources = WordsSources {
+            base: Self::load_dict(config.words_dict_path.as_ref())?,
+            overlay: load_overlay(&config.words_overlay_dir)?,
+        };
+        let words = Words::new(
+            sources.overlay.apply(&sources.base),
+            metrics.solve_cache_counters(),
+        );
+        metrics.observe_dictionary_load(start.elapsed());
+        let storage = storage::open(&config.database_path)?;
+        let definitions = config
+            .definitions_path
+            .as_ref()
+            .map(Definitions::open)
+            .transpose()?;
+        let frequencies = config
+            .frequencies_path
+            .as_ref()
+            .map(Frequencies::open)
+            .transpose()?
+            .unwrap_or_default();
 
-        Self {
-            is_dev,
+        Ok(Self {
+            is_dev: config.is_dev,
             assets_metadata,
-            words_dict,
-            words_shortcuts,
-        }
+            admin_token: config.admin_token.clone(),
+            words_dict_path: config.words_dict_path.clone(),
+            words_overlay_dir: config.words_overlay_dir.clone(),
+            storage,
+            definitions,
+            lemmatizer: Lemmatizer::load(),
+            frequencies,
+            hive_reader: HiveReader::load(),
+            rooms: Rooms::default(),
+            plays: Plays::default(),
+            daily: Daily::new(&config.daily_salt, config.daily_timezone),
+            metrics,
+            rate_limiter: RateLimiter::new(config),
+            sources: Mutex::new(sources),
+            words: ArcSwap::from_pointee(words),
+        })
     }
 
     pub fn shared(self) -> SharedAppState {
         Arc::new(self)
     }
+
+    /// Runs `query` on a blocking thread, SQLite would hold up the async workers.
+    pub async fn with_storage<T: Send + 'static>(
+        self: &Arc<Self>,
+        query: impl FnOnce(&dyn Storage) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let app_state = Arc::clone(self);
+        tokio::task::spawn_blocking(move || query(app_state.storage.as_ref())).await?
+    }
+
+    /// Current snapshot of the dictionary.
+    /// Hold on to it for the whole request, so a reload in between doesn't mix the data.
+    pub fn words(&self) -> Arc<Words> {
+        self.words.load_full()
+    }
+
+    /// Reads the dictionary and overlays again and swaps them in, requests in flight keep the old one.
+    pub async fn reload_words(self: &Arc<Self>) -> Result<usize> {
+        self.rebuild_words(|app_state, sources| {
+            let start = Instant::now();
+            sources.base = Self::load_dict(app_state.words_dict_path.as_ref())?;
+            sources.overlay = load_overlay(&app_state.words_overlay_dir)?;
+            let size = app_state.store_words(sources);
+            app_state.metrics.observe_dictionary_load(start.elapsed());
+            Ok(size)
+        })
+        .await
+    }
+
+    /// Runs `rebuild` with the sources locked on a blocking thread,
+    /// reading and encoding the whole dictionary would hold up the async workers.
+    async fn rebuild_words(
+        self: &Arc<Self>,
+        rebuild: impl FnOnce(&Self, &mut WordsSources) -> Result<usize> + Send + 'static,
+    ) -> Result<usize> {
+        let app_state = Arc::clone(self);
+        tokio::task::spawn_blocking(move || {
+            let mut sources = app_state.sources.lock().unwrap();
+            rebuild(&app_state, &mut sources)
+        })
+        .await?
+    }
+
+    pub fn words_overlay(&self) -> WordsOverlay {
+        self.sources.lock().unwrap().overlay.clone()
+    }
+
+    /// Saves the overlay files and applies them to the current dictionary.
+    pub async fn update_words_overlay(self: &Arc<Self>, overlay: WordsOverlay) -> Result<usize> {
+        self.rebuild_words(|app_state, sources| {
+            save_overlay(&app_state.words_overlay_dir, &overlay)?;
+            sources.overlay = overlay;
+            Ok(app_state.store_words(sources))
+        })
+        .await
+    }
+
+    fn store_words(&self, sources: &WordsSources) -> usize {
+        let dict = sources.overlay.apply(&sources.base);
+        let size = dict.size();
+        let words = Words::new(dict, self.metrics.solve_cache_counters());
+        self.words.store(Arc::new(words));
+        size
+    }
+
+    fn load_dict(path: Option<&PathBuf>) -> Result<WordsDict> {
+        match path {
+            Some(path) => WordsDict::open(path),
+            None => Ok(WordsDict::load()),
+        }
+    }
+}
+
+/// Missing files are empty lists.
+fn load_overlay(dir: &Path) -> Result<WordsOverlay> {
+    let read = |name: &str| {
+        let path = dir.join(name);
+        match fs::read_to_string(&path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(String::new()),
+            result => result.with_context(|| format!("unable to read {}", path.display())),
+        }
+    };
+    Ok(WordsOverlay::new(&read(ALLOW_FILE)?, &read(BLOCK_FILE)?))
+}
+
+fn save_overlay(dir: &Path, overlay: &WordsOverlay) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("unable to create {}", dir.display()))?;
+    let write = |name: &str, text: String| {
+        // Written aside and renamed, so a reader never sees half of the file
+        let path = dir.join(name);
+        let tmp_path = path.with_extension("txt.tmp");
+        fs::write(&tmp_path, text)
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .with_context(|| format!("unable to write {}", path.display()))
+    };
+    write(ALLOW_FILE, words_overlay::list_to_text(overlay.allow()))?;
+    write(BLOCK_FILE, words_overlay::list_to_text(overlay.block()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[tokio::test]
+    async fn reload_keeps_taken_snapshot() {
+        let dir = std::env::temp_dir().join(format!("app_state_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("words.txt");
+        fs::write(&path, "hello\nhelp\n").unwrap();
+        let config = AppConfig {
+            words_dict_path: Some(path.clone()),
+            words_overlay_dir: dir.join("overlays"),
+            ..AppConfig::for_tests("app_state")
+        };
+        let app_state = AppState::new(&config).unwrap().shared();
+
+        let in_flight = app_state.words();
+        fs::write(&path, "hello\nhelp\nhelium\n").unwrap();
+        assert_eq!(app_state.reload_words().await.unwrap(), 3);
+
+        assert_eq!(in_flight.dict.size(), 2);
+        assert_eq!(app_state.words().dict.size(), 3);
+        assert_eq!(app_state.words().shortcuts.search_range(b"he"), Some(0..3));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn overlay_is_persisted_and_applied() {
+        let dir = std::env::temp_dir().join(format!("app_state_overlay_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("words.txt");
+        fs::write(&path, "hello\nhelp\n").unwrap();
+        let config = AppConfig {
+            words_dict_path: Some(path.clone()),
+            words_overlay_dir: dir.join("overlays"),
+            ..AppConfig::for_tests("app_state")
+        };
+        let app_state = AppState::new(&config).unwrap().shared();
+
+        let overlay = WordsOverlay::new("helium\n", "help\n");
+        assert_eq!(
+            app_state
+                .update_words_overlay(overlay.clone())
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            app_state
+                .words()
+                .dict
+                .search_range(0..2, b"he")
+                .collect::<Vec<_>>(),
+            vec![&b"helium"[..], b"hello"]
+        );
+
+        // Base changes keep the overlay on top
+        fs::write(&path, "hello\nhelp\nhelm\n").unwrap();
+        assert_eq!(app_state.reload_words().await.unwrap(), 3);
+        assert_eq!(AppState::new(&config).unwrap().words_overlay(), overlay);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
 }
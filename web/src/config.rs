@@ -0,0 +1,140 @@
+use chrono_tz::Tz;
+use std::{env, path::PathBuf};
+
+use crate::rate_limit::ApiQuotas;
+
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub is_dev: bool,
+    pub host: String,
+    pub port: usize,
+    /// Word list or binary dictionary, bundled one is used when missing.
+    pub words_dict_path: Option<PathBuf>,
+    /// Directory with `allow.txt` and `block.txt` applied on top of the dictionary.
+    pub words_overlay_dir: PathBuf,
+    /// Definitions file built with `word_puzzles dict definitions`, lookups are off when missing.
+    pub definitions_path: Option<PathBuf>,
+    /// Word counts for sorting solutions by frequency, alphabetical order is used when missing.
+    pub frequencies_path: Option<PathBuf>,
+    /// SQLite database for puzzle history, `:memory:` keeps it in memory.
+    pub database_path: PathBuf,
+    /// Secret for the admin endpoints, they are disabled when missing.
+    pub admin_token: Option<String>,
+    /// Secret mixed with the date to pick the daily puzzles, so they can't be worked out ahead.
+    /// Only development runs without it.
+    pub daily_salt: String,
+    /// Timezone whose midnight starts a new daily puzzle.
+    pub daily_timezone: Tz,
+    /// OTLP/HTTP collector the request traces are sent to, like `http://localhost:4318`.
+    pub otlp_endpoint: Option<String>,
+    /// File the request traces are appended to as OTLP JSON lines.
+    pub otlp_file: Option<PathBuf>,
+    /// Private address `/metrics` is served on, like `127.0.0.1:9090`.
+    /// Otherwise metrics are only served to admins.
+    pub metrics_addr: Option<String>,
+    /// Requests per minute allowed to a client in each group of API routes.
+    pub api_quotas: ApiQuotas,
+    /// Keys raising the API quotas of the clients sending them.
+    pub api_keys: Vec<String>,
+    /// Client addresses are taken from `X-Forwarded-For`, set when behind a reverse proxy.
+    pub trust_proxy: bool,
+}
+
+impl AppConfig {
+    pub fn from_env() -> Self {
+        let is_dev = env::var("APPLICATION_MODE").is_ok_and(|e| e == "development");
+
+        // Setup application bind addr
+        let host = env::var("APPLICATION_HOST")
+            .ok()
+            .filter(|h| h != "localhost")
+            .unwrap_or("127.0.0.1".to_owned());
+        let port: usize = env::var("APPLICATION_PORT")
+            .unwrap_or_else(|_| "8080".to_owned())
+            .parse()
+            .expect("PORT must be a number");
+
+        let words_dict_path = env::var("WORDS_DICT_PATH").ok().map(PathBuf::from);
+        let words_overlay_dir = env::var("WORDS_OVERLAY_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("overlays"));
+        let definitions_path = env::var("DEFINITIONS_PATH").ok().map(PathBuf::from);
+        let frequencies_path = env::var("FREQUENCIES_PATH").ok().map(PathBuf::from);
+        let database_path = env::var("DATABASE_PATH")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("word_puzzles.db"));
+        let admin_token = env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+        let daily_salt = env::var("DAILY_SALT").unwrap_or_default();
+        assert!(
+            is_dev || !daily_salt.is_empty(),
+            "DAILY_SALT must be set in production, the daily puzzles could be worked out ahead"
+        );
+        let daily_timezone: Tz = env::var("DAILY_TIMEZONE")
+            .unwrap_or_else(|_| "UTC".to_owned())
+            .parse()
+            .expect("DAILY_TIMEZONE must be an IANA timezone name");
+        let otlp_endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+            .ok()
+            .filter(|e| !e.is_empty());
+        let otlp_file = env::var("OTLP_TRACES_FILE").ok().map(PathBuf::from);
+        let metrics_addr = env::var("METRICS_ADDR").ok().filter(|a| !a.is_empty());
+        let api_quotas = env::var("API_RATE_LIMITS")
+            .map(|quotas| {
+                ApiQuotas::parse(&quotas).expect("API_RATE_LIMITS must be like search=60")
+            })
+            .unwrap_or_default();
+        let api_keys = env::var("API_KEYS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(str::to_owned)
+            .collect();
+        let trust_proxy = env::var("TRUST_PROXY").is_ok_and(|t| t == "true" || t == "1");
+
+        Self {
+            is_dev,
+            host,
+            port,
+            words_dict_path,
+            words_overlay_dir,
+            definitions_path,
+            frequencies_path,
+            database_path,
+            admin_token,
+            daily_salt,
+            daily_timezone,
+            otlp_endpoint,
+            otlp_file,
+            metrics_addr,
+            api_quotas,
+            api_keys,
+            trust_proxy,
+        }
+    }
+
+    /// Development config of the route tests, with an in-memory database and no dictionary files.
+    /// `name` gives each test module its own missing overlay directory.
+    #[cfg(test)]
+    pub fn for_tests(name: &str) -> Self {
+        Self {
+            is_dev: true,
+            host: "127.0.0.1".to_owned(),
+            port: 0,
+            words_dict_path: None,
+            words_overlay_dir: env::temp_dir().join(format!("{}_no_overlays", name)),
+            definitions_path: None,
+            frequencies_path: None,
+            database_path: crate::storage::MEMORY_PATH.into(),
+            admin_token: None,
+            daily_salt: String::new(),
+            daily_timezone: chrono_tz::UTC,
+            otlp_endpoint: None,
+            otlp_file: None,
+            metrics_addr: None,
+            api_quotas: ApiQuotas::default(),
+            api_keys: vec![],
+            trust_proxy: false,
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::{collections::HashMap, sync::Mutex};
+
+use crate::{
+    app_state::Words,
+    games::{DailyPuzzle, Game},
+};
+use words::dict_format::checksum;
+
+/// Days of puzzles kept, for every game.
+const MAX_CACHED_DAYS: usize = 400;
+
+/// Puzzles of the day, generated from the date and a secret salt instead of stored.
+/// A generated puzzle is cached, so a dictionary reload doesn't change a day already served.
+#[derive(Debug)]
+pub struct Daily {
+    salt: String,
+    timezone: Tz,
+    puzzles: Mutex<HashMap<(&'static str, NaiveDate), Option<DailyPuzzle>>>,
+}
+
+impl Daily {
+    pub fn new(salt: &str, timezone: Tz) -> Self {
+        Self {
+            salt: salt.to_owned(),
+            timezone,
+            puzzles: Mutex::default(),
+        }
+    }
+
+    /// Date in the configured timezone, a new puzzle starts at its midnight.
+    pub fn today(&self) -> NaiveDate {
+        Utc::now().with_timezone(&self.timezone).date_naive()
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Puzzle of the game for the date, `None` for games without daily puzzles.
+    pub fn puzzle(&self, game: &dyn Game, words: &Words, date: NaiveDate) -> Option<DailyPuzzle> {
+        let key = (game.id(), date);
+        if let Some(puzzle) = self.puzzles.lock().unwrap().get(&key) {
+            return puzzle.clone();
+        }
+        // Generated unlocked, a concurrent request for the same day gets the same puzzle anyway
+        let puzzle = game.daily(words, self.seed(game.id(), date));
+        let mut puzzles = self.puzzles.lock().unwrap();
+        if puzzles.len() >= MAX_CACHED_DAYS {
+            let oldest = puzzles.keys().min_by_key(|(_, date)| *date).copied();
+            if let Some(oldest) = oldest {
+                puzzles.remove(&oldest);
+            }
+        }
+        puzzles.insert(key, puzzle.clone());
+        puzzle
+    }
+
+    /// Hash of the salt, game and date.
+    fn seed(&self, game_id: &str, date: NaiveDate) -> u64 {
+        checksum(format!("{}:{}:{}", self.salt, game_id, date).as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{metrics::Metrics, routes::games::spelling_bee::SpellingBeeGame};
+    use words::WordsDict;
+
+    #[test]
+    fn puzzles_depend_on_date_and_salt() {
+        let words = Words::new(WordsDict::load(), Metrics::default().solve_cache_counters());
+        let daily = Daily::new("secret", chrono_tz::UTC);
+        let day = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let puzzle = daily.puzzle(&SpellingBeeGame, &words, day).unwrap();
+        assert_eq!(puzzle.name.len(), 7);
+        assert!(puzzle.links[0].1.ends_with(&puzzle.name));
+
+        let again = Daily::new("secret", chrono_tz::UTC);
+        assert_eq!(
+            again.puzzle(&SpellingBeeGame, &words, day),
+            Some(puzzle.clone())
+        );
+        let next_day = day.succ_opt().unwrap();
+        assert_ne!(
+            again.puzzle(&SpellingBeeGame, &words, next_day),
+            Some(puzzle.clone())
+        );
+        let other_salt = Daily::new("other", chrono_tz::UTC);
+        assert_ne!(
+            other_salt.seed("spelling_bee", day),
+            daily.seed("spelling_bee", day)
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use axum::Router;
+
+use crate::{
+    app_state::{SharedAppState, Words},
+    routes::games::spelling_bee::SpellingBeeGame,
+};
+
+pub const GAMES_PATH: &str = "/games";
+
+/// Every game served by the app, in the order they are listed.
+pub static GAMES: &[&dyn Game] = &[&SpellingBeeGame];
+
+/// Puzzle of the day of a game, the same for everyone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyPuzzle {
+    /// Shown as the puzzle, like the letters of a Spelling Bee.
+    pub name: String,
+    /// Pages of the puzzle, by their link text.
+    pub links: Vec<(&'static str, String)>,
+}
+
+/// A puzzle with its own page, listed on the landing page and in the navigation.
+pub trait Game: Sync {
+    /// Path segment under [`GAMES_PATH`].
+    fn id(&self) -> &'static str;
+
+    fn name(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// Emoji shown next to the name.
+    fn icon(&self) -> &'static str;
+
+    /// Routes nested under [`Game::url`], `/` is the game page.
+    fn router(&self) -> Router<SharedAppState>;
+
+    /// Puzzle picked by `seed`, the same seed gives the same puzzle.
+    /// Games without daily puzzles keep the default.
+    fn daily(&self, _words: &Words, _seed: u64) -> Option<DailyPuzzle> {
+        None
+    }
+
+    fn url(&self) -> String {
+        format!("{}/{}", GAMES_PATH, self.id())
+    }
+}
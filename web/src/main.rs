@@ -1,55 +1,144 @@
-use axum::Router;
+use axum::{
+    middleware::{from_fn, from_fn_with_state},
+    Router,
+};
 use dotenv::dotenv;
-use env_logger::Env;
-use std::env;
+use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tower_http::{compression::CompressionLayer, cors::CorsLayer};
 
+mod accounts;
+mod admin;
 mod api;
 mod app_state;
 mod assets;
+mod config;
+mod daily;
+mod games;
+mod metrics;
+mod plays;
+mod rate_limit;
+mod reload;
+mod rooms;
 mod routes;
+mod solution_options;
+mod solve_cache;
+mod storage;
+mod streaks;
+mod telemetry;
 mod templates;
 pub mod utils;
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
-    let is_dev = env::var("APPLICATION_MODE").is_ok_and(|e| e == "development");
+    let config = config::AppConfig::from_env();
+    let _telemetry = telemetry::init(&config).expect("Unable to set up logs and traces");
     log::info!(
         "Application mode is {}",
-        if is_dev { "development" } else { "production" }
+        if config.is_dev {
+            "development"
+        } else {
+            "production"
+        }
     );
 
-    // Setup application bind addr
-    let host = env::var("APPLICATION_HOST")
-        .ok()
-        .filter(|h| h != "localhost")
-        .unwrap_or("127.0.0.1".to_owned());
-    let port: usize = env::var("APPLICATION_PORT")
-        .unwrap_or_else(|_| "8080".to_owned())
-        .parse()
-        .expect("PORT must be a number");
-
-    let addr = format!("{}:{}", host, port);
+    if config.daily_salt.is_empty() {
+        log::warn!("DAILY_SALT is empty, the daily puzzles can be worked out ahead");
+    }
+
+    let addr = format!("{}:{}", config.host, config.port);
     let listener = TcpListener::bind(&addr).await.unwrap();
-    log::info!("Starting on: http://{}", &addr);
+    log::info!("Starting on: http://{}", addr);
+
+    let app_state = app_state::AppState::new(&config)
+        .expect("Unable to load the dictionary, definitions, frequencies or database")
+        .shared();
+    log::info!("Dictionary has {} words", app_state.words().dict.size());
+    if let Some(definitions) = &app_state.definitions {
+        log::info!("Definitions cover {} words", definitions.len());
+    }
+    if !app_state.frequencies.is_empty() {
+        log::info!("Frequencies cover {} words", app_state.frequencies.len());
+    }
+
+    // Dictionary can be reloaded on file change, SIGHUP or through the admin endpoint
+    let _watcher = app_state.words_dict_path.as_ref().and_then(|path| {
+        reload::watch_dictionary(app_state.clone())
+            .inspect_err(|err| log::error!("Unable to watch {}: {}", path.display(), err))
+            .ok()
+    });
+    reload::listen_sighup(app_state.clone());
 
-    let app_state = app_state::AppState::new(is_dev).shared();
     let compression = CompressionLayer::new();
 
     let web_router = routes::create_router()
         .with_state(app_state.clone())
         .layer(compression.clone());
 
+    let admin_router = admin::create_router().with_state(app_state.clone());
+
+    let accounts_router = accounts::create_router()
+        .with_state(app_state.clone())
+        .layer(compression.clone());
+
+    // Scraped on a private address, when there is one
+    if let Some(metrics_addr) = &config.metrics_addr {
+        let metrics_listener = TcpListener::bind(metrics_addr).await.unwrap();
+        log::info!("Metrics on: http://{}/metrics", metrics_addr);
+        let metrics_router = metrics::create_router().with_state(app_state.clone());
+        tokio::spawn(async move {
+            if let Err(err) = axum::serve(metrics_listener, metrics_router).await {
+                log::error!("Metrics server stopped: {}", err);
+            }
+        });
+    }
+
     let cors = CorsLayer::permissive();
     let api_router = api::create_router()
-        .with_state(app_state)
+        .with_state(app_state.clone())
         .layer(cors)
         .layer(compression);
 
-    let app = Router::new().nest("/", web_router).nest("/api", api_router);
+    let app = Router::new()
+        .nest("/", web_router)
+        .nest("/api", api_router)
+        .nest("/admin", admin_router)
+        .nest(accounts::ACCOUNT_PATH, accounts_router)
+        .route_layer(from_fn_with_state(app_state, metrics::track_request))
+        .layer(from_fn(telemetry::trace_request));
+
+    // Stopped gracefully, so the traces of the last requests are sent
+    // Peer addresses are kept for the rate limits
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .unwrap();
+    log::info!("Stopped");
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(err) => {
+                log::error!("Unable to listen for SIGTERM: {}", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
-    axum::serve(listener, app).await.unwrap();
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {},
+        _ = terminate => {},
+    }
 }
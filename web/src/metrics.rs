@@ -0,0 +1,227 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::header::CONTENT_TYPE,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use prometheus::{
+    exponential_buckets, Encoder, Gauge, HistogramOpts, HistogramVec, IntCounter, IntCounterVec,
+    IntGauge, Opts, Registry, TextEncoder, TEXT_FORMAT,
+};
+use std::time::{Duration, Instant};
+
+use crate::{
+    app_state::{SharedAppState, Words},
+    solve_cache::SolveCacheCounters,
+};
+
+/// Requests, solvers and the dictionary, scraped by Prometheus in its text format.
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+    solver_duration: HistogramVec,
+    solver_results: HistogramVec,
+    dictionary_words: IntGauge,
+    dictionary_load: Gauge,
+    cache_hits: IntCounter,
+    cache_misses: IntCounter,
+    cache_entries: IntGauge,
+    cache_bytes: IntGauge,
+    cache_capacity_bytes: IntGauge,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new().expect("metrics have valid names and are registered once")
+    }
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new();
+        let requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "Requests by route and status"),
+            &["method", "route", "status"],
+        )?;
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "Time to answer, by route"),
+            &["method", "route"],
+        )?;
+        let solver_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "solver_duration_seconds",
+                "Time the solvers run by game, cached solutions aside",
+            ),
+            &["game"],
+        )?;
+        let solver_results = HistogramVec::new(
+            HistogramOpts::new("solver_result_words", "Words found by the solvers, by game")
+                .buckets(exponential_buckets(1.0, 4.0, 10)?),
+            &["game"],
+        )?;
+        let dictionary_words = IntGauge::new("dictionary_words", "Words in the dictionary")?;
+        let dictionary_load = Gauge::new(
+            "dictionary_load_seconds",
+            "Time the last dictionary load or reload took",
+        )?;
+        let cache_hits =
+            IntCounter::new("solve_cache_hits_total", "Solutions served from the cache")?;
+        let cache_misses = IntCounter::new(
+            "solve_cache_misses_total",
+            "Solutions missing from the cache",
+        )?;
+        let cache_gauge = |name: &str, help: &str| IntGauge::new(name, help);
+        let cache_entries = cache_gauge("solve_cache_entries", "Solutions in the cache")?;
+        let cache_bytes = cache_gauge("solve_cache_bytes", "Bytes taken by the cached solutions")?;
+        let cache_capacity_bytes = cache_gauge(
+            "solve_cache_capacity_bytes",
+            "Bytes the cache is bounded to",
+        )?;
+
+        registry.register(Box::new(requests.clone()))?;
+        registry.register(Box::new(request_duration.clone()))?;
+        registry.register(Box::new(solver_duration.clone()))?;
+        registry.register(Box::new(solver_results.clone()))?;
+        registry.register(Box::new(dictionary_words.clone()))?;
+        registry.register(Box::new(dictionary_load.clone()))?;
+        registry.register(Box::new(cache_hits.clone()))?;
+        registry.register(Box::new(cache_misses.clone()))?;
+        for gauge in [&cache_entries, &cache_bytes, &cache_capacity_bytes] {
+            registry.register(Box::new(gauge.clone()))?;
+        }
+
+        Ok(Self {
+            registry,
+            requests,
+            request_duration,
+            solver_duration,
+            solver_results,
+            dictionary_words,
+            dictionary_load,
+            cache_hits,
+            cache_misses,
+            cache_entries,
+            cache_bytes,
+            cache_capacity_bytes,
+        })
+    }
+
+    /// `route` is the pattern the request matched, so ids in paths don't make new series.
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        self.requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .inc();
+        self.request_duration
+            .with_label_values(&[method, route])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Runs a solver of `game`, timing it and counting the words it finds.
+    pub fn observe_solver<T>(&self, game: &str, solve: impl FnOnce() -> Vec<T>) -> Vec<T> {
+        let start = Instant::now();
+        let words = solve();
+        self.solver_duration
+            .with_label_values(&[game])
+            .observe(start.elapsed().as_secs_f64());
+        self.solver_results
+            .with_label_values(&[game])
+            .observe(words.len() as f64);
+        words
+    }
+
+    /// Counted by the solution cache of each dictionary snapshot.
+    pub fn solve_cache_counters(&self) -> SolveCacheCounters {
+        SolveCacheCounters {
+            hits: self.cache_hits.clone(),
+            misses: self.cache_misses.clone(),
+        }
+    }
+
+    pub fn observe_dictionary_load(&self, duration: Duration) {
+        self.dictionary_load.set(duration.as_secs_f64());
+    }
+
+    /// Text exposition of every metric, the dictionary and its cache as they are now.
+    pub fn render(&self, words: &Words) -> String {
+        self.dictionary_words.set(words.dict.size() as i64);
+        let stats = words.solutions.stats();
+        self.cache_entries.set(stats.entries as i64);
+        self.cache_bytes.set(stats.bytes as i64);
+        self.cache_capacity_bytes.set(stats.capacity_bytes as i64);
+
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .expect("metrics are valid");
+        String::from_utf8(buffer).expect("text format is UTF-8")
+    }
+}
+
+/// Served on its own listener, off the public router.
+pub fn create_router() -> Router<SharedAppState> {
+    Router::new().route("/metrics", get(metrics_route))
+}
+
+pub async fn metrics_route(State(app_state): State<SharedAppState>) -> Response {
+    let text = app_state.metrics.render(&app_state.words());
+    ([(CONTENT_TYPE, TEXT_FORMAT)], text).into_response()
+}
+
+/// Counts and times the requests of the routes it is layered on with `route_layer`,
+/// which is where the matched route is known.
+pub async fn track_request(
+    State(app_state): State<SharedAppState>,
+    matched_path: Option<MatchedPath>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let route = matched_path
+        .as_ref()
+        .map_or("unmatched", MatchedPath::as_str);
+    app_state.metrics.observe_request(
+        method.as_str(),
+        route,
+        response.status().as_u16(),
+        start.elapsed(),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve_cache::SolveKey;
+    use words::WordsDict;
+
+    #[test]
+    fn metrics_are_rendered_as_text() {
+        let metrics = Metrics::default();
+        metrics.observe_request("GET", "/api/search", 200, Duration::from_millis(3));
+        let solved = metrics.observe_solver("spelling_bee", || vec!["zest"; 5]);
+        assert_eq!(solved.len(), 5);
+        metrics.observe_dictionary_load(Duration::from_secs(2));
+
+        let words = Words::new(
+            WordsDict::from_text(b"zest\n"),
+            metrics.solve_cache_counters(),
+        );
+        words
+            .solutions
+            .get_or_solve(SolveKey::simple("zestabc"), Vec::new);
+        let text = metrics.render(&words);
+        assert!(text
+            .contains(r#"http_requests_total{method="GET",route="/api/search",status="200"} 1"#));
+        assert!(text.contains(r#"solver_result_words_bucket{game="spelling_bee",le="16"} 1"#));
+        assert!(text.contains("dictionary_words 1\n"));
+        assert!(text.contains("dictionary_load_seconds 2\n"));
+        assert!(text.contains("solve_cache_capacity_bytes 33554432\n"));
+        assert!(text.contains("solve_cache_misses_total 1\n"));
+    }
+}
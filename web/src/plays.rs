@@ -0,0 +1,68 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use words::spelling_bee_play::SpellingBeePlay;
+
+use crate::utils::random_token::random_token;
+
+/// Least recently played games are dropped past this many.
+const MAX_PLAYS: usize = 10_000;
+/// Games not played for this long are dropped.
+const PLAY_IDLE: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub type PlayId = String;
+
+#[derive(Debug)]
+struct PlaySession {
+    play: SpellingBeePlay,
+    last_played: Instant,
+}
+
+/// Games being played, keyed by the id of the play cookie.
+/// They live in memory only, they are gone on restart.
+#[derive(Debug, Default)]
+pub struct Plays {
+    plays: Mutex<HashMap<PlayId, PlaySession>>,
+}
+
+impl Plays {
+    /// Replaces the game of `id`, or starts a session with a new id.
+    pub fn start(&self, id: Option<&str>, play: SpellingBeePlay) -> PlayId {
+        let mut plays = self.plays.lock().unwrap();
+        let now = Instant::now();
+        plays.retain(|_, session| now.duration_since(session.last_played) < PLAY_IDLE);
+        let id = match id {
+            Some(id) if plays.contains_key(id) => id.to_owned(),
+            _ => {
+                if plays.len() >= MAX_PLAYS {
+                    let oldest = plays
+                        .iter()
+                        .min_by_key(|(_, session)| session.last_played)
+                        .map(|(id, _)| id.clone());
+                    if let Some(oldest) = oldest {
+                        plays.remove(&oldest);
+                    }
+                }
+                random_token(16)
+            }
+        };
+        let session = PlaySession {
+            play,
+            last_played: now,
+        };
+        plays.insert(id.clone(), session);
+        id
+    }
+
+    /// Runs `f` on the game of `id`, `None` when there is none.
+    pub fn update<T>(&self, id: &str, f: impl FnOnce(&mut SpellingBeePlay) -> T) -> Option<T> {
+        let mut plays = self.plays.lock().unwrap();
+        let session = plays
+            .get_mut(id)
+            .filter(|session| session.last_played.elapsed() < PLAY_IDLE)?;
+        session.last_played = Instant::now();
+        Some(f(&mut session.play))
+    }
+}
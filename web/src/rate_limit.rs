@@ -0,0 +1,515 @@
+use anyhow::{bail, Context, Result};
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header::RETRY_AFTER, request::Parts, HeaderMap, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::{api::ApiError, app_state::SharedAppState, config::AppConfig};
+
+pub const API_KEY_HEADER: HeaderName = HeaderName::from_static("x-api-key");
+const FORWARDED_FOR_HEADER: HeaderName = HeaderName::from_static("x-forwarded-for");
+/// Requests sent with a valid API key get this many times the quota.
+pub const API_KEY_QUOTA_FACTOR: u32 = 10;
+/// Buckets refill within a minute, full ones are dropped this often.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Past this many buckets a new client takes the place of the one idle for the longest.
+const MAX_BUCKETS: usize = 100_000;
+
+/// Routes sharing a quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteGroup {
+    Search,
+    Solve,
+    Define,
+    /// Logging in and registering, both hash a password.
+    Accounts,
+    /// Creating a room, each one holds a solution.
+    Rooms,
+}
+
+impl RouteGroup {
+    pub const ALL: [Self; 5] = [
+        Self::Search,
+        Self::Solve,
+        Self::Define,
+        Self::Accounts,
+        Self::Rooms,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Solve => "solve",
+            Self::Define => "define",
+            Self::Accounts => "accounts",
+            Self::Rooms => "rooms",
+        }
+    }
+}
+
+/// Requests a client can make per minute in each group, `0` doesn't limit the group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiQuotas {
+    pub search: u32,
+    pub solve: u32,
+    pub define: u32,
+    pub accounts: u32,
+    pub rooms: u32,
+}
+
+impl Default for ApiQuotas {
+    fn default() -> Self {
+        Self {
+            search: 60,
+            solve: 60,
+            define: 120,
+            accounts: 10,
+            rooms: 10,
+        }
+    }
+}
+
+impl ApiQuotas {
+    /// Like `search=30,solve=120`, groups left out keep their default.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut quotas = Self::default();
+        for quota in text.split(',').map(str::trim).filter(|q| !q.is_empty()) {
+            let (name, per_minute) = quota
+                .split_once('=')
+                .with_context(|| format!("{} is not group=requests", quota))?;
+            let per_minute = per_minute
+                .trim()
+                .parse()
+                .with_context(|| format!("{} is not a number of requests", per_minute))?;
+            match RouteGroup::ALL.iter().find(|g| g.name() == name.trim()) {
+                Some(RouteGroup::Search) => quotas.search = per_minute,
+                Some(RouteGroup::Solve) => quotas.solve = per_minute,
+                Some(RouteGroup::Define) => quotas.define = per_minute,
+                Some(RouteGroup::Accounts) => quotas.accounts = per_minute,
+                Some(RouteGroup::Rooms) => quotas.rooms = per_minute,
+                None => bail!("unknown route group {}", name),
+            }
+        }
+        Ok(quotas)
+    }
+
+    pub fn per_minute(&self, group: RouteGroup) -> u32 {
+        match group {
+            RouteGroup::Search => self.search,
+            RouteGroup::Solve => self.solve,
+            RouteGroup::Define => self.define,
+            RouteGroup::Accounts => self.accounts,
+            RouteGroup::Rooms => self.rooms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Client {
+    Ip(IpAddr),
+    ApiKey(String),
+    /// Account logged in to, whatever the address.
+    Username(String),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Tokens come back at `capacity` per minute.
+    fn refill(&mut self, capacity: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * capacity / 60.0).min(capacity);
+        self.updated = now;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    buckets: HashMap<(Client, RouteGroup), Bucket>,
+    swept: Option<Instant>,
+}
+
+/// Token buckets of every client, one per route group.
+#[derive(Debug)]
+pub struct RateLimiter {
+    quotas: ApiQuotas,
+    api_keys: HashSet<String>,
+    trust_proxy: bool,
+    buckets: Mutex<Buckets>,
+    max_buckets: usize,
+}
+
+impl RateLimiter {
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            quotas: config.api_quotas,
+            api_keys: config.api_keys.iter().cloned().collect(),
+            trust_proxy: config.trust_proxy,
+            buckets: Mutex::default(),
+            max_buckets: MAX_BUCKETS,
+        }
+    }
+
+    /// Clients with an API key are counted by key, the others by address.
+    /// Behind a trusted proxy the address is the last one it added to `X-Forwarded-For`.
+    /// IPv6 clients are counted by their /64, the smallest network usually handed out.
+    fn client(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Result<Client, ApiError> {
+        if let Some(key) = headers.get(API_KEY_HEADER) {
+            return match key.to_str() {
+                Ok(key) if self.api_keys.contains(key) => Ok(Client::ApiKey(key.to_owned())),
+                _ => Err(ApiError::new(
+                    StatusCode::UNAUTHORIZED,
+                    "invalid_api_key",
+                    "Unknown API key",
+                )),
+            };
+        }
+        Ok(self.address(headers, peer))
+    }
+
+    fn address(&self, headers: &HeaderMap, peer: Option<IpAddr>) -> Client {
+        let forwarded = self
+            .trust_proxy
+            .then(|| headers.get(FORWARDED_FOR_HEADER)?.to_str().ok())
+            .flatten()
+            .and_then(|addresses| addresses.rsplit(',').next()?.trim().parse().ok());
+        let ip = forwarded
+            .or(peer)
+            .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        Client::Ip(network(ip))
+    }
+
+    /// Takes an accounts token of the address and, when logging in, one of `username` as well,
+    /// so a password isn't guessed at from many addresses either. API keys don't count here.
+    pub fn take_account(
+        &self,
+        headers: &HeaderMap,
+        peer: Option<IpAddr>,
+        username: Option<&str>,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        self.take(self.address(headers, peer), RouteGroup::Accounts, now)?;
+        match username {
+            Some(username) => self.take(
+                Client::Username(username.to_owned()),
+                RouteGroup::Accounts,
+                now,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    fn capacity(&self, client: &Client, group: RouteGroup) -> f64 {
+        let factor = match client {
+            Client::Ip(_) | Client::Username(_) => 1,
+            Client::ApiKey(_) => API_KEY_QUOTA_FACTOR,
+        };
+        f64::from(self.quotas.per_minute(group)) * f64::from(factor)
+    }
+
+    /// Takes a token from the bucket of the client, or tells how long until there is one.
+    fn take(&self, client: Client, group: RouteGroup, now: Instant) -> Result<(), Duration> {
+        let capacity = self.capacity(&client, group);
+        if capacity == 0.0 {
+            return Ok(());
+        }
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets
+            .swept
+            .is_none_or(|swept| now.saturating_duration_since(swept) >= SWEEP_INTERVAL)
+        {
+            // A full bucket is the same as none
+            buckets.buckets.retain(|(client, group), bucket| {
+                let capacity = self.capacity(client, *group);
+                bucket.refill(capacity, now);
+                bucket.tokens < capacity
+            });
+            buckets.swept = Some(now);
+        }
+        let key = (client, group);
+        if buckets.buckets.len() >= self.max_buckets && !buckets.buckets.contains_key(&key) {
+            let stalest = buckets
+                .buckets
+                .iter()
+                .min_by_key(|(_, bucket)| bucket.updated)
+                .map(|(key, _)| key.clone());
+            if let Some(stalest) = stalest {
+                buckets.buckets.remove(&stalest);
+            }
+        }
+        let bucket = buckets.buckets.entry(key).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        bucket.refill(capacity, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) * 60.0 / capacity,
+            ))
+        }
+    }
+}
+
+/// IPv6 addresses are cut to their /64, IPv4 ones mapped to IPv6 are IPv4 again.
+fn network(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => IpAddr::V4(ip),
+            None => IpAddr::V6(Ipv6Addr::from(u128::from(ip) & !(u64::MAX as u128))),
+        },
+        ip => ip,
+    }
+}
+
+/// Route group whose quota a [`RateLimit`] takes from.
+pub trait Quota: Send + Sync {
+    const GROUP: RouteGroup;
+}
+
+pub struct SearchQuota;
+pub struct SolveQuota;
+pub struct DefineQuota;
+pub struct RoomsQuota;
+
+impl Quota for SearchQuota {
+    const GROUP: RouteGroup = RouteGroup::Search;
+}
+
+impl Quota for SolveQuota {
+    const GROUP: RouteGroup = RouteGroup::Solve;
+}
+
+impl Quota for DefineQuota {
+    const GROUP: RouteGroup = RouteGroup::Define;
+}
+
+impl Quota for RoomsQuota {
+    const GROUP: RouteGroup = RouteGroup::Rooms;
+}
+
+/// API request admitted within the quota of its client for the group of `Q`,
+/// answered with `429`, an [`ApiError`] and `Retry-After` otherwise.
+pub struct RateLimit<Q>(PhantomData<Q>);
+
+#[async_trait]
+impl<Q: Quota> FromRequestParts<SharedAppState> for RateLimit<Q> {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &SharedAppState,
+    ) -> Result<Self, Self::Rejection> {
+        let rejection = rejection(parts, app_state, Q::GROUP, |message| {
+            ApiError::new(StatusCode::TOO_MANY_REQUESTS, "rate_limited", message).into_response()
+        });
+        rejection.map_or(Ok(Self(PhantomData)), Err)
+    }
+}
+
+/// [`RateLimit`] for pages and fragments, the `429` carries the message as text.
+pub struct PageRateLimit<Q>(PhantomData<Q>);
+
+#[async_trait]
+impl<Q: Quota> FromRequestParts<SharedAppState> for PageRateLimit<Q> {
+    type Rejection = Response;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        app_state: &SharedAppState,
+    ) -> Result<Self, Self::Rejection> {
+        let rejection = rejection(parts, app_state, Q::GROUP, |message| {
+            (StatusCode::TOO_MANY_REQUESTS, message).into_response()
+        });
+        rejection.map_or(Ok(Self(PhantomData)), Err)
+    }
+}
+
+/// Takes a token of the client of the request, or tells why not.
+/// `limited` makes the response out of the message when the quota is used up.
+fn rejection(
+    parts: &Parts,
+    app_state: &SharedAppState,
+    group: RouteGroup,
+    limited: impl FnOnce(String) -> Response,
+) -> Option<Response> {
+    let limiter = &app_state.rate_limiter;
+    let peer = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let client = match limiter.client(&parts.headers, peer) {
+        Ok(client) => client,
+        Err(err) => return Some(err.into_response()),
+    };
+    limiter
+        .take(client, group, Instant::now())
+        .err()
+        .map(|retry_after| {
+            let seconds = retry_after_seconds(retry_after);
+            let message = format!(
+                "Too many {} requests, try again in {} seconds",
+                group.name(),
+                seconds
+            );
+            let mut response = limited(message);
+            response.headers_mut().insert(RETRY_AFTER, seconds.into());
+            response
+        })
+}
+
+/// Whole seconds for `Retry-After`, at least one.
+pub fn retry_after_seconds(retry_after: Duration) -> u64 {
+    retry_after.as_secs_f64().ceil().max(1.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(quotas: ApiQuotas) -> RateLimiter {
+        RateLimiter {
+            quotas,
+            api_keys: HashSet::from(["secret".to_owned()]),
+            trust_proxy: true,
+            buckets: Mutex::default(),
+            max_buckets: MAX_BUCKETS,
+        }
+    }
+
+    #[test]
+    fn buckets_refill_over_the_minute() {
+        let quotas = ApiQuotas::parse("search=2, define=0").unwrap();
+        assert_eq!((quotas.search, quotas.solve, quotas.define), (2, 60, 0));
+        assert_eq!(quotas.accounts, 10);
+        assert!(ApiQuotas::parse("draw=10").is_err());
+        let limiter = limiter(quotas);
+
+        let start = Instant::now();
+        let alice = Client::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        let bob = Client::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)));
+        let search = RouteGroup::Search;
+        assert!(limiter.take(alice.clone(), search, start).is_ok());
+        assert!(limiter.take(alice.clone(), search, start).is_ok());
+        let retry_after = limiter.take(alice.clone(), search, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(30));
+        assert!(limiter.take(bob, search, start).is_ok());
+        assert!(limiter
+            .take(alice.clone(), RouteGroup::Solve, start)
+            .is_ok());
+        assert!(limiter
+            .take(alice.clone(), search, start + Duration::from_secs(30))
+            .is_ok());
+        for _ in 0..1000 {
+            assert!(limiter
+                .take(alice.clone(), RouteGroup::Define, start)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn clients_are_told_apart_by_key_or_address() {
+        let limiter = limiter(ApiQuotas::default());
+        let peer = Some(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            limiter.client(&headers, peer).unwrap(),
+            Client::Ip(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        );
+        headers.insert(FORWARDED_FOR_HEADER, "1.2.3.4, 5.6.7.8".parse().unwrap());
+        assert_eq!(
+            limiter.client(&headers, peer).unwrap(),
+            Client::Ip("5.6.7.8".parse().unwrap())
+        );
+        let peer = Some("2001:db8:1:2:3:4:5:6".parse().unwrap());
+        headers.insert(FORWARDED_FOR_HEADER, "2001:db8:1:2::9".parse().unwrap());
+        assert_eq!(
+            limiter.client(&headers, peer).unwrap(),
+            Client::Ip("2001:db8:1:2::".parse().unwrap())
+        );
+        headers.insert(FORWARDED_FOR_HEADER, "::ffff:5.6.7.8".parse().unwrap());
+        assert_eq!(
+            limiter.client(&headers, peer).unwrap(),
+            Client::Ip("5.6.7.8".parse().unwrap())
+        );
+        headers.insert(API_KEY_HEADER, "secret".parse().unwrap());
+        let client = limiter.client(&headers, peer).unwrap();
+        assert_eq!(client, Client::ApiKey("secret".to_owned()));
+        assert_eq!(limiter.capacity(&client, RouteGroup::Solve), 600.0);
+        headers.insert(API_KEY_HEADER, "guess".parse().unwrap());
+        assert!(limiter.client(&headers, peer).is_err());
+    }
+
+    #[test]
+    fn logins_are_counted_by_address_and_username() {
+        let limiter = limiter(ApiQuotas::parse("accounts=2").unwrap());
+        let now = Instant::now();
+        let from = |ip: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(FORWARDED_FOR_HEADER, ip.parse().unwrap());
+            headers
+        };
+        let take = |ip: &str, username| limiter.take_account(&from(ip), None, username, now);
+        assert!(take("10.0.0.1", Some("alice")).is_ok());
+        assert!(take("10.0.0.2", Some("alice")).is_ok());
+        assert!(take("10.0.0.3", Some("alice")).is_err());
+        // The address was counted all the same
+        assert!(take("10.0.0.3", Some("bob")).is_ok());
+        assert!(take("10.0.0.3", None).is_err());
+        assert!(take("10.0.0.4", None).is_ok());
+    }
+
+    #[test]
+    fn new_clients_replace_the_stalest_bucket() {
+        let limiter = RateLimiter {
+            max_buckets: 2,
+            ..limiter(ApiQuotas::parse("search=1").unwrap())
+        };
+        let start = Instant::now();
+        let client = |i: u8| Client::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, i)));
+        let search = RouteGroup::Search;
+        assert!(limiter.take(client(0), search, start).is_ok());
+        let later = start + Duration::from_secs(1);
+        assert!(limiter.take(client(1), search, later).is_ok());
+        assert!(limiter.take(client(2), search, later).is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 2);
+        // Still counted, only the first client lost its bucket
+        assert!(limiter.take(client(1), search, later).is_err());
+        assert!(limiter.take(client(2), search, later).is_err());
+    }
+
+    #[test]
+    fn full_buckets_are_swept_every_minute() {
+        // A token every 10 seconds
+        let limiter = limiter(ApiQuotas::parse("search=6").unwrap());
+        let start = Instant::now();
+        let client = |i: u8| Client::Ip(IpAddr::V4(Ipv4Addr::new(10, 0, 0, i)));
+        for i in 0..10 {
+            assert!(limiter.take(client(i), RouteGroup::Search, start).is_ok());
+        }
+        let later = start + Duration::from_secs(55);
+        assert!(limiter.take(client(10), RouteGroup::Search, later).is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 11);
+
+        let next_minute = start + SWEEP_INTERVAL;
+        assert!(limiter
+            .take(client(0), RouteGroup::Search, next_minute)
+            .is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 2);
+    }
+}
@@ -0,0 +1,77 @@
+use notify::{RecursiveMode, Watcher};
+use std::{path::Path, time::Duration};
+use tokio::sync::mpsc;
+
+use crate::app_state::SharedAppState;
+
+/// Editors and deploy scripts touch the file several times in a row.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub async fn reload_words(app_state: &SharedAppState, reason: &str) {
+    match app_state.reload_words().await {
+        Ok(size) => log::info!("Dictionary reloaded on {}, {} words", reason, size),
+        Err(err) => log::error!("Dictionary reload on {} failed: {}", reason, err),
+    }
+}
+
+/// Reloads the dictionary when its file changes.
+/// Parent directory is watched, as files are usually replaced rather than written in place.
+pub fn watch_dictionary(app_state: SharedAppState) -> notify::Result<impl Watcher> {
+    let Some(path) = app_state.words_dict_path.clone() else {
+        return Err(notify::Error::generic("dictionary path is not set"));
+    };
+    let file_name = path.file_name().map(|name| name.to_owned());
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."))
+        .to_owned();
+
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        if event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name() == file_name.as_deref())
+        {
+            let _ = tx.try_send(());
+        }
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    tokio::spawn(async move {
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(DEBOUNCE).await;
+            while rx.try_recv().is_ok() {}
+            reload_words(&app_state, "file change").await;
+        }
+    });
+    Ok(watcher)
+}
+
+#[cfg(unix)]
+pub fn listen_sighup(app_state: SharedAppState) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => hangup,
+            Err(err) => {
+                log::error!("Unable to listen for SIGHUP: {}", err);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            reload_words(&app_state, "SIGHUP").await;
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn listen_sighup(_app_state: SharedAppState) {}
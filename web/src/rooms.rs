@@ -0,0 +1,227 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use tokio::sync::broadcast;
+use words::spelling_bee::SolvedWord;
+
+use crate::utils::random_token::random_token;
+
+/// Oldest rooms nobody is in are closed past this many.
+const MAX_ROOMS: usize = 1000;
+/// Events a slow connection may fall behind before it is sent the whole room again.
+const EVENTS_CAPACITY: usize = 64;
+
+pub type RoomId = String;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundWord {
+    pub word: String,
+    pub finder: String,
+    pub score: usize,
+    pub pangram: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoomEvent {
+    /// `score` and `found_count` are the ones of the room with the word.
+    Found {
+        found: FoundWord,
+        score: usize,
+        found_count: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Guess {
+    Found(FoundWord),
+    NotInSolution,
+    AlreadyFound { finder: String },
+}
+
+/// Words found so far, most recent first, and their score.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomProgress {
+    pub found: Vec<FoundWord>,
+    pub score: usize,
+}
+
+/// Spelling Bee solved together: everyone in the room sees the same letters and the words
+/// found by the others as they come.
+#[derive(Debug)]
+pub struct Room {
+    pub id: RoomId,
+    /// Center letter first.
+    pub letters: String,
+    solution: Vec<SolvedWord>,
+    progress: Mutex<RoomProgress>,
+    events: broadcast::Sender<RoomEvent>,
+    created: Instant,
+}
+
+impl Room {
+    fn new(id: RoomId, letters: &str, solution: Vec<SolvedWord>) -> Self {
+        Self {
+            id,
+            letters: letters.to_owned(),
+            solution,
+            progress: Mutex::default(),
+            events: broadcast::channel(EVENTS_CAPACITY).0,
+            created: Instant::now(),
+        }
+    }
+
+    pub fn words_count(&self) -> usize {
+        self.solution.len()
+    }
+
+    pub fn max_score(&self) -> usize {
+        self.solution.iter().map(|w| w.score).sum()
+    }
+
+    pub fn progress(&self) -> RoomProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    /// Events of the words found from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<RoomEvent> {
+        self.events.subscribe()
+    }
+
+    /// A new word is announced to everyone in the room.
+    pub fn guess(&self, word: &str, finder: &str) -> Guess {
+        let word = word.trim().to_lowercase();
+        let Some(solved) = self.solution.iter().find(|w| w.word == word) else {
+            return Guess::NotInSolution;
+        };
+        let mut progress = self.progress.lock().unwrap();
+        if let Some(found) = progress.found.iter().find(|f| f.word == word) {
+            return Guess::AlreadyFound {
+                finder: found.finder.clone(),
+            };
+        }
+        let found = FoundWord {
+            word,
+            finder: finder.to_owned(),
+            score: solved.score,
+            pangram: solved.pangram,
+        };
+        progress.found.insert(0, found.clone());
+        progress.score += found.score;
+        // Sent under the lock, so events come in the order of the progress
+        let _ = self.events.send(RoomEvent::Found {
+            found: found.clone(),
+            score: progress.score,
+            found_count: progress.found.len(),
+        });
+        Guess::Found(found)
+    }
+}
+
+/// Rooms live in memory only, they are gone on restart.
+#[derive(Debug)]
+pub struct Rooms {
+    rooms: Mutex<HashMap<RoomId, Arc<Room>>>,
+    capacity: usize,
+}
+
+impl Default for Rooms {
+    fn default() -> Self {
+        Self {
+            rooms: Mutex::default(),
+            capacity: MAX_ROOMS,
+        }
+    }
+}
+
+impl Rooms {
+    /// `letters` are normalized and `solution` their solved words.
+    /// When full, the oldest room nobody is connected to is closed,
+    /// `None` when every room has someone in it.
+    pub fn create(&self, letters: &str, solution: Vec<SolvedWord>) -> Option<Arc<Room>> {
+        let mut rooms = self.rooms.lock().unwrap();
+        if rooms.len() >= self.capacity {
+            let oldest = rooms
+                .values()
+                .filter(|room| room.events.receiver_count() == 0)
+                .min_by_key(|room| room.created)
+                .map(|room| room.id.clone())?;
+            rooms.remove(&oldest);
+        }
+        let room = Arc::new(Room::new(random_token(8), letters, solution));
+        rooms.insert(room.id.clone(), room.clone());
+        Some(room)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<Room>> {
+        self.rooms.lock().unwrap().get(id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved(word: &str, score: usize) -> SolvedWord {
+        SolvedWord {
+            word: word.to_owned(),
+            score,
+            pangram: false,
+        }
+    }
+
+    #[test]
+    fn words_are_found_once_for_everyone() {
+        let rooms = Rooms::default();
+        let room = rooms
+            .create("abcdefg", vec![solved("abba", 1), solved("deface", 6)])
+            .unwrap();
+        assert_eq!(rooms.get(&room.id).unwrap().letters, "abcdefg");
+        let mut events = room.subscribe();
+
+        assert_eq!(room.guess("nope", "ann"), Guess::NotInSolution);
+        let Guess::Found(found) = room.guess(" Deface", "ann") else {
+            panic!("deface is in the solution");
+        };
+        assert_eq!((found.word.as_str(), found.score), ("deface", 6));
+        assert_eq!(
+            room.guess("deface", "ben"),
+            Guess::AlreadyFound {
+                finder: "ann".to_owned()
+            }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            RoomEvent::Found {
+                found,
+                score: 6,
+                found_count: 1
+            }
+        );
+        assert!(events.try_recv().is_err());
+
+        room.guess("abba", "ben");
+        let progress = room.progress();
+        assert_eq!(progress.score, 7);
+        assert_eq!(progress.found[0].finder, "ben");
+        assert_eq!(room.max_score(), 7);
+    }
+
+    #[test]
+    fn rooms_in_use_are_kept_open() {
+        let rooms = Rooms {
+            capacity: 2,
+            ..Default::default()
+        };
+        let first = rooms.create("abcdefg", vec![]).unwrap();
+        let _events = first.subscribe();
+        let second = rooms.create("abcdefg", vec![]).unwrap();
+        let third = rooms.create("abcdefg", vec![]).unwrap();
+        assert!(rooms.get(&first.id).is_some());
+        assert!(rooms.get(&second.id).is_none());
+
+        let _events = third.subscribe();
+        assert!(rooms.create("abcdefg", vec![]).is_none());
+    }
+}
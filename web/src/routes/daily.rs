@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::{Days, NaiveDate};
+use maud::Markup;
+
+use crate::{
+    accounts::{user, CurrentUser},
+    app_state::SharedAppState,
+    games::{DailyPuzzle, Game, GAMES},
+    templates::{
+        daily::{daily_archive, daily_page},
+        layout::layout,
+    },
+};
+
+/// Days listed in the archive, older ones are not served.
+const ARCHIVE_DAYS: u64 = 30;
+
+pub fn create_router() -> Router<SharedAppState> {
+    Router::new()
+        .route("/", get(today_route))
+        .route("/archive", get(archive_route))
+        .route("/:date", get(day_route))
+}
+
+async fn today_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+) -> Markup {
+    let today = app_state.daily.today();
+    day_page(&app_state, &current_user, today).await
+}
+
+/// Days of the archive only, the puzzles of tomorrow are not out yet
+/// and any older day would be generated and cached on request.
+async fn day_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Path(date): Path<String>,
+) -> Response {
+    let today = app_state.daily.today();
+    match NaiveDate::parse_from_str(&date, "%Y-%m-%d") {
+        Ok(date) if is_served(date, today) => day_page(&app_state, &current_user, date)
+            .await
+            .into_response(),
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn is_served(date: NaiveDate, today: NaiveDate) -> bool {
+    let first = today.checked_sub_days(Days::new(ARCHIVE_DAYS));
+    date <= today && first.is_none_or(|first| first <= date)
+}
+
+async fn archive_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+) -> Markup {
+    let today = app_state.daily.today();
+    let dates: Vec<NaiveDate> = (1..=ARCHIVE_DAYS)
+        .filter_map(|days| today.checked_sub_days(Days::new(days)))
+        .collect();
+    layout(
+        daily_archive(&dates),
+        Some("Past puzzles of the day"),
+        user(&current_user),
+    )
+}
+
+async fn day_page(
+    app_state: &SharedAppState,
+    current_user: &Option<CurrentUser>,
+    date: NaiveDate,
+) -> Markup {
+    let generating = app_state.clone();
+    // Generating scans the whole dictionary, the first request of a day waits for it
+    let puzzles = tokio::task::spawn_blocking(move || {
+        let words = generating.words();
+        GAMES
+            .iter()
+            .filter_map(|&game| Some((game, generating.daily.puzzle(game, &words, date)?)))
+            .collect::<Vec<(&dyn Game, DailyPuzzle)>>()
+    })
+    .await
+    .unwrap_or_default();
+    let page = daily_page(
+        date,
+        app_state.daily.today(),
+        app_state.daily.timezone(),
+        &puzzles,
+    );
+    layout(page, Some("Puzzles of the day"), user(current_user))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_days_of_the_archive_are_served() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        assert!(is_served(today, today));
+        assert!(is_served(
+            NaiveDate::from_ymd_opt(2026, 9, 19).unwrap(),
+            today
+        ));
+        assert!(!is_served(
+            NaiveDate::from_ymd_opt(2026, 9, 18).unwrap(),
+            today
+        ));
+        assert!(!is_served(today.succ_opt().unwrap(), today));
+        assert!(!is_served(NaiveDate::MIN, today));
+    }
+}
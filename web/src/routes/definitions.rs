@@ -0,0 +1,42 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+};
+use axum_htmx::{HxRequest, VaryHxRequest};
+use maud::html;
+use serde::Deserialize;
+
+use crate::{
+    accounts::{user, CurrentUser},
+    app_state::SharedAppState,
+    templates::{definitions::word_definitions, layout::layout},
+};
+
+#[derive(Deserialize)]
+pub struct DefineQuery {
+    pub w: String,
+}
+
+/// Definitions fragment for HTMX, the whole page otherwise.
+pub async fn define_route(
+    HxRequest(hx_request): HxRequest,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Query(query): Query<DefineQuery>,
+) -> Response {
+    let word = query.w.trim().to_lowercase();
+    let definitions = app_state.definitions.as_ref().map(|d| d.get(&word));
+    let template = word_definitions(definitions);
+    if hx_request {
+        return (VaryHxRequest, template).into_response();
+    }
+    let page = html!(
+        h1 { (word) };
+        (template)
+    );
+    (
+        VaryHxRequest,
+        layout(page, Some(&word), user(&current_user)),
+    )
+        .into_response()
+}
@@ -1,8 +1,16 @@
-use crate::app_state::SharedAppState;
+use crate::{app_state::SharedAppState, games::GAMES};
 use axum::Router;
 
-mod spelling_bee;
+pub mod spelling_bee;
+mod spelling_bee_hints;
+mod spelling_bee_history;
+pub mod spelling_bee_permalink;
+mod spelling_bee_play;
+mod spelling_bee_rooms;
+mod spelling_bee_screenshot;
 
 pub fn create_router() -> Router<SharedAppState> {
-    Router::new().nest("/spelling_bee", spelling_bee::create_router())
+    GAMES.iter().fold(Router::new(), |router, game| {
+        router.nest(&format!("/{}", game.id()), game.router())
+    })
 }
@@ -1,62 +1,259 @@
 use crate::{
-    app_state::SharedAppState,
-    templates::spelling_bee::{input_hinted, input_simple, solution},
+    accounts::{user, user_id, CurrentUser},
+    app_state::{SharedAppState, Words},
+    games::{DailyPuzzle, Game},
+    rate_limit::{PageRateLimit, SolveQuota},
+    solution_options::{SolutionOptions, SolutionSection},
+    solve_cache::SolveKey,
+    storage::{NewPuzzle, User, UserId, SPELLING_BEE},
+    telemetry::server_timing::stage,
+    templates::{
+        layout::layout,
+        spelling_bee::{
+            self, hints_link, input_hinted, input_simple, solution, ContainerGameMode, HINTS_URL,
+            PLAY_URL,
+        },
+    },
+    utils::etag::{etag, public_etag, DictionaryTag},
 };
+
+use super::{
+    spelling_bee_hints, spelling_bee_history,
+    spelling_bee_permalink::{SpellingBeePermalink, SpellingBeeQuery},
+    spelling_bee_play, spelling_bee_rooms,
+    spelling_bee_screenshot::{self, SCREENSHOT_MAX_SIZE},
+};
+use anyhow::Result;
 use axum::{
-    extract::State,
-    response::IntoResponse,
+    extract::{DefaultBodyLimit, Query, State},
+    middleware::from_fn,
+    response::{IntoResponse, Redirect, Response},
     routing::{get, post},
-    Form, Router,
+    Extension, Form, Router,
 };
-use maud::html;
+use axum_htmx::{HxReplaceUrl, HxRequest, VaryHxRequest};
+use chrono::Local;
+use maud::{html, Markup};
 use serde::Deserialize;
-use words::spelling_bee::{SpellingBee, SpellingBeeHintedParams, SpellingBeeSimpleParams};
+use words::{
+    spelling_bee::{normalize_letters, SolvedWord, SpellingBee, SpellingBeeSimpleParams},
+    spelling_bee_generator::generate_letters,
+};
 
-pub async fn input_simple_route() -> impl IntoResponse {
-    html!(
-        ul id="spelling-bee-solution" hx-swap-oob="true" {}
-        (input_simple("", ""))
+pub struct SpellingBeeGame;
+
+impl Game for SpellingBeeGame {
+    fn id(&self) -> &'static str {
+        SPELLING_BEE
+    }
+
+    fn name(&self) -> &'static str {
+        "Spelling bee"
+    }
+
+    fn description(&self) -> &'static str {
+        "Find the words made of seven letters that use the center one, with or without hints."
+    }
+
+    fn icon(&self) -> &'static str {
+        "🐝"
+    }
+
+    fn router(&self) -> Router<SharedAppState> {
+        create_router()
+    }
+
+    fn daily(&self, words: &Words, seed: u64) -> Option<DailyPuzzle> {
+        let letters = generate_letters(&words.dict, &words.shortcuts, seed)?;
+        let mut links = vec![("Play", format!("{}?letters={}", PLAY_URL, letters))];
+        let permalink = SpellingBeePermalink::from_hints(&letters, None, None);
+        if let Ok(url) = permalink.url(ContainerGameMode::Simple, &SolutionOptions::default()) {
+            links.push(("Solution", url.to_string()));
+        }
+        Some(DailyPuzzle {
+            name: letters,
+            links,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SpellingBeePageQuery {
+    pub mode: Option<String>,
+    #[serde(flatten)]
+    pub puzzle: SpellingBeeQuery,
+    #[serde(flatten)]
+    pub options: SolutionOptions,
+}
+
+/// Rehydrates the form from a permalink and renders its solution.
+/// Nothing is saved, crawlers and link previews load permalinks too.
+pub async fn page_route(
+    _: PageRateLimit<SolveQuota>,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Query(query): Query<SpellingBeePageQuery>,
+) -> Markup {
+    let mode = ContainerGameMode::from_query(query.mode.as_deref());
+    let permalink = SpellingBeePermalink::from_query(&query.puzzle);
+    let options = query.options;
+    let (sections, letters_error) = match &permalink {
+        Some(permalink) => {
+            let words = solve(&app_state, permalink, mode);
+            solution_sections(&app_state, words, &options)
+        }
+        None => (vec![], String::new()),
+    };
+    let permalink = permalink.unwrap_or_default();
+    let input = match mode {
+        ContainerGameMode::Simple => input_simple(&permalink.letters, &letters_error, &options),
+        ContainerGameMode::Hinted => input_hinted(
+            &permalink.letters,
+            &letters_error,
+            &permalink.letter_matrix_text(),
+            &permalink.letter_list_text(),
+            &options,
+        ),
+    };
+    solver_page(
+        input,
+        &sections,
+        &hints_url(Some(&permalink), mode),
+        user(&current_user),
     )
 }
 
-pub async fn input_hinted_route() -> impl IntoResponse {
-    html!(
-        ul id="spelling-bee-solution" hx-swap-oob="true" {}
-        (input_hinted("", "", "", ""))
+/// Whole page around the solver form, for first loads and clients without HTMX.
+pub fn solver_page(
+    input: Markup,
+    sections: &[SolutionSection],
+    hints_url: &str,
+    user: Option<&User>,
+) -> Markup {
+    let game = SpellingBeeGame;
+    let template = html!(
+        h1 { (game.icon()) " " (game.name()) };
+        (spelling_bee::container(input, sections, hints_url))
+    );
+    layout(template, Some(game.name()), user)
+}
+
+pub async fn input_simple_route(HxRequest(hx_request): HxRequest) -> Response {
+    if !hx_request {
+        return (
+            VaryHxRequest,
+            Redirect::to(&format!("{}?mode=simple", SpellingBeeGame.url())),
+        )
+            .into_response();
+    }
+    (
+        VaryHxRequest,
+        html!(
+            div id="spelling-bee-solution" hx-swap-oob="true" {}
+            (input_simple("", "", &SolutionOptions::default()))
+        ),
+    )
+        .into_response()
+}
+
+pub async fn input_hinted_route(HxRequest(hx_request): HxRequest) -> Response {
+    if !hx_request {
+        return (
+            VaryHxRequest,
+            Redirect::to(&format!("{}?mode=hinted", SpellingBeeGame.url())),
+        )
+            .into_response();
+    }
+    (
+        VaryHxRequest,
+        html!(
+            div id="spelling-bee-solution" hx-swap-oob="true" {}
+            (input_hinted("", "", "", "", &SolutionOptions::default()))
+        ),
     )
+        .into_response()
+}
+
+/// Solves the puzzle from a form or a permalink, puzzles solved lately come from the cache.
+pub fn solve(
+    app_state: &SharedAppState,
+    permalink: &SpellingBeePermalink,
+    mode: ContainerGameMode,
+) -> Result<Vec<SolvedWord>> {
+    let snapshot = app_state.words();
+    // Params are checked first, invalid letters may have the key of valid ones
+    let words = match mode {
+        ContainerGameMode::Simple => {
+            let params = SpellingBeeSimpleParams::new(&permalink.letters)?;
+            let key = SolveKey::simple(&permalink.letters);
+            stage("solve", || {
+                snapshot.solutions.get_or_solve(key, || {
+                    app_state.metrics.observe_solver(SPELLING_BEE, || {
+                        params.solve(&snapshot.dict, &snapshot.shortcuts)
+                    })
+                })
+            })
+        }
+        ContainerGameMode::Hinted => {
+            let params = permalink.params()?;
+            let key = SolveKey::hinted(
+                &permalink.letters,
+                &permalink.letter_matrix,
+                &permalink.letter_list,
+            );
+            stage("solve", || {
+                snapshot.solutions.get_or_solve(key, || {
+                    app_state.metrics.observe_solver(SPELLING_BEE, || {
+                        params.solve(&snapshot.dict, &snapshot.shortcuts)
+                    })
+                })
+            })
+        }
+    };
+    Ok(words.to_vec())
 }
 
 #[derive(Deserialize)]
 pub struct SimpleSolutionForm {
     pub letters: Option<String>,
+    #[serde(flatten)]
+    pub options: SolutionOptions,
 }
 
+/// Submitted form, saved to the history unless sent by HTMX.
+/// Answers HTMX with the solution fragment, anything else with the whole page.
 pub async fn solve_simple_route(
+    _: PageRateLimit<SolveQuota>,
+    HxRequest(hx_request): HxRequest,
+    current_user: Option<CurrentUser>,
     State(app_state): State<SharedAppState>,
     Form(data): Form<SimpleSolutionForm>,
-) -> impl IntoResponse {
-    if let Some(letters) = data.letters {
-        match SpellingBeeSimpleParams::new(&letters) {
-            Ok(game) => {
-                let words = game.scan_dict(&app_state.words_dict, &app_state.words_shortcuts);
-                html!(
-                    div.errors id="letters-error" hx-swap-oob="true" {}
-                    (solution(words))
-                )
-            }
-            Err(err) => html!(
-                div.errors id="letters-error" hx-swap-oob="true" {
-                    (err.to_string())
-                }
-                (solution(vec![]))
-            ),
+) -> Response {
+    let options = data.options;
+    let Some(letters) = data.letters.map(|l| normalize_letters(&l)) else {
+        if !hx_request {
+            let input = input_simple("", "", &options);
+            return solver_page(input, &[], HINTS_URL, user(&current_user)).into_response();
         }
-    } else {
-        html!(
-            div.errors id="letters-error" hx-swap-oob="true" {}
-            (solution(vec![]))
-        )
+        return solved(&app_state, Ok(vec![]), &options, HINTS_URL).into_response();
+    };
+    let permalink = SpellingBeePermalink::from_hints(&letters, None, None);
+    let words = solve(&app_state, &permalink, ContainerGameMode::Simple);
+    let hints_url = hints_url(Some(&permalink), ContainerGameMode::Simple);
+    if !hx_request {
+        if let Ok(words) = &words {
+            let puzzle = new_puzzle(&letters, None, None, words);
+            save_puzzle(&app_state, user_id(&current_user), puzzle).await;
+        }
+        let (sections, letters_error) = solution_sections(&app_state, words, &options);
+        let input = input_simple(&letters, &letters_error, &options);
+        return solver_page(input, &sections, &hints_url, user(&current_user)).into_response();
     }
+    (
+        replace_url(&permalink, ContainerGameMode::Simple, &options, &words),
+        solved(&app_state, words, &options, &hints_url),
+    )
+        .into_response()
 }
 
 #[derive(Deserialize)]
@@ -64,90 +261,534 @@ pub struct HintedSolutionForm {
     pub letters: Option<String>,
     pub letter_matrix: Option<String>,
     pub letter_list: Option<String>,
+    #[serde(flatten)]
+    pub options: SolutionOptions,
 }
 
 pub async fn solve_hinted_route(
+    _: PageRateLimit<SolveQuota>,
+    HxRequest(hx_request): HxRequest,
+    current_user: Option<CurrentUser>,
     State(app_state): State<SharedAppState>,
     Form(data): Form<HintedSolutionForm>,
-) -> impl IntoResponse {
-    if let Some(letters) = data.letters.map(|l| {
-        l.to_lowercase()
-            .split_whitespace()
-            .collect::<Vec<&str>>()
-            .join("")
-    }) {
-        let allowed_bytes = letters.as_bytes();
-        let letters_len = data
-            .letter_matrix
-            .map(|ll| ll.to_lowercase())
-            .map(|ll| {
-                let mut out: Vec<(u8, Vec<usize>)> = vec![];
-                for entry in ll.split('\n') {
-                    let bytes = entry.as_bytes();
-                    if let Some(first_byte) = bytes.first() {
-                        if !allowed_bytes.contains(first_byte) {
-                            continue;
-                        }
-                        let mut avail_lens = vec![];
-                        let parts = entry.split_whitespace().collect::<Vec<&str>>();
-                        for i in 1..6 {
-                            if parts.get(i).is_some_and(|&e| e != "-") {
-                                avail_lens.push(i + 3);
-                            }
-                        }
-
-                        out.push((*first_byte, avail_lens));
-                    }
-                }
-                out
-            })
-            .unwrap_or_default();
-        let letter_list = data
-            .letter_list
-            .map(|ll| ll.to_lowercase().as_bytes().to_vec())
-            .filter(|bytes| bytes.len() > 1)
-            .map(|bytes| {
-                let mut out = vec![];
-                let mut i = 0;
-                while i < bytes.len() - 1 {
-                    if allowed_bytes.contains(&bytes[i]) && allowed_bytes.contains(&bytes[i + 1]) {
-                        out.push([bytes[i], bytes[i + 1]]);
-                        i += 2;
-                        continue;
-                    }
-
-                    i += 1;
-                }
-                out
-            })
-            .unwrap_or_default();
-        match SpellingBeeHintedParams::new(&letters, letters_len, letter_list) {
-            Ok(game) => {
-                let words = game.scan_dict(&app_state.words_dict, &app_state.words_shortcuts);
-                html!(
-                    div.errors id="letters-error" hx-swap-oob="true" {}
-                    (solution(words))
-                )
-            }
-            Err(err) => html!(
-                div.errors id="letters-error" hx-swap-oob="true" {
-                    (err.to_string())
-                }
-                (solution(vec![]))
-            ),
+) -> Response {
+    let options = data.options;
+    let Some(letters) = data.letters.map(|l| normalize_letters(&l)) else {
+        if !hx_request {
+            let input = input_hinted("", "", "", "", &options);
+            return solver_page(input, &[], HINTS_URL, user(&current_user)).into_response();
         }
-    } else {
-        html!(
-            div.errors id="letters-error" hx-swap-oob="true" {}
-            (solution(vec![]))
+        return solved(&app_state, Ok(vec![]), &options, HINTS_URL).into_response();
+    };
+    let permalink = SpellingBeePermalink::from_hints(
+        &letters,
+        data.letter_matrix.as_deref(),
+        data.letter_list.as_deref(),
+    );
+    let words = solve(&app_state, &permalink, ContainerGameMode::Hinted);
+    let hints_url = hints_url(Some(&permalink), ContainerGameMode::Hinted);
+    if !hx_request {
+        if let Ok(words) = &words {
+            let puzzle = new_puzzle(
+                &letters,
+                data.letter_matrix.clone(),
+                data.letter_list.clone(),
+                words,
+            );
+            save_puzzle(&app_state, user_id(&current_user), puzzle).await;
+        }
+        let (sections, letters_error) = solution_sections(&app_state, words, &options);
+        let input = input_hinted(
+            &letters,
+            &letters_error,
+            data.letter_matrix.as_deref().unwrap_or_default(),
+            data.letter_list.as_deref().unwrap_or_default(),
+            &options,
+        );
+        return solver_page(input, &sections, &hints_url, user(&current_user)).into_response();
+    }
+    (
+        replace_url(&permalink, ContainerGameMode::Hinted, &options, &words),
+        solved(&app_state, words, &options, &hints_url),
+    )
+        .into_response()
+}
+
+/// Solution fragment of the simple form solved as you type.
+/// It doesn't depend on the user, so it is a GET any cache can keep.
+pub async fn solution_simple_route(
+    _: PageRateLimit<SolveQuota>,
+    HxRequest(hx_request): HxRequest,
+    State(app_state): State<SharedAppState>,
+    Query(data): Query<SimpleSolutionForm>,
+) -> Response {
+    let letters = data.letters.map(|l| normalize_letters(&l));
+    let permalink = letters.map(|letters| SpellingBeePermalink::from_hints(&letters, None, None));
+    solution_fragment(
+        &app_state,
+        hx_request,
+        permalink,
+        ContainerGameMode::Simple,
+        &data.options,
+    )
+}
+
+pub async fn solution_hinted_route(
+    _: PageRateLimit<SolveQuota>,
+    HxRequest(hx_request): HxRequest,
+    State(app_state): State<SharedAppState>,
+    Query(data): Query<HintedSolutionForm>,
+) -> Response {
+    let letters = data.letters.map(|l| normalize_letters(&l));
+    let permalink = letters.map(|letters| {
+        SpellingBeePermalink::from_hints(
+            &letters,
+            data.letter_matrix.as_deref(),
+            data.letter_list.as_deref(),
         )
+    });
+    solution_fragment(
+        &app_state,
+        hx_request,
+        permalink,
+        ContainerGameMode::Hinted,
+        &data.options,
+    )
+}
+
+/// HTMX gets the solution, anything else is sent to the page of the permalink.
+fn solution_fragment(
+    app_state: &SharedAppState,
+    hx_request: bool,
+    permalink: Option<SpellingBeePermalink>,
+    mode: ContainerGameMode,
+    options: &SolutionOptions,
+) -> Response {
+    let Some(permalink) = permalink else {
+        if !hx_request {
+            return (VaryHxRequest, Redirect::to(&SpellingBeeGame.url())).into_response();
+        }
+        let fragment = solved(app_state, Ok(vec![]), options, HINTS_URL);
+        return (VaryHxRequest, fragment).into_response();
+    };
+    if !hx_request {
+        let url = permalink
+            .url(mode, options)
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| SpellingBeeGame.url());
+        return (VaryHxRequest, Redirect::to(&url)).into_response();
+    }
+    let dictionary = DictionaryTag(app_state.words().dict.checksum());
+    let words = solve(app_state, &permalink, mode);
+    let hints_url = hints_url(Some(&permalink), mode);
+    (
+        VaryHxRequest,
+        Extension(dictionary),
+        replace_url(&permalink, mode, options, &words),
+        solved(app_state, words, options, &hints_url),
+    )
+        .into_response()
+}
+
+/// Sections of words to list, and the letters error.
+pub fn solution_sections(
+    app_state: &SharedAppState,
+    words: Result<Vec<SolvedWord>>,
+    options: &SolutionOptions,
+) -> (Vec<SolutionSection>, String) {
+    match words {
+        Ok(words) => {
+            let snapshot = app_state.words();
+            let sections = stage("sections", || {
+                options.sections(
+                    &words,
+                    &app_state.lemmatizer,
+                    &snapshot.dict,
+                    &app_state.frequencies,
+                )
+            });
+            (sections, String::new())
+        }
+        Err(err) => (vec![], err.to_string()),
+    }
+}
+
+/// Solution with the letters error and the hints link swapped out of band.
+fn solved(
+    app_state: &SharedAppState,
+    words: Result<Vec<SolvedWord>>,
+    options: &SolutionOptions,
+    hints_url: &str,
+) -> Markup {
+    let (sections, error) = solution_sections(app_state, words, options);
+    html!(
+        div.errors id="letters-error" hx-swap-oob="true" {
+            (error)
+        }
+        (hints_link(hints_url, true))
+        (solution(&sections))
+    )
+}
+
+/// Hints page of the puzzle, the empty one when there are no letters yet.
+fn hints_url(permalink: Option<&SpellingBeePermalink>, mode: ContainerGameMode) -> String {
+    permalink
+        .filter(|permalink| !permalink.letters.is_empty())
+        .and_then(|permalink| {
+            permalink
+                .hints_url(mode, None)
+                .inspect_err(|err| log::error!("Unable to build hints url: {}", err))
+                .ok()
+        })
+        .map(|url| url.to_string())
+        .unwrap_or_else(|| HINTS_URL.to_owned())
+}
+
+/// Keeps the address bar pointing at the permalink of the solved puzzle.
+/// Replaced rather than pushed, as the form is solved on every keystroke.
+fn replace_url(
+    permalink: &SpellingBeePermalink,
+    mode: ContainerGameMode,
+    options: &SolutionOptions,
+    words: &Result<Vec<SolvedWord>>,
+) -> Option<HxReplaceUrl> {
+    if words.is_err() {
+        return None;
+    }
+    permalink
+        .url(mode, options)
+        .inspect_err(|err| log::error!("Unable to build permalink: {}", err))
+        .ok()
+        .map(HxReplaceUrl)
+}
+
+pub(super) fn new_puzzle(
+    letters: &str,
+    letter_matrix: Option<String>,
+    letter_list: Option<String>,
+    words: &[SolvedWord],
+) -> NewPuzzle {
+    NewPuzzle {
+        game: SPELLING_BEE.to_owned(),
+        letters: letters.to_owned(),
+        letter_matrix,
+        letter_list,
+        date: Local::now().date_naive(),
+        words: words.to_vec(),
+    }
+}
+
+/// History is best effort, the solution is shown even if it can't be saved.
+/// Only submitted forms and screenshots are saved, not the forms solved as you type.
+pub(super) async fn save_puzzle(
+    app_state: &SharedAppState,
+    user: Option<UserId>,
+    puzzle: NewPuzzle,
+) {
+    let letters = puzzle.letters.clone();
+    let saved = app_state
+        .with_storage(move |storage| storage.save_puzzle(&puzzle, user))
+        .await;
+    if let Err(err) = saved {
+        log::error!("Unable to save puzzle {}: {:#}", letters, err);
     }
 }
 
 pub fn create_router() -> Router<SharedAppState> {
     Router::new()
+        .route("/", get(page_route).layer(from_fn(etag)))
         .route("/input_simple", get(input_simple_route))
         .route("/input_hinted", get(input_hinted_route))
-        .route("/solve_simple", post(solve_simple_route))
-        .route("/solve_hinted", post(solve_hinted_route))
+        .route(
+            "/solve_simple",
+            get(solution_simple_route)
+                .layer(from_fn(public_etag))
+                .post(solve_simple_route),
+        )
+        .route(
+            "/solve_hinted",
+            get(solution_hinted_route)
+                .layer(from_fn(public_etag))
+                .post(solve_hinted_route),
+        )
+        .route(
+            "/hints",
+            get(spelling_bee_hints::hints_route).layer(from_fn(etag)),
+        )
+        .route(
+            "/screenshot",
+            post(spelling_bee_screenshot::screenshot_route)
+                .layer(DefaultBodyLimit::max(SCREENSHOT_MAX_SIZE)),
+        )
+        .nest("/history", spelling_bee_history::create_router())
+        .nest("/rooms", spelling_bee_rooms::create_router())
+        .nest("/play", spelling_bee_play::create_router())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app_state::AppState, config::AppConfig, rate_limit::ApiQuotas};
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn app_state() -> SharedAppState {
+        let config = AppConfig::for_tests("spelling_bee_routes");
+        AppState::new(&config).unwrap().shared()
+    }
+
+    fn app() -> Router {
+        create_router().with_state(app_state())
+    }
+
+    fn solve_request(hx_request: bool) -> Request<Body> {
+        let request = Request::post("/solve_simple")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+        let request = if hx_request {
+            request.header("HX-Request", "true")
+        } else {
+            request
+        };
+        request.body(Body::from("letters=zwieslt")).unwrap()
+    }
+
+    async fn body_text(response: Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn only_submitted_puzzles_are_saved() {
+        let app_state = app_state();
+        let app = create_router().with_state(app_state.clone());
+        let saved = || app_state.storage.puzzles(SPELLING_BEE, None, 10).unwrap();
+        let response = app.clone().oneshot(solve_request(true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(saved().is_empty());
+        let response = app.clone().oneshot(solve_request(false)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(saved().len(), 1);
+        let permalink = Request::get("/?mode=simple&letters=abcdefg")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(permalink).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(saved().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn htmx_gets_fragment() {
+        let response = app().oneshot(solve_request(true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["HX-Replace-Url"],
+            "/games/spelling_bee?mode=simple&letters=zwieslt"
+        );
+        let body = body_text(response).await;
+        assert!(body.starts_with("<div class=\"errors\""));
+        assert!(body.contains("<summary>liszt</summary>"));
+    }
+
+    #[tokio::test]
+    async fn plain_post_gets_whole_page() {
+        let response = app().oneshot(solve_request(false)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_text(response).await;
+        assert!(body.starts_with("<!DOCTYPE html>"));
+        assert!(body.contains("value=\"zwieslt\""));
+        assert!(body.contains("<summary>liszt</summary>"));
+    }
+
+    #[tokio::test]
+    async fn solving_takes_from_the_quota() {
+        let config = AppConfig {
+            api_quotas: ApiQuotas::parse("solve=1").unwrap(),
+            ..AppConfig::for_tests("spelling_bee_routes")
+        };
+        let app = create_router().with_state(AppState::new(&config).unwrap().shared());
+        let response = app.clone().oneshot(solve_request(true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(solve_request(true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+        assert!(body_text(response)
+            .await
+            .starts_with("Too many solve requests"));
+    }
+
+    #[tokio::test]
+    async fn permalink_shows_letters_errors() {
+        for (letters, error) in [
+            ("zwi3slt", "is not a latin letter"),
+            ("zwieslta", "letters must have 7 unique characters"),
+        ] {
+            let request = Request::get(format!("/?mode=simple&letters={}", letters))
+                .body(Body::empty())
+                .unwrap();
+            let response = app().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = body_text(response).await;
+            assert!(body.contains(error), "{}", letters);
+            assert!(
+                body.contains(&format!("value=\"{}\"", letters)),
+                "{}",
+                letters
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn permalink_is_revalidated_by_etag() {
+        let app = app();
+        let permalink = "/?mode=simple&letters=zwieslt";
+        let request = Request::get(permalink).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "private, no-cache"
+        );
+        let etag = response.headers()[header::ETAG].clone();
+
+        let request = Request::get(permalink)
+            .header(header::IF_NONE_MATCH, etag.clone())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag);
+        assert!(body_text(response).await.is_empty());
+
+        let request = Request::get("/?mode=simple&letters=zwiesla")
+            .header(header::IF_NONE_MATCH, etag)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn solution_fragment_is_shared_by_caches() {
+        let app = app();
+        let url = "/solve_simple?letters=ZWIESLT&contains=a+b%26c";
+        let request = Request::get(url)
+            .header("HX-Request", "true")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            "public, no-cache"
+        );
+        assert_eq!(response.headers()[header::VARY], "hx-request");
+        let dictionary = app_state().words().dict.checksum();
+        let etag = response.headers()[header::ETAG].to_str().unwrap();
+        assert!(etag.starts_with(&format!("W/\"{:016x}-", dictionary)));
+        assert_eq!(
+            response.headers()["HX-Replace-Url"],
+            "/games/spelling_bee?mode=simple&letters=zwieslt&contains=a+b%26c"
+        );
+
+        let request = Request::get(url).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "/games/spelling_bee?mode=simple&letters=zwieslt&contains=a+b%26c"
+        );
+
+        let response = app.oneshot(solve_request(true)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(header::ETAG));
+        assert!(!response.headers().contains_key(header::CACHE_CONTROL));
+    }
+
+    #[tokio::test]
+    async fn plain_input_switch_redirects() {
+        let request = Request::get("/input_hinted").body(Body::empty()).unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "/games/spelling_bee?mode=hinted"
+        );
+        assert_eq!(response.headers()[header::VARY], "hx-request");
+    }
+
+    #[tokio::test]
+    async fn hints_reveal_one_step_at_a_time() {
+        let request = Request::get("/hints?letters=zwieslt&hint=first_letters")
+            .header("HX-Request", "true")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_text(response).await;
+        assert!(body.starts_with("<div id=\"spelling-bee-hints\""));
+        assert!(body.contains("Words per first letter"));
+        assert!(!body.contains("Words per first two letters"));
+        assert!(!body.contains("liszt"));
+        assert!(body.contains("hx-get=\"/games/spelling_bee/hints?mode=simple&amp;letters=zwieslt&amp;hint=prefixes\""));
+    }
+
+    fn screenshot_request(image: &[u8], hx_request: bool) -> Request<Body> {
+        let boundary = "screenshot-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"screenshot\"; filename=\"hive.png\"\r\nContent-Type: image/png\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(image);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        let request = Request::post("/screenshot").header(
+            header::CONTENT_TYPE,
+            format!("multipart/form-data; boundary={boundary}"),
+        );
+        let request = if hx_request {
+            request.header("HX-Request", "true")
+        } else {
+            request
+        };
+        request.body(Body::from(body)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn screenshot_goes_to_solution() {
+        let image = include_bytes!("../../../../ocr/fixtures/light_lacerst.png");
+        let response = app()
+            .oneshot(screenshot_request(image, false))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            response.headers()[header::LOCATION],
+            "/games/spelling_bee?mode=simple&letters=lacerst"
+        );
+
+        let response = app()
+            .oneshot(screenshot_request(image, true))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["HX-Location"],
+            "/games/spelling_bee?mode=simple&letters=lacerst"
+        );
+    }
+
+    #[tokio::test]
+    async fn unreadable_screenshot_shows_error() {
+        let response = app()
+            .oneshot(screenshot_request(b"not an image", true))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_text(response).await;
+        assert!(body.starts_with("<form id=\"spelling-bee-screenshot\""));
+        assert!(body.contains("unable to read the letters of the screenshot"));
+    }
 }
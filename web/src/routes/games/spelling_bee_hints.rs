@@ -0,0 +1,81 @@
+use axum::{
+    extract::{Query, State},
+    response::{IntoResponse, Response},
+};
+use axum_htmx::{HxBoosted, HxRequest, VaryHxRequest};
+use serde::Deserialize;
+use words::spelling_bee_hints::{HintStep, SpellingBeeHints};
+
+use crate::{
+    accounts::{user, CurrentUser},
+    app_state::SharedAppState,
+    rate_limit::{PageRateLimit, SolveQuota},
+    solution_options::SolutionOptions,
+    templates::{
+        layout::layout,
+        spelling_bee::{hints, hints_page, ContainerGameMode, SPELLING_BEE_URL},
+    },
+};
+
+use super::{
+    spelling_bee::solve,
+    spelling_bee_permalink::{SpellingBeePermalink, SpellingBeeQuery},
+};
+
+#[derive(Deserialize)]
+pub struct HintsQuery {
+    pub mode: Option<String>,
+    #[serde(flatten)]
+    pub puzzle: SpellingBeeQuery,
+    /// Last revealed step, nothing is revealed when missing.
+    pub hint: Option<String>,
+}
+
+/// Reveals the solution of a permalink step by step, the steps are kept in the url.
+/// The next step button gets only the hints fragment, boosted navigation the whole page.
+pub async fn hints_route(
+    _: PageRateLimit<SolveQuota>,
+    HxRequest(hx_request): HxRequest,
+    HxBoosted(hx_boosted): HxBoosted,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Query(query): Query<HintsQuery>,
+) -> Response {
+    let mode = ContainerGameMode::from_query(query.mode.as_deref());
+    let step = query.hint.as_deref().and_then(HintStep::from_query);
+    let permalink = SpellingBeePermalink::from_query(&query.puzzle);
+    let (fragment, letters_error) = match &permalink {
+        Some(permalink) => match solve(&app_state, permalink, mode) {
+            Ok(words) => {
+                let next_url = HintStep::next(step)
+                    .map(|next| permalink.hints_url(mode, Some(next)))
+                    .transpose()
+                    .inspect_err(|err| log::error!("Unable to build hints url: {}", err))
+                    .ok()
+                    .flatten()
+                    .map(|url| url.to_string());
+                let fragment = hints(&SpellingBeeHints::new(&words), step, next_url.as_deref());
+                (Some(fragment), String::new())
+            }
+            Err(err) => (None, err.to_string()),
+        },
+        None => (None, String::new()),
+    };
+    if hx_request && !hx_boosted {
+        if let Some(fragment) = fragment {
+            return (VaryHxRequest, fragment).into_response();
+        }
+    }
+    let solver_url = permalink
+        .as_ref()
+        .and_then(|permalink| permalink.url(mode, &SolutionOptions::default()).ok())
+        .map(|url| url.to_string())
+        .unwrap_or_else(|| SPELLING_BEE_URL.to_owned());
+    let letters = permalink.map(|p| p.letters).unwrap_or_default();
+    let page = hints_page(&letters, &letters_error, fragment, &solver_url);
+    (
+        VaryHxRequest,
+        layout(page, Some("Spelling bee hints"), user(&current_user)),
+    )
+        .into_response()
+}
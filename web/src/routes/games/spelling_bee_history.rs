@@ -0,0 +1,127 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use axum_htmx::HxRequest;
+use chrono::Local;
+use serde::Deserialize;
+
+use crate::{
+    accounts::{user, user_id, CurrentUser},
+    app_state::SharedAppState,
+    solution_options::SolutionOptions,
+    storage::{PuzzleId, SPELLING_BEE},
+    streaks::Streaks,
+    templates::{
+        layout::layout,
+        spelling_bee::{found_word, history, history_puzzle, ContainerGameMode, SPELLING_BEE_URL},
+    },
+};
+
+use super::spelling_bee_permalink::SpellingBeePermalink;
+
+const HISTORY_LIMIT: usize = 100;
+
+/// Puzzles of the logged in user, the shared ones otherwise.
+pub async fn history_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+) -> Response {
+    let id = user_id(&current_user);
+    let history_data = app_state
+        .with_storage(move |storage| {
+            let puzzles = storage.puzzles(SPELLING_BEE, id, HISTORY_LIMIT)?;
+            let dates = storage.solved_dates(SPELLING_BEE, id)?;
+            Ok((puzzles, Streaks::new(&dates, Local::now().date_naive())))
+        })
+        .await;
+    match history_data {
+        Ok((puzzles, streaks)) => {
+            let user = user(&current_user);
+            let template = history(&puzzles, &streaks, user.is_some());
+            layout(template, Some("Spelling bee history"), user).into_response()
+        }
+        Err(err) => storage_error(err),
+    }
+}
+
+pub async fn puzzle_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Path(id): Path<PuzzleId>,
+) -> Response {
+    let id_of_user = user_id(&current_user);
+    match app_state
+        .with_storage(move |storage| storage.puzzle(id, id_of_user))
+        .await
+    {
+        Ok(Some(puzzle)) if puzzle.game == SPELLING_BEE => {
+            let mode = if puzzle.letter_matrix.is_some() || puzzle.letter_list.is_some() {
+                ContainerGameMode::Hinted
+            } else {
+                ContainerGameMode::Simple
+            };
+            let solver_url = SpellingBeePermalink::from_hints(
+                &puzzle.letters,
+                puzzle.letter_matrix.as_deref(),
+                puzzle.letter_list.as_deref(),
+            )
+            .url(mode, &SolutionOptions::default())
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| SPELLING_BEE_URL.to_owned());
+            let title = format!("Spelling bee {}", puzzle.letters);
+            let template = history_puzzle(&puzzle, &solver_url);
+            layout(template, Some(&title), user(&current_user)).into_response()
+        }
+        Ok(_) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => storage_error(err),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FoundWordForm {
+    pub word: String,
+    pub found: bool,
+}
+
+/// Without HTMX the browser is sent back to the puzzle page.
+pub async fn found_word_route(
+    HxRequest(hx_request): HxRequest,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Path(id): Path<PuzzleId>,
+    Form(data): Form<FoundWordForm>,
+) -> Response {
+    let user = user_id(&current_user);
+    let (word, found) = (data.word.clone(), data.found);
+    match app_state
+        .with_storage(move |storage| storage.set_found(id, user, &word, found))
+        .await
+    {
+        Ok(true) if !hx_request => {
+            Redirect::to(&format!("/games/spelling_bee/history/{}", id)).into_response()
+        }
+        Ok(true) => found_word(id, &data.word, data.found).into_response(),
+        Ok(false) => StatusCode::NOT_FOUND.into_response(),
+        Err(err) => storage_error(err),
+    }
+}
+
+fn storage_error(err: anyhow::Error) -> Response {
+    log::error!("Puzzle history is unavailable: {:#}", err);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Puzzle history is unavailable",
+    )
+        .into_response()
+}
+
+pub fn create_router() -> Router<SharedAppState> {
+    Router::new()
+        .route("/", get(history_route))
+        .route("/:id", get(puzzle_route))
+        .route("/:id/found", post(found_word_route))
+}
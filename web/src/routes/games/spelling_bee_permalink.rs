@@ -0,0 +1,281 @@
+use anyhow::Result;
+use axum::http::Uri;
+use serde::Deserialize;
+use std::ops::RangeInclusive;
+use words::{
+    spelling_bee::{normalize_letters, SpellingBeeHintedParams},
+    spelling_bee_hints::HintStep,
+};
+
+use crate::{
+    solution_options::SolutionOptions,
+    templates::spelling_bee::{ContainerGameMode, HINTS_URL, SPELLING_BEE_URL},
+    utils::modify_query::with_query,
+};
+
+/// Word lengths in the columns of the letter matrix, each is a single digit in the url.
+const MATRIX_LENGTHS: RangeInclusive<usize> = 4..=8;
+
+/// Puzzle input in the query string of the index page, hints are kept parsed:
+/// `m=a458b4c` is the letter matrix, each letter followed by the word lengths it starts,
+/// `l=abacbe` is the two letter list as concatenated pairs.
+#[derive(Deserialize, Debug, Default)]
+pub struct SpellingBeeQuery {
+    pub letters: Option<String>,
+    pub m: Option<String>,
+    pub l: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpellingBeePermalink {
+    pub letters: String,
+    pub letter_matrix: Vec<(u8, Vec<usize>)>,
+    pub letter_list: Vec<[u8; 2]>,
+}
+
+impl SpellingBeePermalink {
+    /// `letters` are expected to be normalized already.
+    pub fn from_hints(
+        letters: &str,
+        letter_matrix: Option<&str>,
+        letter_list: Option<&str>,
+    ) -> Self {
+        let allowed_bytes = letters.as_bytes();
+        Self {
+            letters: letters.to_owned(),
+            letter_matrix: letter_matrix
+                .map(|lm| SpellingBeeHintedParams::parse_letter_matrix(lm, allowed_bytes))
+                .unwrap_or_default(),
+            letter_list: letter_list
+                .map(|ll| SpellingBeeHintedParams::parse_letter_list(ll, allowed_bytes))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Letters and hints are normalized like the form, as urls are typed or shared by hand too.
+    pub fn from_query(query: &SpellingBeeQuery) -> Option<Self> {
+        let letters = query.letters.as_deref().filter(|l| !l.is_empty())?;
+        // Anything else is left for the solver to reject with its error
+        let letters = normalize_letters(letters);
+        let allowed_bytes = letters.as_bytes();
+
+        let mut letter_matrix: Vec<(u8, Vec<usize>)> = vec![];
+        let matrix = query.m.as_deref().unwrap_or_default().to_ascii_lowercase();
+        for byte in matrix.bytes() {
+            if allowed_bytes.contains(&byte) {
+                letter_matrix.push((byte, vec![]));
+            } else if let (Some((_, lengths)), Some(digit)) =
+                (letter_matrix.last_mut(), (byte as char).to_digit(10))
+            {
+                lengths.push(digit as usize);
+            }
+        }
+        let letter_list = query
+            .l
+            .as_deref()
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+            .as_bytes()
+            .chunks_exact(2)
+            .filter(|pair| pair.iter().all(|b| allowed_bytes.contains(b)))
+            .map(|pair| [pair[0], pair[1]])
+            .collect();
+
+        Some(Self {
+            letters,
+            letter_matrix,
+            letter_list,
+        })
+    }
+
+    pub fn params(&self) -> Result<SpellingBeeHintedParams> {
+        SpellingBeeHintedParams::new(
+            &self.letters,
+            self.letter_matrix.clone(),
+            self.letter_list.clone(),
+        )
+    }
+
+    pub fn url(&self, mode: ContainerGameMode, options: &SolutionOptions) -> Result<Uri> {
+        let mut query = self.query(mode);
+        query.extend(options.query_pairs());
+        with_query(SPELLING_BEE_URL, query)
+    }
+
+    /// Hints page of the puzzle with the steps up to `step` revealed.
+    pub fn hints_url(&self, mode: ContainerGameMode, step: Option<HintStep>) -> Result<Uri> {
+        let mut query = self.query(mode);
+        query.extend(step.map(|step| ("hint".to_owned(), step.as_query().to_owned())));
+        with_query(HINTS_URL, query)
+    }
+
+    fn query(&self, mode: ContainerGameMode) -> Vec<(String, String)> {
+        let mut query = vec![
+            ("mode".to_owned(), mode.as_query().to_owned()),
+            ("letters".to_owned(), self.letters.clone()),
+        ];
+        if mode == ContainerGameMode::Hinted {
+            let m: String = self
+                .letter_matrix
+                .iter()
+                .map(|(letter, lengths)| {
+                    let lengths = lengths
+                        .iter()
+                        .filter(|len| MATRIX_LENGTHS.contains(len))
+                        .map(|len| len.to_string());
+                    std::iter::once((*letter as char).to_string())
+                        .chain(lengths)
+                        .collect::<String>()
+                })
+                .collect();
+            let l: String = self
+                .letter_list
+                .iter()
+                .flat_map(|pair| pair.iter().map(|&b| b as char))
+                .collect();
+            query.extend(
+                [("m", m), ("l", l)]
+                    .into_iter()
+                    .filter(|(_, value)| !value.is_empty())
+                    .map(|(name, value)| (name.to_owned(), value)),
+            );
+        }
+        query
+    }
+
+    /// Matrix text that parses back to the same hints, counts are not kept so `*` stands for any.
+    pub fn letter_matrix_text(&self) -> String {
+        self.letter_matrix
+            .iter()
+            .map(|(letter, lengths)| {
+                let columns: Vec<&str> = MATRIX_LENGTHS
+                    .map(|len| if lengths.contains(&len) { "*" } else { "-" })
+                    .collect();
+                format!("{} {}\n", *letter as char, columns.join(" "))
+            })
+            .collect()
+    }
+
+    pub fn letter_list_text(&self) -> String {
+        self.letter_list
+            .iter()
+            .map(|pair| String::from_utf8_lossy(pair).to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution_options::{GroupBy, HideInflections};
+    use axum::extract::Query;
+
+    #[test]
+    fn it_round_trips_through_url() {
+        let permalink = SpellingBeePermalink::from_hints(
+            "abcdefg",
+            Some("A 3 - 1 - -\nB - - - - 2\nC - - - - -\nZ 1 - - - -\n"),
+            Some("AB-2 AC-1\nBA-4 zz-1"),
+        );
+        assert_eq!(
+            permalink.letter_matrix,
+            vec![(b'a', vec![4, 6]), (b'b', vec![8]), (b'c', vec![])]
+        );
+        assert_eq!(permalink.letter_list, vec![*b"ab", *b"ac", *b"ba"]);
+
+        let url = permalink
+            .url(ContainerGameMode::Hinted, &SolutionOptions::default())
+            .unwrap();
+        assert_eq!(
+            url,
+            "/games/spelling_bee?mode=hinted&letters=abcdefg&m=a46b8c&l=abacba"
+        );
+
+        let Query(query) = Query::<SpellingBeeQuery>::try_from_uri(&url).unwrap();
+        assert_eq!(
+            SpellingBeePermalink::from_query(&query),
+            Some(permalink.clone())
+        );
+
+        let from_text = SpellingBeePermalink::from_hints(
+            "abcdefg",
+            Some(&permalink.letter_matrix_text()),
+            Some(&permalink.letter_list_text()),
+        );
+        assert_eq!(from_text, permalink);
+    }
+
+    #[test]
+    fn simple_url_drops_hints() {
+        let permalink = SpellingBeePermalink::from_hints("abcdefg", None, Some("ab"));
+        let options = SolutionOptions {
+            hide: HideInflections::Plurals,
+            group_by: GroupBy::Prefix,
+            contains: "ab".to_owned(),
+            ..Default::default()
+        };
+        let url = permalink.url(ContainerGameMode::Simple, &options).unwrap();
+        assert_eq!(
+            url,
+            "/games/spelling_bee?mode=simple&letters=abcdefg&hide=plurals&group=prefix&contains=ab"
+        );
+        let url = permalink
+            .hints_url(ContainerGameMode::Simple, Some(HintStep::Prefixes))
+            .unwrap();
+        assert_eq!(
+            url,
+            "/games/spelling_bee/hints?mode=simple&letters=abcdefg&hint=prefixes"
+        );
+        assert_eq!(
+            SpellingBeePermalink::from_query(&SpellingBeeQuery::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn contains_text_round_trips() {
+        let permalink = SpellingBeePermalink::from_hints("abcdefg", None, None);
+        let options = SolutionOptions {
+            contains: "a b&c".to_owned(),
+            ..Default::default()
+        };
+        let url = permalink.url(ContainerGameMode::Simple, &options).unwrap();
+        assert_eq!(
+            url,
+            "/games/spelling_bee?mode=simple&letters=abcdefg&contains=a+b%26c"
+        );
+        let Query(parsed) = Query::<SolutionOptions>::try_from_uri(&url).unwrap();
+        assert_eq!(parsed.contains, "a b&c");
+        assert_eq!(parsed.query_pairs(), options.query_pairs());
+    }
+
+    #[test]
+    fn query_is_normalized() {
+        let query = SpellingBeeQuery {
+            letters: Some("ZWie SLT".to_owned()),
+            m: Some("Z4W5".to_owned()),
+            l: Some("ZWzi".to_owned()),
+        };
+        assert_eq!(
+            SpellingBeePermalink::from_query(&query),
+            Some(SpellingBeePermalink {
+                letters: "zwieslt".to_owned(),
+                letter_matrix: vec![(b'z', vec![4]), (b'w', vec![5])],
+                letter_list: vec![*b"zw", *b"zi"],
+            })
+        );
+    }
+
+    #[test]
+    fn query_letters_are_not_cut() {
+        for letters in ["zwieslta", "zwi3slt"] {
+            let query = SpellingBeeQuery {
+                letters: Some(letters.to_owned()),
+                ..Default::default()
+            };
+            let permalink = SpellingBeePermalink::from_query(&query).unwrap();
+            assert_eq!(permalink.letters, letters);
+        }
+    }
+}
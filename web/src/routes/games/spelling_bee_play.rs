@@ -0,0 +1,231 @@
+use axum::{
+    extract::{Query, State},
+    http::Uri,
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use axum_extra::extract::{
+    cookie::{Cookie, SameSite},
+    CookieJar,
+};
+use axum_htmx::{HxLocation, HxRequest, VaryHxRequest};
+use maud::Markup;
+use rand_core::{OsRng, RngCore};
+use serde::Deserialize;
+use words::{
+    spelling_bee::{normalize_letters, SolvedWord},
+    spelling_bee_play::{Rejection, SpellingBeePlay},
+};
+
+use super::{spelling_bee::solve, spelling_bee_permalink::SpellingBeePermalink};
+use crate::{
+    accounts::{user, CurrentUser},
+    app_state::SharedAppState,
+    templates::{
+        layout::layout,
+        spelling_bee::{play_board, play_page, play_start_page, ContainerGameMode, PLAY_URL},
+    },
+};
+
+const PLAY_COOKIE: &str = "play";
+
+pub fn create_router() -> Router<SharedAppState> {
+    Router::new()
+        .route("/", get(play_route))
+        .route("/guess", post(guess_route))
+        .route("/shuffle", post(shuffle_route))
+}
+
+#[derive(Deserialize)]
+pub struct PlayQuery {
+    pub letters: Option<String>,
+}
+
+/// Starts a game when the letters differ from the one being played,
+/// without letters the game goes on or the letters are asked for.
+async fn play_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    jar: CookieJar,
+    Query(query): Query<PlayQuery>,
+) -> Response {
+    let user = user(&current_user);
+    let play_id = jar.get(PLAY_COOKIE).map(|cookie| cookie.value().to_owned());
+    let current = play_id
+        .as_deref()
+        .and_then(|id| app_state.plays.update(id, |play| play.clone()));
+    let letters = query.letters.map(|letters| normalize_letters(&letters));
+    let play = match (letters, current) {
+        (Some(letters), _) if letters.is_empty() => None,
+        (Some(letters), Some(current)) if letters == current.letters() => Some(current),
+        (Some(letters), _) => {
+            let permalink = SpellingBeePermalink::from_hints(&letters, None, None);
+            let solution = solve(&app_state, &permalink, ContainerGameMode::Simple);
+            match solution.and_then(|solution| SpellingBeePlay::from_solution(&letters, solution)) {
+                Ok(play) => {
+                    let id = app_state.plays.start(play_id.as_deref(), play.clone());
+                    let page = layout(play_page(&play, ""), Some("Play"), user);
+                    return (jar.add(play_cookie(&app_state, id)), page).into_response();
+                }
+                Err(err) => {
+                    let page = play_start_page(&letters, &err.to_string());
+                    return layout(page, Some("Play"), user).into_response();
+                }
+            }
+        }
+        (None, current) => current,
+    };
+    let page = match play {
+        Some(play) => play_page(&play, ""),
+        None => play_start_page("", ""),
+    };
+    layout(page, Some("Play"), user).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct GuessForm {
+    pub word: String,
+}
+
+async fn guess_route(
+    HxRequest(hx_request): HxRequest,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    jar: CookieJar,
+    Form(form): Form<GuessForm>,
+) -> Response {
+    let played = played(&app_state, &jar, |play| {
+        let message = guess_message(&form.word, play.guess(&form.word), play);
+        (play.clone(), message)
+    });
+    board(hx_request, &current_user, played)
+}
+
+async fn shuffle_route(
+    HxRequest(hx_request): HxRequest,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    jar: CookieJar,
+) -> Response {
+    let played = played(&app_state, &jar, |play| {
+        play.shuffle(OsRng.next_u64());
+        (play.clone(), String::new())
+    });
+    board(hx_request, &current_user, played)
+}
+
+/// Game of the play cookie, if it is still going.
+fn played<T>(
+    app_state: &SharedAppState,
+    jar: &CookieJar,
+    f: impl FnOnce(&mut SpellingBeePlay) -> T,
+) -> Option<T> {
+    let id = jar.get(PLAY_COOKIE)?.value().to_owned();
+    app_state.plays.update(&id, f)
+}
+
+/// HTMX swaps the board, anything else gets the whole page.
+/// A game gone meanwhile asks for the letters again.
+fn board(
+    hx_request: bool,
+    current_user: &Option<CurrentUser>,
+    played: Option<(SpellingBeePlay, String)>,
+) -> Response {
+    let Some((play, message)) = played else {
+        if hx_request {
+            let url = Uri::from_static(PLAY_URL);
+            return (VaryHxRequest, HxLocation::from_uri(url), ()).into_response();
+        }
+        return (VaryHxRequest, Redirect::to(PLAY_URL)).into_response();
+    };
+    let markup: Markup = if hx_request {
+        play_board(&play, &message)
+    } else {
+        layout(play_page(&play, &message), Some("Play"), user(current_user))
+    };
+    (VaryHxRequest, markup).into_response()
+}
+
+fn guess_message(
+    word: &str,
+    guess: Result<SolvedWord, Rejection>,
+    play: &SpellingBeePlay,
+) -> String {
+    let word = word.trim().to_lowercase();
+    match guess {
+        Ok(_) if play.is_complete() => format!("{}, every word found!", word),
+        Ok(found) if found.pangram => format!("Pangram! {} +{}", word, found.score),
+        Ok(found) => format!("{} +{}", word, found.score),
+        Err(rejection) => format!("{}: {}", word, rejection),
+    }
+}
+
+/// Lasts as long as the browser session, the game itself is dropped when idle.
+fn play_cookie(app_state: &SharedAppState, id: String) -> Cookie<'static> {
+    Cookie::build((PLAY_COOKIE, id))
+        .path(PLAY_URL)
+        .http_only(true)
+        .secure(!app_state.is_dev)
+        .same_site(SameSite::Lax)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app_state::AppState, config::AppConfig, routes};
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let config = AppConfig::for_tests("play_routes");
+        let app_state = AppState::new(&config).unwrap().shared();
+        routes::create_router().with_state(app_state)
+    }
+
+    async fn guess(app: &Router, word: &str, cookie: &str) -> String {
+        let request = Request::post(format!("{}/guess", PLAY_URL))
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(header::COOKIE, cookie)
+            .header("HX-Request", "true")
+            .body(Body::from(format!("word={}", word)))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn words_are_checked_in_the_session_game() {
+        let app = app();
+        let start = Request::get(format!("{}?letters=ZWIESLT", PLAY_URL))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(start).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let set_cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        let cookie = set_cookie.split(';').next().unwrap().to_owned();
+        assert!(cookie.starts_with("play="));
+
+        let board = guess(&app, "liszt", &cookie).await;
+        assert!(board.starts_with("<div id=\"play\">"));
+        assert!(board.contains("liszt +"));
+        assert!(board.contains("1 of"));
+        let board = guess(&app, "wiles", &cookie).await;
+        assert!(board.contains("missing the center letter"));
+        let board = guess(&app, "liszt", &cookie).await;
+        assert!(board.contains("already found"));
+
+        let request = Request::post(format!("{}/shuffle", PLAY_URL))
+            .header(header::COOKIE, "play=unknown")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    }
+}
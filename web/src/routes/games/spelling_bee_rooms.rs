@@ -0,0 +1,289 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        Path, State, WebSocketUpgrade,
+    },
+    http::StatusCode,
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Form, Router,
+};
+use maud::{html, Markup};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use words::spelling_bee::normalize_letters;
+
+use crate::{
+    accounts::{user, CurrentUser},
+    app_state::SharedAppState,
+    rate_limit::{PageRateLimit, RoomsQuota},
+    rooms::{Guess, Room, RoomEvent, RoomProgress},
+    templates::{
+        layout::layout,
+        spelling_bee::{
+            room_found_word, room_message, room_page, room_score, rooms_page, word_input,
+            ContainerGameMode, ROOMS_URL,
+        },
+    },
+};
+
+use super::{spelling_bee::solve, spelling_bee_permalink::SpellingBeePermalink};
+
+/// Guests without a name are shown as this.
+const GUEST_NAME: &str = "Guest";
+/// Added to the names guests give, so they can't pass for registered users.
+const GUEST_SUFFIX: &str = " (guest)";
+const MAX_NAME_LENGTH: usize = 24;
+
+pub fn create_router() -> Router<SharedAppState> {
+    Router::new()
+        .route("/", get(rooms_route).post(create_room_route))
+        .route("/:id", get(room_route))
+        .route("/:id/words", post(guess_route))
+        .route("/:id/ws", get(room_socket_route))
+}
+
+async fn rooms_route(current_user: Option<CurrentUser>) -> Markup {
+    layout(
+        rooms_page("", ""),
+        Some("Solve together"),
+        user(&current_user),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct RoomForm {
+    pub letters: String,
+}
+
+async fn create_room_route(
+    _: PageRateLimit<RoomsQuota>,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Form(form): Form<RoomForm>,
+) -> Response {
+    let letters = normalize_letters(&form.letters);
+    let permalink = SpellingBeePermalink::from_hints(&letters, None, None);
+    match solve(&app_state, &permalink, ContainerGameMode::Simple) {
+        Ok(solution) => match app_state.rooms.create(&letters, solution) {
+            Some(room) => Redirect::to(&format!("{}/{}", ROOMS_URL, room.id)).into_response(),
+            None => {
+                let page = rooms_page(&letters, "Every room is in use, try again later");
+                let page = layout(page, Some("Solve together"), user(&current_user));
+                (StatusCode::SERVICE_UNAVAILABLE, page).into_response()
+            }
+        },
+        Err(err) => {
+            let page = rooms_page(&letters, &err.to_string());
+            layout(page, Some("Solve together"), user(&current_user)).into_response()
+        }
+    }
+}
+
+async fn room_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(room) = app_state.rooms.get(&id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let user = user(&current_user);
+    let page = room_page(&room, &room.progress(), user.map(|u| u.username.as_str()));
+    let title = format!("Room {}", room.letters);
+    layout(page, Some(&title), user).into_response()
+}
+
+/// Word sent from the room form, by the socket as JSON or posted without JavaScript.
+#[derive(Deserialize)]
+pub struct GuessForm {
+    pub word: String,
+    pub name: Option<String>,
+}
+
+/// Without JavaScript the room is reloaded to show the words found so far.
+async fn guess_route(
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Path(id): Path<String>,
+    Form(form): Form<GuessForm>,
+) -> Response {
+    let Some(room) = app_state.rooms.get(&id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let finder = finder_name(&current_user, form.name.as_deref());
+    room.guess(&form.word, &finder);
+    Redirect::to(&format!("{}/{}", ROOMS_URL, room.id)).into_response()
+}
+
+async fn room_socket_route(
+    ws: WebSocketUpgrade,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    Path(id): Path<String>,
+) -> Response {
+    let Some(room) = app_state.rooms.get(&id) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    ws.on_upgrade(move |socket| room_socket(socket, room, current_user))
+}
+
+/// Sends the words found by everyone as they come and answers the words sent by this one.
+async fn room_socket(mut socket: WebSocket, room: Arc<Room>, current_user: Option<CurrentUser>) {
+    let mut events = room.subscribe();
+    // Anything found while the page was loading or the socket reconnecting
+    if send(&mut socket, progress_fragment(&room, &room.progress()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+    loop {
+        let fragment = tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(form) = serde_json::from_str::<GuessForm>(&text) else {
+                        continue;
+                    };
+                    let finder = finder_name(&current_user, form.name.as_deref());
+                    guess_fragment(&form.word, room.guess(&form.word, &finder))
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(RoomEvent::Found { found, score, found_count }) => html!(
+                    ul id="room-found" hx-swap-oob="afterbegin" { (room_found_word(&found)) }
+                    (room_score(&room, score, found_count))
+                ),
+                Err(RecvError::Lagged(_)) => progress_fragment(&room, &room.progress()),
+                Err(RecvError::Closed) => break,
+            },
+        };
+        if send(&mut socket, fragment).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, fragment: Markup) -> Result<(), axum::Error> {
+    socket.send(Message::Text(fragment.into_string())).await
+}
+
+/// Whole list of found words and the score.
+fn progress_fragment(room: &Room, progress: &RoomProgress) -> Markup {
+    html!(
+      ul id="room-found" {
+        @for found in &progress.found {
+          (room_found_word(found))
+        }
+      }
+      (room_score(room, progress.score, progress.found.len()))
+    )
+}
+
+/// Answer to the sender only, the word itself comes to everyone as an event.
+/// The word input is swapped for an empty one.
+fn guess_fragment(word: &str, guess: Guess) -> Markup {
+    let word = word.trim().to_lowercase();
+    let message = match guess {
+        Guess::Found(found) => format!("{} found, {} points", found.word, found.score),
+        Guess::NotInSolution => format!("{} is not a word of this puzzle", word),
+        Guess::AlreadyFound { finder } => format!("{} was already found by {}", word, finder),
+    };
+    html!((room_message(&message))(word_input()))
+}
+
+/// Logged in users are shown by their username, guests by the name they give marked as a guest.
+fn finder_name(current_user: &Option<CurrentUser>, name: Option<&str>) -> String {
+    if let Some(user) = user(current_user) {
+        return user.username.clone();
+    }
+    let name: String = name
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_NAME_LENGTH)
+        .collect();
+    if name.is_empty() {
+        GUEST_NAME.to_owned()
+    } else {
+        name + GUEST_SUFFIX
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app_state::AppState, config::AppConfig};
+    use futures_util::{SinkExt, StreamExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::{connect_async, tungstenite, MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    fn app_state() -> SharedAppState {
+        let config = AppConfig::for_tests("rooms_routes");
+        AppState::new(&config).unwrap().shared()
+    }
+
+    async fn next_text(client: &mut Client) -> String {
+        match client.next().await.unwrap().unwrap() {
+            tungstenite::Message::Text(text) => text,
+            message => panic!("unexpected message {:?}", message),
+        }
+    }
+
+    #[tokio::test]
+    async fn found_words_reach_everyone_in_the_room() {
+        let app_state = app_state();
+        let permalink = SpellingBeePermalink::from_hints("zwieslt", None, None);
+        let solution = solve(&app_state, &permalink, ContainerGameMode::Simple).unwrap();
+        let room = app_state.rooms.create("zwieslt", solution).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = create_router().with_state(app_state);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let url = format!("ws://{}/{}/ws", addr, room.id);
+        let (mut ann, _) = connect_async(&url).await.unwrap();
+        let (mut ben, _) = connect_async(&url).await.unwrap();
+        assert!(next_text(&mut ann).await.contains("0 of"));
+        assert!(next_text(&mut ben).await.contains("0 of"));
+
+        // As the HTMX ws support sends the form
+        let guess = r#"{"word":"Liszt","name":"ann","HEADERS":{"HX-Request":"true"}}"#;
+        ann.send(tungstenite::Message::Text(guess.to_owned()))
+            .await
+            .unwrap();
+        let event = next_text(&mut ben).await;
+        assert!(event.contains("hx-swap-oob=\"afterbegin\""));
+        assert!(event.contains("liszt"));
+        assert!(event.contains("by ann (guest)"));
+        assert!(event.contains("1 of"));
+
+        let ann_texts = [next_text(&mut ann).await, next_text(&mut ann).await];
+        assert!(ann_texts.iter().any(|text| text.contains("liszt found")));
+
+        ben.send(tungstenite::Message::Text(guess.replace("ann", "ben")))
+            .await
+            .unwrap();
+        assert!(next_text(&mut ben)
+            .await
+            .contains("liszt was already found by ann (guest)"));
+        assert_eq!(room.progress().found.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_room_is_not_found() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = create_router().with_state(app_state());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let url = format!("ws://{}/nope/ws", addr);
+        assert!(connect_async(&url).await.is_err());
+    }
+}
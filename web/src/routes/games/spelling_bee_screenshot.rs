@@ -0,0 +1,85 @@
+use anyhow::{bail, Context, Result};
+use axum::{
+    extract::{Multipart, State},
+    response::{IntoResponse, Redirect, Response},
+};
+use axum_htmx::{HxLocation, HxRequest, VaryHxRequest};
+use words::spelling_bee::normalize_letters;
+
+use crate::{
+    accounts::{user, user_id, CurrentUser},
+    app_state::SharedAppState,
+    rate_limit::{PageRateLimit, SolveQuota},
+    solution_options::SolutionOptions,
+    templates::spelling_bee::{input_simple, screenshot_form, ContainerGameMode, HINTS_URL},
+};
+
+use super::{
+    spelling_bee::{new_puzzle, save_puzzle, solve, solver_page},
+    spelling_bee_permalink::SpellingBeePermalink,
+};
+
+/// Uploads larger than this are refused, phone screenshots stay well below.
+pub const SCREENSHOT_MAX_SIZE: usize = 16 * 1024 * 1024;
+const SCREENSHOT_FIELD: &str = "screenshot";
+
+/// Reads the hive letters of an uploaded screenshot, saves their solution to the history and goes
+/// to it, the simple form is filled with them. Errors are shown next to the upload.
+pub async fn screenshot_route(
+    _: PageRateLimit<SolveQuota>,
+    HxRequest(hx_request): HxRequest,
+    current_user: Option<CurrentUser>,
+    State(app_state): State<SharedAppState>,
+    multipart: Multipart,
+) -> Response {
+    let letters = match read_letters(&app_state, multipart).await {
+        Ok(letters) => letters,
+        Err(err) => {
+            let error = format!("{:#}", err);
+            if hx_request {
+                return (VaryHxRequest, screenshot_form(&error)).into_response();
+            }
+            let input = input_simple("", &error, &SolutionOptions::default());
+            let page = solver_page(input, &[], HINTS_URL, user(&current_user));
+            return (VaryHxRequest, page).into_response();
+        }
+    };
+    let mode = ContainerGameMode::Simple;
+    let permalink = SpellingBeePermalink::from_hints(&letters, None, None);
+    // The permalink doesn't save, the upload is what was submitted
+    if let Ok(words) = solve(&app_state, &permalink, mode) {
+        let puzzle = new_puzzle(&permalink.letters, None, None, &words);
+        save_puzzle(&app_state, user_id(&current_user), puzzle).await;
+    }
+    let url = match permalink.url(mode, &SolutionOptions::default()) {
+        Ok(url) => url,
+        Err(err) => {
+            log::error!("Unable to build permalink: {}", err);
+            return (VaryHxRequest, screenshot_form(&err.to_string())).into_response();
+        }
+    };
+    if hx_request {
+        // The whole page is loaded like a boosted link, the url is pushed to the history
+        return (VaryHxRequest, HxLocation::from_uri(url), ()).into_response();
+    }
+    (VaryHxRequest, Redirect::to(&url.to_string())).into_response()
+}
+
+async fn read_letters(app_state: &SharedAppState, mut multipart: Multipart) -> Result<String> {
+    let mut image = None;
+    while let Some(field) = multipart.next_field().await? {
+        if field.name() == Some(SCREENSHOT_FIELD) {
+            image = Some(field.bytes().await?);
+            break;
+        }
+    }
+    let Some(image) = image.filter(|image| !image.is_empty()) else {
+        bail!("no screenshot uploaded");
+    };
+    // Decoding a large image takes a while, keep it off the async workers
+    let app_state = app_state.clone();
+    let letters = tokio::task::spawn_blocking(move || app_state.hive_reader.read(&image))
+        .await?
+        .context("unable to read the letters of the screenshot")?;
+    Ok(normalize_letters(&letters.letters()))
+}
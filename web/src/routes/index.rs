@@ -1,26 +1,20 @@
-use axum::extract::Query;
-use maud::{html, Markup};
-use serde::Deserialize;
-
-use crate::templates::{
-    layout::layout,
-    spelling_bee::{self, ContainerGameMode},
+use axum::{
+    extract::RawQuery,
+    response::{IntoResponse, Redirect, Response},
 };
 
-#[derive(Deserialize)]
-pub struct IndexPageQuery {
-    pub mode: Option<String>,
-}
+use super::games::spelling_bee::SpellingBeeGame;
+use crate::{
+    accounts::{user, CurrentUser},
+    games::{Game, GAMES},
+    templates::{index::games_list, layout::layout},
+};
 
-pub async fn index_route(Query(query): Query<IndexPageQuery>) -> Markup {
-    let mode = if query.mode.is_some_and(|m| m == "hinted") {
-        ContainerGameMode::Hinted
-    } else {
-        ContainerGameMode::Simple
-    };
-    let template = html!(
-        h1 { "Puzzle solver" };
-        (spelling_bee::container(mode))
-    );
-    layout(template, None)
+/// Lists the games, permalinks from before the games got their own pages are redirected.
+pub async fn index_route(current_user: Option<CurrentUser>, RawQuery(query): RawQuery) -> Response {
+    if let Some(query) = query.filter(|q| q.contains("mode=") || q.contains("letters=")) {
+        return Redirect::permanent(&format!("{}?{}", SpellingBeeGame.url(), query))
+            .into_response();
+    }
+    layout(games_list(GAMES), None, user(&current_user)).into_response()
 }
@@ -1,18 +1,27 @@
 mod assets;
-mod games;
+mod daily;
+mod definitions;
+pub mod games;
 mod index;
 
 use self::{
     assets::{assets_route, favicon_route},
+    definitions::define_route,
     index::index_route,
 };
-use crate::{app_state::SharedAppState, assets::ASSETS_PATH};
+use crate::{
+    app_state::SharedAppState,
+    assets::ASSETS_PATH,
+    templates::{daily::DAILY_URL, definitions::DEFINE_URL},
+};
 use axum::{routing::get, Router};
 
 pub fn create_router() -> Router<SharedAppState> {
     Router::new()
         .route("/", get(index_route))
+        .route(DEFINE_URL, get(define_route))
         .nest("/games", games::create_router())
+        .nest(DAILY_URL, daily::create_router())
         .route("/favicon.svg", get(favicon_route))
         .route(ASSETS_PATH, get(assets_route))
 }
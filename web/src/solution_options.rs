@@ -0,0 +1,326 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+};
+use utoipa::ToSchema;
+use words::{
+    morphology::{group_by_lemma, LemmaGroup},
+    spelling_bee::SolvedWord,
+    Frequencies, Inflection, Lemmatizer, WordsDict,
+};
+
+/// Which inflected words to leave out of a solution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HideInflections {
+    #[default]
+    None,
+    /// Plural nouns and third person verbs.
+    Plurals,
+    All,
+}
+
+impl HideInflections {
+    pub const ALL: [Self; 3] = [Self::None, Self::Plurals, Self::All];
+
+    pub fn as_query(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Plurals => "plurals",
+            Self::All => "all",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "Nothing",
+            Self::Plurals => "Plurals",
+            Self::All => "All inflections",
+        }
+    }
+
+    pub fn hides(&self, inflection: Option<Inflection>) -> bool {
+        match self {
+            Self::None => false,
+            Self::Plurals => inflection == Some(Inflection::Plural),
+            Self::All => inflection.is_some(),
+        }
+    }
+}
+
+/// Order of the words inside a section, ties are broken alphabetically.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Alphabet,
+    /// Shortest first.
+    Length,
+    /// Highest scoring first.
+    Score,
+    /// Most common first, needs a frequencies file.
+    Frequency,
+}
+
+impl SortOrder {
+    pub const ALL: [Self; 4] = [Self::Alphabet, Self::Length, Self::Score, Self::Frequency];
+
+    pub fn as_query(&self) -> &'static str {
+        match self {
+            Self::Alphabet => "alphabet",
+            Self::Length => "length",
+            Self::Score => "score",
+            Self::Frequency => "frequency",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Alphabet => "Alphabet",
+            Self::Length => "Length",
+            Self::Score => "Score",
+            Self::Frequency => "Frequency",
+        }
+    }
+
+    /// Groups are ordered by their first word, and by the best one for the score.
+    fn sort(
+        &self,
+        groups: &mut [LemmaGroup],
+        scores: &HashMap<&str, usize>,
+        frequencies: &Frequencies,
+    ) {
+        groups.sort_by(|a, b| a.words[0].0.cmp(&b.words[0].0));
+        match self {
+            Self::Alphabet => {}
+            Self::Length => groups.sort_by_key(|group| group.words[0].0.len()),
+            Self::Score => groups.sort_by_key(|group| {
+                Reverse(
+                    group
+                        .words
+                        .iter()
+                        .filter_map(|(word, _)| scores.get(word.as_str()))
+                        .max()
+                        .copied(),
+                )
+            }),
+            Self::Frequency => groups.sort_by_key(|group| Reverse(frequencies.get(&group.base))),
+        }
+    }
+}
+
+/// Sections of a solution, mirroring the grids of the hints page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    #[default]
+    None,
+    Letter,
+    Length,
+    /// Two letter prefix, like the two letter list.
+    Prefix,
+}
+
+impl GroupBy {
+    pub const ALL: [Self; 4] = [Self::None, Self::Letter, Self::Length, Self::Prefix];
+
+    pub fn as_query(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Letter => "letter",
+            Self::Length => "length",
+            Self::Prefix => "prefix",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::None => "Nothing",
+            Self::Letter => "Start letter",
+            Self::Length => "Length",
+            Self::Prefix => "Two letters",
+        }
+    }
+
+    /// Sections are ordered by the key, lengths before letters so they sort numerically.
+    fn key(&self, word: &str) -> (usize, String) {
+        match self {
+            Self::None => (0, String::new()),
+            Self::Letter => (0, word.chars().take(1).collect()),
+            Self::Length => (word.len(), String::new()),
+            Self::Prefix => (0, word.chars().take(2).collect()),
+        }
+    }
+
+    fn title(&self, (length, letters): &(usize, String)) -> Option<String> {
+        match self {
+            Self::None => None,
+            Self::Length => Some(format!("{} letters", length)),
+            Self::Letter | Self::Prefix => Some(letters.to_uppercase()),
+        }
+    }
+}
+
+/// Words of a solution section grouped by base form, the title is missing when not grouping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionSection {
+    pub title: Option<String>,
+    pub groups: Vec<LemmaGroup>,
+}
+
+/// How a solution is shown, shared by the forms, permalinks and the API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct SolutionOptions {
+    #[serde(default)]
+    pub hide: HideInflections,
+    #[serde(default)]
+    pub sort: SortOrder,
+    #[serde(default, rename = "group")]
+    pub group_by: GroupBy,
+    /// Only words containing the text are listed.
+    #[serde(default)]
+    pub contains: String,
+}
+
+impl SolutionOptions {
+    /// Query parameters that differ from the defaults.
+    pub fn query_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = vec![];
+        if self.hide != HideInflections::default() {
+            pairs.push(("hide".to_owned(), self.hide.as_query().to_owned()));
+        }
+        if self.sort != SortOrder::default() {
+            pairs.push(("sort".to_owned(), self.sort.as_query().to_owned()));
+        }
+        if self.group_by != GroupBy::default() {
+            pairs.push(("group".to_owned(), self.group_by.as_query().to_owned()));
+        }
+        if !self.contains.trim().is_empty() {
+            pairs.push(("contains".to_owned(), self.contains.trim().to_owned()));
+        }
+        pairs
+    }
+
+    /// Splits the words into sections, groups them by base form inside each one and sorts the
+    /// groups. Hidden inflections and words without the `contains` text are left out.
+    pub fn sections(
+        &self,
+        words: &[SolvedWord],
+        lemmatizer: &Lemmatizer,
+        dict: &WordsDict,
+        frequencies: &Frequencies,
+    ) -> Vec<SolutionSection> {
+        let contains = self.contains.trim().to_lowercase();
+        let scores: HashMap<&str, usize> =
+            words.iter().map(|w| (w.word.as_str(), w.score)).collect();
+        let mut sections: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for solved in words.iter().filter(|w| w.word.contains(&contains)) {
+            let lemma = lemmatizer.lemma(&solved.word, dict);
+            if self.hides(lemma.as_ref().map(|l| l.inflection)) {
+                continue;
+            }
+            sections
+                .entry(self.group_by.key(&solved.word))
+                .or_default()
+                .push((solved.word.clone(), lemma));
+        }
+        sections
+            .into_iter()
+            .map(|(key, words)| {
+                let mut groups = group_by_lemma(words);
+                self.sort.sort(&mut groups, &scores, frequencies);
+                SolutionSection {
+                    title: self.group_by.title(&key),
+                    groups,
+                }
+            })
+            .collect()
+    }
+
+    pub fn hides(&self, inflection: Option<Inflection>) -> bool {
+        self.hide.hides(inflection)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solved(word: &str, score: usize) -> SolvedWord {
+        SolvedWord {
+            word: word.to_owned(),
+            score,
+            pangram: false,
+        }
+    }
+
+    fn titles_and_words(sections: &[SolutionSection]) -> Vec<(Option<String>, Vec<&str>)> {
+        sections
+            .iter()
+            .map(|section| {
+                let words = section
+                    .groups
+                    .iter()
+                    .flat_map(|g| g.words.iter().map(|(w, _)| w.as_str()))
+                    .collect();
+                (section.title.clone(), words)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_sorts_groups_and_filters() {
+        let dict = WordsDict::from_text(b"cat\ncats\ncoat\ntactic\ntoast\n");
+        let lemmatizer = Lemmatizer::new("");
+        let frequencies = Frequencies::from_text("coat 30\ncat 20\ntoast 10\n");
+        let words = vec![
+            solved("toast", 5),
+            solved("cats", 4),
+            solved("tactic", 6),
+            solved("cat", 1),
+            solved("coat", 1),
+        ];
+        let sections = |options: SolutionOptions| {
+            let sections = options.sections(&words, &lemmatizer, &dict, &frequencies);
+            titles_and_words(&sections)
+                .into_iter()
+                .map(|(title, words)| (title.unwrap_or_default(), words.join(" ")))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sections(SolutionOptions::default()),
+            vec![("".to_owned(), "cat cats coat tactic toast".to_owned())]
+        );
+        assert_eq!(
+            sections(SolutionOptions {
+                sort: SortOrder::Score,
+                ..Default::default()
+            }),
+            vec![("".to_owned(), "tactic toast cat cats coat".to_owned())]
+        );
+        assert_eq!(
+            sections(SolutionOptions {
+                sort: SortOrder::Frequency,
+                group_by: GroupBy::Letter,
+                ..Default::default()
+            }),
+            vec![
+                ("C".to_owned(), "coat cat cats".to_owned()),
+                ("T".to_owned(), "toast tactic".to_owned())
+            ]
+        );
+        assert_eq!(
+            sections(SolutionOptions {
+                group_by: GroupBy::Length,
+                contains: " AT".to_owned(),
+                ..Default::default()
+            }),
+            vec![
+                ("3 letters".to_owned(), "cat".to_owned()),
+                ("4 letters".to_owned(), "cats coat".to_owned())
+            ]
+        );
+    }
+}
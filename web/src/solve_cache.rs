@@ -0,0 +1,257 @@
+use prometheus::IntCounter;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    mem::size_of,
+    sync::{Arc, Mutex},
+};
+use words::{spelling_bee::SolvedWord, LettersMask};
+
+/// Solutions are kept until they take this many bytes, least recently used first out.
+pub const SOLVE_CACHE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Letter matrix and two letter list, each sorted.
+type Hints = (Vec<(u8, Vec<usize>)>, Vec<[u8; 2]>);
+
+/// Normalized puzzle: the order of the outer letters and of the hints doesn't change the solution.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SolveKey {
+    center: u8,
+    letters: LettersMask,
+    /// `None` for puzzles solved without hints.
+    hints: Option<Hints>,
+}
+
+impl SolveKey {
+    /// `letters` are validated already, center letter first.
+    pub fn simple(letters: &str) -> Self {
+        Self {
+            center: letters.as_bytes().first().copied().unwrap_or_default(),
+            letters: LettersMask::from_word(letters.as_bytes()),
+            hints: None,
+        }
+    }
+
+    pub fn hinted(
+        letters: &str,
+        letter_matrix: &[(u8, Vec<usize>)],
+        letter_list: &[[u8; 2]],
+    ) -> Self {
+        let mut letter_matrix = letter_matrix.to_vec();
+        letter_matrix.sort_unstable();
+        let mut letter_list = letter_list.to_vec();
+        letter_list.sort_unstable();
+        Self {
+            hints: Some((letter_matrix, letter_list)),
+            ..Self::simple(letters)
+        }
+    }
+
+    fn size(&self) -> usize {
+        let hints = self.hints.as_ref().map_or(0, |(matrix, list)| {
+            matrix
+                .iter()
+                .map(|(_, lengths)| {
+                    size_of::<(u8, Vec<usize>)>() + lengths.len() * size_of::<usize>()
+                })
+                .sum::<usize>()
+                + list.len() * 2
+        });
+        size_of::<Self>() + hints
+    }
+}
+
+/// Hits and misses shared by the caches of every dictionary snapshot, so they add up across reloads.
+#[derive(Debug, Clone)]
+pub struct SolveCacheCounters {
+    pub hits: IntCounter,
+    pub misses: IntCounter,
+}
+
+/// Hits and misses are counted since startup, the rest is the cache of the current dictionary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SolveCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+    pub capacity_bytes: usize,
+}
+
+#[derive(Debug)]
+struct Entry {
+    solution: Arc<Vec<SolvedWord>>,
+    bytes: usize,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct Lru {
+    entries: HashMap<SolveKey, Entry>,
+    /// Keys by their last use, the first one goes out first.
+    by_use: BTreeMap<u64, SolveKey>,
+    bytes: usize,
+    clock: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &SolveKey) -> Option<Arc<Vec<SolvedWord>>> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        self.by_use.remove(&entry.last_used);
+        entry.last_used = self.clock;
+        self.by_use.insert(self.clock, key.clone());
+        Some(entry.solution.clone())
+    }
+
+    fn insert(&mut self, key: SolveKey, solution: Arc<Vec<SolvedWord>>, capacity: usize) {
+        let bytes = key.size() + solution_size(&solution);
+        if bytes > capacity {
+            return;
+        }
+        if let Some(old) = self.entries.remove(&key) {
+            self.by_use.remove(&old.last_used);
+            self.bytes -= old.bytes;
+        }
+        while self.bytes + bytes > capacity {
+            let Some((_, oldest)) = self.by_use.pop_first() else {
+                break;
+            };
+            if let Some(old) = self.entries.remove(&oldest) {
+                self.bytes -= old.bytes;
+            }
+        }
+        self.clock += 1;
+        self.by_use.insert(self.clock, key.clone());
+        let entry = Entry {
+            solution,
+            bytes,
+            last_used: self.clock,
+        };
+        self.entries.insert(key, entry);
+        self.bytes += bytes;
+    }
+}
+
+fn solution_size(solution: &[SolvedWord]) -> usize {
+    solution
+        .iter()
+        .map(|solved| size_of::<SolvedWord>() + solved.word.capacity())
+        .sum()
+}
+
+/// Solutions of the puzzles solved lately, the same letters come again on every keystroke
+/// and from other users. Each dictionary snapshot has its own, so a reload starts afresh.
+#[derive(Debug)]
+pub struct SolveCache {
+    capacity_bytes: usize,
+    lru: Mutex<Lru>,
+    counters: SolveCacheCounters,
+}
+
+impl SolveCache {
+    pub fn new(capacity_bytes: usize, counters: SolveCacheCounters) -> Self {
+        Self {
+            capacity_bytes,
+            lru: Mutex::default(),
+            counters,
+        }
+    }
+
+    /// Cached solution of the puzzle, `solve` runs unlocked on a miss.
+    pub fn get_or_solve(
+        &self,
+        key: SolveKey,
+        solve: impl FnOnce() -> Vec<SolvedWord>,
+    ) -> Arc<Vec<SolvedWord>> {
+        if let Some(solution) = self.lru.lock().unwrap().touch(&key) {
+            self.counters.hits.inc();
+            return solution;
+        }
+        self.counters.misses.inc();
+        let solution = Arc::new(solve());
+        self.lru
+            .lock()
+            .unwrap()
+            .insert(key, solution.clone(), self.capacity_bytes);
+        solution
+    }
+
+    pub fn stats(&self) -> SolveCacheStats {
+        let lru = self.lru.lock().unwrap();
+        SolveCacheStats {
+            hits: self.counters.hits.get(),
+            misses: self.counters.misses.get(),
+            entries: lru.entries.len(),
+            bytes: lru.bytes,
+            capacity_bytes: self.capacity_bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters() -> SolveCacheCounters {
+        SolveCacheCounters {
+            hits: IntCounter::new("hits", "Hits").unwrap(),
+            misses: IntCounter::new("misses", "Misses").unwrap(),
+        }
+    }
+
+    fn solved(word: &str) -> SolvedWord {
+        SolvedWord {
+            word: word.to_owned(),
+            score: 1,
+            pangram: false,
+        }
+    }
+
+    #[test]
+    fn same_puzzle_is_solved_once() {
+        let counters = counters();
+        let cache = SolveCache::new(SOLVE_CACHE_BYTES, counters.clone());
+        let first = cache.get_or_solve(SolveKey::simple("zwieslt"), || vec![solved("zest")]);
+        let again = cache.get_or_solve(SolveKey::simple("ztlsewi"), || unreachable!());
+        assert_eq!(first, again);
+        cache.get_or_solve(SolveKey::simple("wzieslt"), Vec::new);
+        cache.get_or_solve(
+            SolveKey::hinted("zwieslt", &[(b'z', vec![4])], &[]),
+            Vec::new,
+        );
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 3, 3));
+        // A new snapshot starts empty but keeps counting
+        let reloaded = SolveCache::new(SOLVE_CACHE_BYTES, counters);
+        reloaded.get_or_solve(SolveKey::simple("zwieslt"), Vec::new);
+        let stats = reloaded.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 4, 1));
+        assert_eq!(
+            SolveKey::hinted("zwieslt", &[], &[*b"zi", *b"ze"]),
+            SolveKey::hinted("zwieslt", &[], &[*b"ze", *b"zi"])
+        );
+    }
+
+    #[test]
+    fn least_recently_used_go_first() {
+        let entry_bytes = SolveKey::simple("abcdefg").size() + solution_size(&[solved("bead")]);
+        let cache = SolveCache::new(entry_bytes * 2, counters());
+        cache.get_or_solve(SolveKey::simple("abcdefg"), || vec![solved("bead")]);
+        cache.get_or_solve(SolveKey::simple("bacdefg"), || vec![solved("abed")]);
+        cache.get_or_solve(SolveKey::simple("abcdefg"), || unreachable!());
+        cache.get_or_solve(SolveKey::simple("cabdefg"), || vec![solved("face")]);
+
+        let stats = cache.stats();
+        assert_eq!(stats.entries, 2);
+        assert!(stats.bytes <= stats.capacity_bytes);
+        cache.get_or_solve(SolveKey::simple("abcdefg"), || unreachable!());
+        let solved_again = std::cell::Cell::new(false);
+        cache.get_or_solve(SolveKey::simple("bacdefg"), || {
+            solved_again.set(true);
+            vec![solved("abed")]
+        });
+        assert!(solved_again.get());
+    }
+}
@@ -0,0 +1,189 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Mutex,
+};
+
+use super::{
+    non_empty, unique_words, NewPuzzle, Puzzle, PuzzleId, PuzzleSummary, Storage, User, UserId,
+};
+
+/// Keeps everything until restart, for tests and throwaway runs.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    data: Mutex<MemoryData>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryData {
+    /// Found words are kept apart, per user.
+    puzzles: Vec<Puzzle>,
+    solved: BTreeSet<(Option<UserId>, PuzzleId)>,
+    found: HashMap<(Option<UserId>, PuzzleId), BTreeSet<String>>,
+    users: Vec<(User, String)>,
+    sessions: HashMap<String, (UserId, DateTime<Utc>)>,
+}
+
+impl MemoryData {
+    fn solved_puzzle(&self, id: PuzzleId, user: Option<UserId>) -> Option<&Puzzle> {
+        if !self.solved.contains(&(user, id)) {
+            return None;
+        }
+        self.puzzles.iter().find(|p| p.id == id)
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn save_puzzle(&self, new: &NewPuzzle, user: Option<UserId>) -> Result<PuzzleId> {
+        let mut data = self.data.lock().unwrap();
+        let letter_matrix = non_empty(new.letter_matrix.as_deref());
+        let letter_list = non_empty(new.letter_list.as_deref());
+        let existing = data.puzzles.iter().find(|p| {
+            p.game == new.game
+                && p.letters == new.letters
+                && p.letter_matrix == letter_matrix
+                && p.letter_list == letter_list
+                && p.date == new.date
+        });
+        let id = match existing {
+            Some(existing) => existing.id,
+            None => {
+                let id = data.puzzles.len() as PuzzleId + 1;
+                data.puzzles.push(Puzzle {
+                    id,
+                    game: new.game.clone(),
+                    letters: new.letters.clone(),
+                    letter_matrix,
+                    letter_list,
+                    date: new.date,
+                    words: unique_words(&new.words),
+                    found: Default::default(),
+                });
+                id
+            }
+        };
+        data.solved.insert((user, id));
+        Ok(id)
+    }
+
+    fn puzzle(&self, id: PuzzleId, user: Option<UserId>) -> Result<Option<Puzzle>> {
+        let data = self.data.lock().unwrap();
+        Ok(data.solved_puzzle(id, user).map(|puzzle| Puzzle {
+            found: data.found.get(&(user, id)).cloned().unwrap_or_default(),
+            ..puzzle.clone()
+        }))
+    }
+
+    fn puzzles(
+        &self,
+        game: &str,
+        user: Option<UserId>,
+        limit: usize,
+    ) -> Result<Vec<PuzzleSummary>> {
+        let data = self.data.lock().unwrap();
+        let mut matching: Vec<&Puzzle> = data
+            .puzzles
+            .iter()
+            .filter(|p| p.game == game && data.solved.contains(&(user, p.id)))
+            .collect();
+        matching.sort_by_key(|p| std::cmp::Reverse((p.date, p.id)));
+        Ok(matching
+            .into_iter()
+            .take(limit)
+            .map(|p| PuzzleSummary {
+                id: p.id,
+                letters: p.letters.clone(),
+                hinted: p.letter_matrix.is_some() || p.letter_list.is_some(),
+                date: p.date,
+                words_count: p.words.len(),
+                found_count: data.found.get(&(user, p.id)).map_or(0, BTreeSet::len),
+            })
+            .collect())
+    }
+
+    fn solved_dates(&self, game: &str, user: Option<UserId>) -> Result<Vec<NaiveDate>> {
+        let data = self.data.lock().unwrap();
+        let dates: BTreeSet<NaiveDate> = data
+            .puzzles
+            .iter()
+            .filter(|p| p.game == game && data.solved.contains(&(user, p.id)))
+            .map(|p| p.date)
+            .collect();
+        Ok(dates.into_iter().rev().collect())
+    }
+
+    fn set_found(
+        &self,
+        id: PuzzleId,
+        user: Option<UserId>,
+        word: &str,
+        found: bool,
+    ) -> Result<bool> {
+        let mut data = self.data.lock().unwrap();
+        let Some(puzzle) = data.solved_puzzle(id, user) else {
+            return Ok(false);
+        };
+        if !puzzle.words.iter().any(|w| w.word == word) {
+            return Ok(false);
+        }
+        let words = data.found.entry((user, id)).or_default();
+        if found {
+            words.insert(word.to_owned());
+        } else {
+            words.remove(word);
+        }
+        Ok(true)
+    }
+
+    fn create_user(&self, username: &str, password_hash: &str) -> Result<Option<User>> {
+        let mut data = self.data.lock().unwrap();
+        if data.users.iter().any(|(user, _)| user.username == username) {
+            return Ok(None);
+        }
+        let user = User {
+            id: data.users.len() as UserId + 1,
+            username: username.to_owned(),
+        };
+        data.users.push((user.clone(), password_hash.to_owned()));
+        Ok(Some(user))
+    }
+
+    fn user_credentials(&self, username: &str) -> Result<Option<(User, String)>> {
+        let data = self.data.lock().unwrap();
+        Ok(data
+            .users
+            .iter()
+            .find(|(user, _)| user.username == username)
+            .cloned())
+    }
+
+    fn create_session(&self, token: &str, user: UserId, expires: DateTime<Utc>) -> Result<()> {
+        let mut data = self.data.lock().unwrap();
+        let now = Utc::now();
+        data.sessions.retain(|_, (_, expires)| *expires > now);
+        data.sessions.insert(token.to_owned(), (user, expires));
+        Ok(())
+    }
+
+    fn session_user(&self, token: &str, now: DateTime<Utc>) -> Result<Option<User>> {
+        let data = self.data.lock().unwrap();
+        let Some((id, _)) = data
+            .sessions
+            .get(token)
+            .filter(|(_, expires)| *expires > now)
+        else {
+            return Ok(None);
+        };
+        Ok(data
+            .users
+            .iter()
+            .find(|(user, _)| user.id == *id)
+            .map(|(user, _)| user.clone()))
+    }
+
+    fn delete_session(&self, token: &str) -> Result<()> {
+        self.data.lock().unwrap().sessions.remove(token);
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+    path::Path,
+};
+use words::spelling_bee::SolvedWord;
+
+mod memory_storage;
+mod sqlite_storage;
+
+pub use memory_storage::MemoryStorage;
+pub use sqlite_storage::SqliteStorage;
+
+/// Path that keeps everything in memory instead of a database file.
+pub const MEMORY_PATH: &str = ":memory:";
+
+pub const SPELLING_BEE: &str = "spelling_bee";
+
+pub type PuzzleId = i64;
+pub type UserId = i64;
+
+/// Puzzle as it was submitted, solved with the dictionary of that moment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewPuzzle {
+    pub game: String,
+    pub letters: String,
+    pub letter_matrix: Option<String>,
+    pub letter_list: Option<String>,
+    pub date: NaiveDate,
+    pub words: Vec<SolvedWord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub id: PuzzleId,
+    pub game: String,
+    pub letters: String,
+    pub letter_matrix: Option<String>,
+    pub letter_list: Option<String>,
+    pub date: NaiveDate,
+    pub words: Vec<SolvedWord>,
+    pub found: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleSummary {
+    pub id: PuzzleId,
+    pub letters: String,
+    pub hinted: bool,
+    pub date: NaiveDate,
+    pub words_count: usize,
+    pub found_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    pub id: UserId,
+    pub username: String,
+}
+
+/// Puzzles are shared, each user keeps the ones they solved and the words they found.
+/// `None` as the user is everyone who is not logged in, they share one history.
+pub trait Storage: fmt::Debug + Send + Sync {
+    /// Same puzzle submitted again on the same day keeps its first id and solution.
+    fn save_puzzle(&self, puzzle: &NewPuzzle, user: Option<UserId>) -> Result<PuzzleId>;
+
+    /// `None` unless the user solved the puzzle.
+    fn puzzle(&self, id: PuzzleId, user: Option<UserId>) -> Result<Option<Puzzle>>;
+
+    /// Most recent first.
+    fn puzzles(&self, game: &str, user: Option<UserId>, limit: usize)
+        -> Result<Vec<PuzzleSummary>>;
+
+    /// Days the user solved a puzzle of the game on, most recent first.
+    fn solved_dates(&self, game: &str, user: Option<UserId>) -> Result<Vec<NaiveDate>>;
+
+    /// Marks a word of the solution as found or not.
+    /// Returns `false` when the word is not in the solution or the user didn't solve the puzzle.
+    fn set_found(
+        &self,
+        id: PuzzleId,
+        user: Option<UserId>,
+        word: &str,
+        found: bool,
+    ) -> Result<bool>;
+
+    /// `None` when the username is taken.
+    fn create_user(&self, username: &str, password_hash: &str) -> Result<Option<User>>;
+
+    /// User with the hash of their password.
+    fn user_credentials(&self, username: &str) -> Result<Option<(User, String)>>;
+
+    /// Expired sessions are dropped along the way.
+    fn create_session(&self, token: &str, user: UserId, expires: DateTime<Utc>) -> Result<()>;
+
+    /// User of the session, `None` once it expired.
+    fn session_user(&self, token: &str, now: DateTime<Utc>) -> Result<Option<User>>;
+
+    fn delete_session(&self, token: &str) -> Result<()>;
+}
+
+pub fn open(path: &Path) -> Result<Box<dyn Storage>> {
+    if path.as_os_str() == MEMORY_PATH {
+        return Ok(Box::new(MemoryStorage::default()));
+    }
+    Ok(Box::new(SqliteStorage::open(path)?))
+}
+
+/// Empty hints are the same as no hints.
+fn non_empty(hint: Option<&str>) -> Option<String> {
+    hint.map(str::trim)
+        .filter(|hint| !hint.is_empty())
+        .map(str::to_owned)
+}
+
+/// Solvers may find a word twice, it is kept once at its first position.
+fn unique_words(words: &[SolvedWord]) -> Vec<SolvedWord> {
+    let mut seen = HashSet::new();
+    words
+        .iter()
+        .filter(|word| seen.insert(&word.word))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_puzzle(letters: &str, day: u32) -> NewPuzzle {
+        NewPuzzle {
+            game: SPELLING_BEE.to_owned(),
+            letters: letters.to_owned(),
+            letter_matrix: None,
+            letter_list: Some("ab-2\n".to_owned()),
+            date: NaiveDate::from_ymd_opt(2024, 9, day).unwrap(),
+            words: vec![
+                SolvedWord {
+                    word: "abba".to_owned(),
+                    score: 1,
+                    pangram: false,
+                },
+                SolvedWord {
+                    word: "abcdefg".to_owned(),
+                    score: 14,
+                    pangram: true,
+                },
+            ],
+        }
+    }
+
+    fn check_storage(storage: &dyn Storage) {
+        let first = storage
+            .save_puzzle(&new_puzzle("abcdefg", 1), None)
+            .unwrap();
+        let second = storage
+            .save_puzzle(&new_puzzle("gfedcba", 2), None)
+            .unwrap();
+        assert_ne!(first, second);
+        assert_eq!(
+            storage
+                .save_puzzle(&new_puzzle("abcdefg", 1), None)
+                .unwrap(),
+            first
+        );
+
+        assert!(storage.set_found(first, None, "abba", true).unwrap());
+        assert!(!storage.set_found(first, None, "nope", true).unwrap());
+
+        let puzzle = storage.puzzle(first, None).unwrap().unwrap();
+        let expected = new_puzzle("abcdefg", 1);
+        assert_eq!(puzzle.letters, expected.letters);
+        assert_eq!(puzzle.letter_matrix, None);
+        assert_eq!(puzzle.letter_list.as_deref(), Some("ab-2"));
+        assert_eq!(puzzle.date, expected.date);
+        assert_eq!(puzzle.words, expected.words);
+        assert_eq!(puzzle.found, BTreeSet::from(["abba".to_owned()]));
+        assert_eq!(storage.puzzle(second + 100, None).unwrap(), None);
+
+        let puzzles = storage.puzzles(SPELLING_BEE, None, 10).unwrap();
+        assert_eq!(
+            puzzles.iter().map(|p| p.id).collect::<Vec<_>>(),
+            vec![second, first]
+        );
+        assert!(puzzles[1].hinted);
+        assert_eq!((puzzles[1].words_count, puzzles[1].found_count), (2, 1));
+        assert_eq!(storage.puzzles(SPELLING_BEE, None, 1).unwrap().len(), 1);
+        assert!(storage.puzzles("other", None, 10).unwrap().is_empty());
+        assert_eq!(
+            storage.solved_dates(SPELLING_BEE, None).unwrap(),
+            vec![new_puzzle("", 2).date, new_puzzle("", 1).date]
+        );
+
+        let mut repeated = new_puzzle("abcdefg", 3);
+        repeated.words.push(repeated.words[0].clone());
+        let third = storage.save_puzzle(&repeated, None).unwrap();
+        assert_eq!(
+            storage.puzzle(third, None).unwrap().unwrap().words,
+            expected.words
+        );
+
+        assert!(storage.set_found(first, None, "abba", false).unwrap());
+        assert!(storage
+            .puzzle(first, None)
+            .unwrap()
+            .unwrap()
+            .found
+            .is_empty());
+    }
+
+    fn check_users(storage: &dyn Storage) {
+        let alice = storage.create_user("alice", "hash").unwrap().unwrap();
+        assert_eq!(storage.create_user("alice", "other").unwrap(), None);
+        assert_eq!(
+            storage.user_credentials("alice").unwrap(),
+            Some((alice.clone(), "hash".to_owned()))
+        );
+        assert_eq!(storage.user_credentials("bob").unwrap(), None);
+
+        // Same puzzle, each user with their own history and found words
+        let shared = storage
+            .save_puzzle(&new_puzzle("abcdefg", 1), None)
+            .unwrap();
+        assert!(storage.set_found(shared, None, "abba", true).unwrap());
+        assert!(storage
+            .puzzles(SPELLING_BEE, Some(alice.id), 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(storage.puzzle(shared, Some(alice.id)).unwrap(), None);
+        assert!(!storage
+            .set_found(shared, Some(alice.id), "abba", true)
+            .unwrap());
+        let id = storage
+            .save_puzzle(&new_puzzle("abcdefg", 1), Some(alice.id))
+            .unwrap();
+        assert_eq!(id, shared);
+        assert!(storage
+            .puzzle(id, Some(alice.id))
+            .unwrap()
+            .unwrap()
+            .found
+            .is_empty());
+        assert!(storage
+            .set_found(id, Some(alice.id), "abcdefg", true)
+            .unwrap());
+        let puzzles = storage.puzzles(SPELLING_BEE, Some(alice.id), 10).unwrap();
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].found_count, 1);
+        assert_eq!(
+            storage.puzzle(id, None).unwrap().unwrap().found,
+            BTreeSet::from(["abba".to_owned()])
+        );
+
+        let now = Utc::now();
+        let expires = now + chrono::Duration::days(1);
+        storage.create_session("token", alice.id, expires).unwrap();
+        assert_eq!(
+            storage.session_user("token", now).unwrap(),
+            Some(alice.clone())
+        );
+        assert_eq!(storage.session_user("token", expires).unwrap(), None);
+        assert_eq!(storage.session_user("other", now).unwrap(), None);
+        storage.delete_session("token").unwrap();
+        assert_eq!(storage.session_user("token", now).unwrap(), None);
+    }
+
+    #[test]
+    fn memory_storage() {
+        check_storage(&MemoryStorage::default());
+        check_users(&MemoryStorage::default());
+    }
+
+    #[test]
+    fn sqlite_storage() {
+        check_storage(&SqliteStorage::open_in_memory().unwrap());
+        check_users(&SqliteStorage::open_in_memory().unwrap());
+    }
+}
@@ -0,0 +1,398 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension};
+use std::{path::Path, sync::Mutex};
+use words::spelling_bee::SolvedWord;
+
+use super::{
+    non_empty, unique_words, NewPuzzle, Puzzle, PuzzleId, PuzzleSummary, Storage, User, UserId,
+};
+
+/// Stands for everyone who is not logged in, user ids start at 1.
+const ANONYMOUS: UserId = 0;
+
+/// Applied in order, `user_version` of the database keeps how many are done.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE puzzles (
+        id INTEGER PRIMARY KEY,
+        game TEXT NOT NULL,
+        letters TEXT NOT NULL,
+        letter_matrix TEXT NOT NULL DEFAULT '',
+        letter_list TEXT NOT NULL DEFAULT '',
+        date TEXT NOT NULL,
+        UNIQUE (game, letters, letter_matrix, letter_list, date)
+    );
+    CREATE INDEX puzzles_game_date ON puzzles (game, date);
+    CREATE TABLE solution_words (
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles (id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        word TEXT NOT NULL,
+        score INTEGER NOT NULL,
+        pangram INTEGER NOT NULL,
+        PRIMARY KEY (puzzle_id, word)
+    );
+    CREATE TABLE found_words (
+        puzzle_id INTEGER NOT NULL,
+        word TEXT NOT NULL,
+        PRIMARY KEY (puzzle_id, word),
+        FOREIGN KEY (puzzle_id, word) REFERENCES solution_words (puzzle_id, word) ON DELETE CASCADE
+    );
+",
+    "
+    CREATE TABLE users (
+        id INTEGER PRIMARY KEY,
+        username TEXT NOT NULL UNIQUE,
+        password_hash TEXT NOT NULL,
+        created TEXT NOT NULL
+    );
+    CREATE TABLE sessions (
+        token TEXT PRIMARY KEY,
+        user_id INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        expires TEXT NOT NULL
+    );
+    CREATE TABLE solved_puzzles (
+        user_id INTEGER NOT NULL,
+        puzzle_id INTEGER NOT NULL REFERENCES puzzles (id) ON DELETE CASCADE,
+        PRIMARY KEY (user_id, puzzle_id)
+    );
+    INSERT INTO solved_puzzles (user_id, puzzle_id) SELECT 0, id FROM puzzles;
+    ALTER TABLE found_words RENAME TO shared_found_words;
+    CREATE TABLE found_words (
+        user_id INTEGER NOT NULL,
+        puzzle_id INTEGER NOT NULL,
+        word TEXT NOT NULL,
+        PRIMARY KEY (user_id, puzzle_id, word),
+        FOREIGN KEY (puzzle_id, word) REFERENCES solution_words (puzzle_id, word) ON DELETE CASCADE
+    );
+    INSERT INTO found_words (user_id, puzzle_id, word)
+        SELECT 0, puzzle_id, word FROM shared_found_words;
+    DROP TABLE shared_found_words;
+",
+];
+
+#[derive(Debug)]
+pub struct SqliteStorage {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path)
+            .with_context(|| format!("unable to open database {}", path.display()))?;
+        Self::new(connection)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(mut connection: Connection) -> Result<Self> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut connection)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+fn migrate(connection: &mut Connection) -> Result<()> {
+    let version: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = connection.transaction()?;
+        tx.execute_batch(migration)
+            .with_context(|| format!("database migration {} failed", i + 1))?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn owner(user: Option<UserId>) -> UserId {
+    user.unwrap_or(ANONYMOUS)
+}
+
+fn is_solved(connection: &Connection, id: PuzzleId, user: Option<UserId>) -> Result<bool> {
+    let solved = connection
+        .query_row(
+            "SELECT 1 FROM solved_puzzles WHERE user_id = ?1 AND puzzle_id = ?2",
+            params![owner(user), id],
+            |_| Ok(()),
+        )
+        .optional()?;
+    Ok(solved.is_some())
+}
+
+fn insert_puzzle(
+    tx: &rusqlite::Transaction,
+    new: &NewPuzzle,
+    key: &[&dyn rusqlite::ToSql],
+) -> Result<PuzzleId> {
+    tx.execute(
+        "INSERT INTO puzzles (game, letters, letter_matrix, letter_list, date)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        key,
+    )?;
+    let id = tx.last_insert_rowid();
+    let mut insert_word = tx.prepare(
+        "INSERT INTO solution_words (puzzle_id, position, word, score, pangram)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (position, word) in unique_words(&new.words).iter().enumerate() {
+        insert_word.execute(params![id, position, word.word, word.score, word.pangram])?;
+    }
+    Ok(id)
+}
+
+impl Storage for SqliteStorage {
+    fn save_puzzle(&self, new: &NewPuzzle, user: Option<UserId>) -> Result<PuzzleId> {
+        let mut connection = self.connection.lock().unwrap();
+        let tx = connection.transaction()?;
+        let letter_matrix = non_empty(new.letter_matrix.as_deref()).unwrap_or_default();
+        let letter_list = non_empty(new.letter_list.as_deref()).unwrap_or_default();
+        let key = params![new.game, new.letters, letter_matrix, letter_list, new.date];
+
+        let existing = tx
+            .query_row(
+                "SELECT id FROM puzzles
+                 WHERE game = ?1 AND letters = ?2 AND letter_matrix = ?3 AND letter_list = ?4 AND date = ?5",
+                key,
+                |row| row.get(0),
+            )
+            .optional()?;
+        let id = match existing {
+            Some(id) => id,
+            None => insert_puzzle(&tx, new, key)?,
+        };
+        tx.execute(
+            "INSERT OR IGNORE INTO solved_puzzles (user_id, puzzle_id) VALUES (?1, ?2)",
+            params![owner(user), id],
+        )?;
+        tx.commit()?;
+        Ok(id)
+    }
+
+    fn puzzle(&self, id: PuzzleId, user: Option<UserId>) -> Result<Option<Puzzle>> {
+        let connection = self.connection.lock().unwrap();
+        if !is_solved(&connection, id, user)? {
+            return Ok(None);
+        }
+        let puzzle = connection
+            .query_row(
+                "SELECT game, letters, letter_matrix, letter_list, date FROM puzzles WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(Puzzle {
+                        id,
+                        game: row.get(0)?,
+                        letters: row.get(1)?,
+                        letter_matrix: non_empty(Some(&row.get::<_, String>(2)?)),
+                        letter_list: non_empty(Some(&row.get::<_, String>(3)?)),
+                        date: row.get(4)?,
+                        words: vec![],
+                        found: Default::default(),
+                    })
+                },
+            )
+            .optional()?;
+        let Some(mut puzzle) = puzzle else {
+            return Ok(None);
+        };
+
+        puzzle.words = connection
+            .prepare(
+                "SELECT word, score, pangram FROM solution_words
+                 WHERE puzzle_id = ?1 ORDER BY position",
+            )?
+            .query_map([id], |row| {
+                Ok(SolvedWord {
+                    word: row.get(0)?,
+                    score: row.get(1)?,
+                    pangram: row.get(2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        puzzle.found = connection
+            .prepare("SELECT word FROM found_words WHERE user_id = ?1 AND puzzle_id = ?2")?
+            .query_map(params![owner(user), id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(Some(puzzle))
+    }
+
+    fn puzzles(
+        &self,
+        game: &str,
+        user: Option<UserId>,
+        limit: usize,
+    ) -> Result<Vec<PuzzleSummary>> {
+        let connection = self.connection.lock().unwrap();
+        let mut query = connection.prepare(
+            "SELECT id, letters, letter_matrix != '' OR letter_list != '', date,
+                (SELECT COUNT(*) FROM solution_words WHERE puzzle_id = puzzles.id),
+                (SELECT COUNT(*) FROM found_words
+                 WHERE user_id = solved_puzzles.user_id AND puzzle_id = puzzles.id)
+             FROM puzzles JOIN solved_puzzles ON puzzle_id = id
+             WHERE game = ?1 AND user_id = ?2
+             ORDER BY date DESC, id DESC LIMIT ?3",
+        )?;
+        let puzzles = query
+            .query_map(params![game, owner(user), limit], |row| {
+                Ok(PuzzleSummary {
+                    id: row.get(0)?,
+                    letters: row.get(1)?,
+                    hinted: row.get(2)?,
+                    date: row.get(3)?,
+                    words_count: row.get(4)?,
+                    found_count: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(puzzles)
+    }
+
+    fn solved_dates(&self, game: &str, user: Option<UserId>) -> Result<Vec<NaiveDate>> {
+        let connection = self.connection.lock().unwrap();
+        let dates = connection
+            .prepare(
+                "SELECT DISTINCT date FROM puzzles JOIN solved_puzzles ON puzzle_id = id
+                 WHERE game = ?1 AND user_id = ?2
+                 ORDER BY date DESC",
+            )?
+            .query_map(params![game, owner(user)], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(dates)
+    }
+
+    fn set_found(
+        &self,
+        id: PuzzleId,
+        user: Option<UserId>,
+        word: &str,
+        found: bool,
+    ) -> Result<bool> {
+        let connection = self.connection.lock().unwrap();
+        if !is_solved(&connection, id, user)? {
+            return Ok(false);
+        }
+        let in_solution = connection
+            .query_row(
+                "SELECT 1 FROM solution_words WHERE puzzle_id = ?1 AND word = ?2",
+                params![id, word],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !in_solution {
+            return Ok(false);
+        }
+        if found {
+            connection.execute(
+                "INSERT OR IGNORE INTO found_words (user_id, puzzle_id, word) VALUES (?1, ?2, ?3)",
+                params![owner(user), id, word],
+            )?;
+        } else {
+            connection.execute(
+                "DELETE FROM found_words WHERE user_id = ?1 AND puzzle_id = ?2 AND word = ?3",
+                params![owner(user), id, word],
+            )?;
+        }
+        Ok(true)
+    }
+
+    fn create_user(&self, username: &str, password_hash: &str) -> Result<Option<User>> {
+        let connection = self.connection.lock().unwrap();
+        let inserted = connection.execute(
+            "INSERT INTO users (username, password_hash, created) VALUES (?1, ?2, ?3)",
+            params![username, password_hash, Utc::now()],
+        );
+        match inserted {
+            Ok(_) => Ok(Some(User {
+                id: connection.last_insert_rowid(),
+                username: username.to_owned(),
+            })),
+            Err(rusqlite::Error::SqliteFailure(err, _))
+                if err.code == ErrorCode::ConstraintViolation =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn user_credentials(&self, username: &str) -> Result<Option<(User, String)>> {
+        let connection = self.connection.lock().unwrap();
+        let credentials = connection
+            .query_row(
+                "SELECT id, password_hash FROM users WHERE username = ?1",
+                [username],
+                |row| {
+                    let user = User {
+                        id: row.get(0)?,
+                        username: username.to_owned(),
+                    };
+                    Ok((user, row.get(1)?))
+                },
+            )
+            .optional()?;
+        Ok(credentials)
+    }
+
+    fn create_session(&self, token: &str, user: UserId, expires: DateTime<Utc>) -> Result<()> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute("DELETE FROM sessions WHERE expires <= ?1", [Utc::now()])?;
+        connection.execute(
+            "INSERT INTO sessions (token, user_id, expires) VALUES (?1, ?2, ?3)",
+            params![token, user, expires],
+        )?;
+        Ok(())
+    }
+
+    fn session_user(&self, token: &str, now: DateTime<Utc>) -> Result<Option<User>> {
+        let connection = self.connection.lock().unwrap();
+        let user = connection
+            .query_row(
+                "SELECT users.id, username FROM sessions JOIN users ON users.id = user_id
+                 WHERE token = ?1 AND expires > ?2",
+                params![token, now],
+                |row| {
+                    Ok(User {
+                        id: row.get(0)?,
+                        username: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(user)
+    }
+
+    fn delete_session(&self, token: &str) -> Result<()> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute("DELETE FROM sessions WHERE token = ?1", [token])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn found_words_from_before_accounts_are_shared() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(MIGRATIONS[0]).unwrap();
+        connection.pragma_update(None, "user_version", 1).unwrap();
+        connection
+            .execute_batch(
+                "INSERT INTO puzzles (id, game, letters, date) VALUES (1, 'spelling_bee', 'abcdefg', '2024-09-01');
+                 INSERT INTO solution_words VALUES (1, 0, 'abba', 1, 0);
+                 INSERT INTO found_words VALUES (1, 'abba');",
+            )
+            .unwrap();
+        migrate(&mut connection).unwrap();
+        let storage = SqliteStorage::new(connection).unwrap();
+        let puzzle = storage.puzzle(1, None).unwrap().unwrap();
+        assert!(puzzle.found.contains("abba"));
+        assert_eq!(storage.puzzles("spelling_bee", None, 10).unwrap().len(), 1);
+    }
+}
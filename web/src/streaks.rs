@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+
+/// Days in a row with a solved puzzle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    /// Still running until the end of the day after the last solved one.
+    pub current: usize,
+    pub longest: usize,
+}
+
+impl Streaks {
+    /// `dates` are the days with a solved puzzle, most recent first without repeats.
+    pub fn new(dates: &[NaiveDate], today: NaiveDate) -> Self {
+        let mut streaks = Self::default();
+        let mut run = 0;
+        let mut is_first_run = dates
+            .first()
+            .is_some_and(|&last| last == today || last.succ_opt() == Some(today));
+        for (i, date) in dates.iter().enumerate() {
+            let follows = i > 0 && date.succ_opt() == Some(dates[i - 1]);
+            if !follows {
+                if i > 0 {
+                    is_first_run = false;
+                }
+                run = 0;
+            }
+            run += 1;
+            streaks.longest = streaks.longest.max(run);
+            if is_first_run {
+                streaks.current = run;
+            }
+        }
+        streaks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 9, day).unwrap()
+    }
+
+    #[test]
+    fn it_counts_days_in_a_row() {
+        let dates = [day(10), day(9), day(8), day(5), day(4), day(3), day(2)];
+        assert_eq!(
+            Streaks::new(&dates, day(10)),
+            Streaks {
+                current: 3,
+                longest: 4
+            }
+        );
+        assert_eq!(Streaks::new(&dates, day(11)).current, 3);
+        assert_eq!(Streaks::new(&dates, day(12)).current, 0);
+        assert_eq!(Streaks::new(&[], day(12)), Streaks::default());
+    }
+}
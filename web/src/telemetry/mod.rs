@@ -0,0 +1,64 @@
+use anyhow::Result;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{Protocol, WithExportConfig};
+use opentelemetry_sdk::{trace::SdkTracerProvider, Resource};
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::config::AppConfig;
+
+mod otlp_file;
+mod request;
+pub mod server_timing;
+
+pub use request::trace_request;
+
+const SERVICE_NAME: &str = "word-puzzles";
+
+/// Sends the spans left in the exporters when the server stops.
+pub struct TelemetryGuard(Option<SdkTracerProvider>);
+
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.0.take() {
+            if let Err(err) = provider.shutdown() {
+                eprintln!("Unable to flush traces: {}", err);
+            }
+        }
+    }
+}
+
+/// Logs go to stderr filtered by `RUST_LOG` (`info` by default), `log` records included.
+/// Spans are also exported when an OTLP collector or file is configured.
+pub fn init(config: &AppConfig) -> Result<TelemetryGuard> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let provider = tracer_provider(config)?;
+    let otel_layer = provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME)));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(otel_layer)
+        .try_init()?;
+    Ok(TelemetryGuard(provider))
+}
+
+fn tracer_provider(config: &AppConfig) -> Result<Option<SdkTracerProvider>> {
+    if config.otlp_endpoint.is_none() && config.otlp_file.is_none() {
+        return Ok(None);
+    }
+    let resource = Resource::builder().with_service_name(SERVICE_NAME).build();
+    let mut builder = SdkTracerProvider::builder().with_resource(resource);
+    if let Some(endpoint) = &config.otlp_endpoint {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpJson)
+            .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')))
+            .build()?;
+        builder = builder.with_batch_exporter(exporter);
+    }
+    if let Some(path) = &config.otlp_file {
+        builder = builder.with_batch_exporter(otlp_file::OtlpFileExporter::open(path)?);
+    }
+    Ok(Some(builder.build()))
+}
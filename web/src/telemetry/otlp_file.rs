@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use opentelemetry_proto::{
+    tonic::collector::trace::v1::ExportTraceServiceRequest,
+    transform::{
+        common::tonic::ResourceAttributesWithSchema,
+        trace::tonic::group_spans_by_resource_and_scope,
+    },
+};
+use opentelemetry_sdk::{
+    error::{OTelSdkError, OTelSdkResult},
+    trace::{SpanData, SpanExporter},
+    Resource,
+};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::Mutex,
+};
+
+/// Appends every batch of spans as a line of OTLP JSON, the format the collector's
+/// `otlpjsonfile` receiver reads.
+#[derive(Debug)]
+pub struct OtlpFileExporter {
+    file: Mutex<File>,
+    resource: ResourceAttributesWithSchema,
+}
+
+impl OtlpFileExporter {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("unable to open {}", path.display()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+            resource: ResourceAttributesWithSchema::default(),
+        })
+    }
+
+    fn write(&self, batch: Vec<SpanData>) -> Result<()> {
+        let request = ExportTraceServiceRequest {
+            resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+        };
+        let mut line = serde_json::to_vec(&request)?;
+        line.push(b'\n');
+        self.file.lock().unwrap().write_all(&line)?;
+        Ok(())
+    }
+}
+
+impl SpanExporter for OtlpFileExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        self.write(batch)
+            .map_err(|err| OTelSdkError::InternalFailure(format!("{:#}", err)))
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.file
+            .lock()
+            .unwrap()
+            .flush()
+            .map_err(|err| OTelSdkError::InternalFailure(err.to_string()))
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.resource = resource.into();
+    }
+}
@@ -0,0 +1,103 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::{field::Empty, Instrument};
+
+use super::server_timing::{collect_stages, header_value};
+use crate::utils::random_token::random_token;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+const SERVER_TIMING_HEADER: HeaderName = HeaderName::from_static("server-timing");
+const MAX_REQUEST_ID_LENGTH: usize = 64;
+
+/// Runs the request in a span with its id, logs it when done and reports the stages
+/// it went through in `Server-Timing`, `total` last.
+/// An id sent by a proxy in `X-Request-Id` is kept, so the logs of both can be matched.
+pub async fn trace_request(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_owned)
+        .unwrap_or_else(|| random_token(8));
+    let span = tracing::info_span!(
+        "request",
+        otel.name = %format!("{} {}", request.method(), request.uri().path()),
+        method = %request.method(),
+        path = %request.uri().path(),
+        request_id = %request_id,
+        status = Empty,
+    );
+
+    let start = Instant::now();
+    let (mut response, mut stages) = collect_stages(next.run(request))
+        .instrument(span.clone())
+        .await;
+    let total = start.elapsed();
+    stages.push(("total", total));
+
+    let status = response.status().as_u16();
+    span.record("status", status);
+    span.in_scope(|| {
+        tracing::info!(
+            status,
+            elapsed_ms = total.as_secs_f64() * 1000.0,
+            "finished"
+        )
+    });
+
+    let headers = response.headers_mut();
+    headers.insert(SERVER_TIMING_HEADER, header_value(&stages));
+    if let Ok(id) = HeaderValue::from_str(&request_id) {
+        headers.insert(REQUEST_ID_HEADER, id);
+    }
+    response
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    (1..=MAX_REQUEST_ID_LENGTH).contains(&id.len())
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::server_timing::stage;
+    use axum::{body::Body, middleware::from_fn, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn responses_carry_request_id_and_timings() {
+        let app = Router::new()
+            .route("/", get(|| async { stage("search", || "found") }))
+            .layer(from_fn(trace_request));
+
+        let request = Request::get("/").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let timing = response.headers()[SERVER_TIMING_HEADER].to_str().unwrap();
+        assert!(timing.starts_with("search;dur="));
+        assert!(timing.contains(", total;dur="));
+        assert_eq!(response.headers()[REQUEST_ID_HEADER].len(), 16);
+
+        let request = Request::get("/")
+            .header(REQUEST_ID_HEADER, "from-proxy-1")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "from-proxy-1");
+
+        let request = Request::get("/")
+            .header(REQUEST_ID_HEADER, "not valid\tid")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_ne!(response.headers()[REQUEST_ID_HEADER], "not valid\tid");
+    }
+}
@@ -0,0 +1,75 @@
+use axum::http::HeaderValue;
+use std::{
+    cell::RefCell,
+    future::Future,
+    time::{Duration, Instant},
+};
+use tracing::Instrument;
+
+tokio::task_local! {
+    static STAGES: RefCell<Vec<(&'static str, Duration)>>;
+}
+
+/// Runs a stage of the request in its own span and times it for the `Server-Timing` header.
+/// Outside of a request, or on another task, only the span is kept.
+pub fn stage<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    let span = tracing::info_span!("stage", otel.name = name);
+    let start = Instant::now();
+    let result = span.in_scope(f);
+    let elapsed = start.elapsed();
+    let _ = STAGES.try_with(|stages| stages.borrow_mut().push((name, elapsed)));
+    result
+}
+
+/// Same as [`stage`] for work that is awaited, like a blocking task.
+pub async fn stage_async<F: Future>(name: &'static str, future: F) -> F::Output {
+    let span = tracing::info_span!("stage", otel.name = name);
+    let start = Instant::now();
+    let result = future.instrument(span).await;
+    let elapsed = start.elapsed();
+    let _ = STAGES.try_with(|stages| stages.borrow_mut().push((name, elapsed)));
+    result
+}
+
+/// Runs `future` collecting the stages it goes through, in order.
+pub async fn collect_stages<F: Future>(future: F) -> (F::Output, Vec<(&'static str, Duration)>) {
+    STAGES
+        .scope(RefCell::default(), async {
+            let output = future.await;
+            (output, STAGES.with(RefCell::take))
+        })
+        .await
+}
+
+/// `name;dur=1.234` for each stage, durations in milliseconds.
+pub fn header_value(stages: &[(&'static str, Duration)]) -> HeaderValue {
+    let value = stages
+        .iter()
+        .map(|(name, duration)| format!("{};dur={:.3}", name, duration.as_secs_f64() * 1000.0))
+        .collect::<Vec<String>>()
+        .join(", ");
+    HeaderValue::from_str(&value).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stages_of_the_request_are_collected() {
+        let (sum, stages) = collect_stages(async {
+            let parsed = stage("parse", || 40);
+            tokio::task::yield_now().await;
+            stage("search", || parsed + 2)
+        })
+        .await;
+        assert_eq!(sum, 42);
+        let names: Vec<&str> = stages.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["parse", "search"]);
+
+        // Outside of a request the result is the same
+        assert_eq!(stage("parse", || 1), 1);
+        let value = header_value(&[("search", Duration::from_micros(1500))]);
+        assert_eq!(value, "search;dur=1.500");
+    }
+}
@@ -0,0 +1,57 @@
+use maud::{html, Markup};
+
+use crate::accounts::{LOGIN_URL, REGISTER_URL};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountForm {
+    Login,
+    Register,
+}
+
+impl AccountForm {
+    pub fn title(self) -> &'static str {
+        match self {
+            AccountForm::Login => "Log in",
+            AccountForm::Register => "Create account",
+        }
+    }
+
+    fn url(self) -> &'static str {
+        match self {
+            AccountForm::Login => LOGIN_URL,
+            AccountForm::Register => REGISTER_URL,
+        }
+    }
+}
+
+/// Both forms take a username and a password, each links to the other one.
+pub fn account_form(form: AccountForm, username: &str, error: &str) -> Markup {
+    let (other, password_autocomplete) = match form {
+        AccountForm::Login => (AccountForm::Register, "current-password"),
+        AccountForm::Register => (AccountForm::Login, "new-password"),
+    };
+    html!(
+      h1 { (form.title()) };
+      form id="account-form" method="post" action=(form.url()) {
+        fieldset {
+          label for="username" { "Username" };
+          input id="username"
+                name="username"
+                autocomplete="username"
+                required
+                value=(username);
+        }
+        fieldset {
+          label for="password" { "Password" };
+          input id="password"
+                type="password"
+                name="password"
+                autocomplete=(password_autocomplete)
+                required;
+        }
+        button type="submit" { (form.title()) };
+        div.errors id="account-error" { (error) }
+      }
+      a href=(other.url()) { (other.title()) };
+    )
+}
@@ -0,0 +1,20 @@
+use maud::{html, Markup};
+
+pub fn words_overlay(allow: &str, block: &str, words_count: usize, status: &str) -> Markup {
+    html!(
+      h1 { "Dictionary overlays" };
+      p { "Effective dictionary has " (words_count) " words. Blocked words win over allowed ones." };
+      form id="words-overlay-form" method="post" action="/admin/words" {
+        fieldset {
+          label for="allow" { "Allowed words" };
+          textarea id="allow" name="allow" rows="20" cols="30" { (allow) };
+        }
+        fieldset {
+          label for="block" { "Blocked words" };
+          textarea id="block" name="block" rows="20" cols="30" { (block) };
+        }
+        button type="submit" { "Save" };
+        div id="words-overlay-status" { (status) }
+      }
+    )
+}
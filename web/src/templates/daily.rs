@@ -0,0 +1,49 @@
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+use maud::{html, Markup};
+
+use crate::games::{DailyPuzzle, Game};
+
+pub const DAILY_URL: &str = "/daily";
+
+/// `today` is the date of the newest puzzle, in `timezone`.
+pub fn daily_page(
+    date: NaiveDate,
+    today: NaiveDate,
+    timezone: Tz,
+    puzzles: &[(&dyn Game, DailyPuzzle)],
+) -> Markup {
+    html!(
+      h1 {
+        @if date == today { "Puzzles of the day" } @else { "Puzzles of " (date) }
+      };
+      p { (date.format("%A %-d %B %Y")) ", new puzzles every day at midnight " (timezone.name()) "." };
+      @if puzzles.is_empty() {
+        p { "No puzzles for this day." };
+      }
+      ul id="daily" {
+        @for (game, puzzle) in puzzles {
+          li {
+            h2 { (game.icon()) " " (game.name()) ": " (puzzle.name) };
+            @for (text, url) in &puzzle.links {
+              a href=(url) { (text) };
+              " "
+            }
+          }
+        }
+      }
+      a href={(DAILY_URL) "/archive"} { "Past days" };
+    )
+}
+
+/// Most recent day first.
+pub fn daily_archive(dates: &[NaiveDate]) -> Markup {
+    html!(
+      h1 { "Past puzzles of the day" };
+      ul id="daily-archive" {
+        @for date in dates {
+          li { a href={(DAILY_URL) "/" (date)} { (date.format("%A %-d %B %Y")) } };
+        }
+      }
+    )
+}
@@ -0,0 +1,34 @@
+use maud::{html, Markup};
+use words::Definition;
+
+pub const DEFINE_URL: &str = "/define";
+
+/// `None` when no definitions are configured.
+pub fn word_definitions(definitions: Option<&[Definition]>) -> Markup {
+    html!(
+      @match definitions {
+        None => p { "Definitions are not available." },
+        Some([]) => p { "No definitions found." },
+        Some(definitions) => dl {
+          @for definition in definitions {
+            dt { (definition.part_of_speech) };
+            dd { (definition.text) };
+          }
+        },
+      }
+    )
+}
+
+/// Word that loads its definitions the first time it is expanded.
+pub fn expandable_word(word: &str) -> Markup {
+    html!(
+      details hx-get={(DEFINE_URL) "?w=" (word)}
+              hx-trigger="toggle once"
+              hx-target="find .definitions" {
+        summary { (word) };
+        div.definitions {
+          a href={(DEFINE_URL) "?w=" (word)} { "Definitions" };
+        }
+      }
+    )
+}
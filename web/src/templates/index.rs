@@ -0,0 +1,19 @@
+use maud::{html, Markup};
+
+use crate::games::Game;
+
+pub fn games_list(games: &[&dyn Game]) -> Markup {
+    html!(
+      h1 { "Puzzle solver" };
+      ul id="games" {
+        @for game in games {
+          li {
+            a href=(game.url()) {
+              h2 { (game.icon()) " " (game.name()) };
+            }
+            p { (game.description()) };
+          }
+        }
+      }
+    )
+}
@@ -1,4 +1,10 @@
-use crate::assets::ASSETS_PREFIX;
+use crate::{
+    accounts::{LOGIN_URL, LOGOUT_URL},
+    assets::ASSETS_PREFIX,
+    games::GAMES,
+    storage::User,
+    templates::daily::DAILY_URL,
+};
 use maud::{html, Markup, DOCTYPE};
 
 fn head(title: &str) -> Markup {
@@ -25,12 +31,40 @@ fn head(title: &str) -> Markup {
     )
 }
 
-pub fn layout(content: Markup, title: Option<&str>) -> Markup {
+fn nav(user: Option<&User>) -> Markup {
+    html!(
+      nav {
+        a href="/" { "Puzzle solver" };
+        @for game in GAMES {
+          " "
+          a href=(game.url()) { (game.icon()) " " (game.name()) };
+        }
+        " "
+        a href=(DAILY_URL) { "📅 Daily" };
+        " "
+        span id="account" {
+          @if let Some(user) = user {
+            (user.username) " "
+            form method="post" action=(LOGOUT_URL) {
+              button type="submit" { "Log out" };
+            }
+          } @else {
+            a href=(LOGIN_URL) { "Log in" };
+          }
+        }
+      }
+    )
+}
+
+/// `user` is the logged in one, shown in the navigation.
+pub fn layout(content: Markup, title: Option<&str>, user: Option<&User>) -> Markup {
     html!(
       (DOCTYPE)
       html lang="en" {
         (head(title.unwrap_or("Puzzle solver")))
-        body hx-boost="true" {
+        // HTMX leaves error responses out, `429` pages carry a message worth showing
+        body hx-boost="true" "hx-on::before-swap"="if (event.detail.xhr.status === 429) event.detail.shouldSwap = true" {
+          (nav(user))
           (content)
         }
       }
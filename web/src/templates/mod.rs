@@ -1,2 +1,7 @@
+pub mod accounts;
+pub mod admin;
+pub mod daily;
+pub mod definitions;
+pub mod index;
 pub mod layout;
 pub mod spelling_bee;
@@ -1,28 +1,36 @@
-use crate::templates::spelling_bee::{input_hinted, input_simple, solution};
+use crate::{
+    solution_options::SolutionSection,
+    templates::spelling_bee::{hints_link, screenshot_form, solution, PLAY_URL, ROOMS_URL},
+};
 use maud::{html, Markup};
 
-use super::ContainerGameMode;
+pub const SPELLING_BEE_URL: &str = "/games/spelling_bee";
 
 fn switch() -> Markup {
     html!(
       div hx-target="#spelling-bee-form" hx-swap="outerHTML" hx-include="#spelling-bee-form" hx-params="*" {
-        button hx-get="/games/spelling_bee/input_simple" hx-push-url="/?mode=simple" { "Only letters" };
-        button hx-get="/games/spelling_bee/input_hinted" hx-push-url="/?mode=hinted" { "Advanced hints" };
+        a href={(SPELLING_BEE_URL) "?mode=simple"} hx-get={(SPELLING_BEE_URL) "/input_simple"} hx-push-url={(SPELLING_BEE_URL) "?mode=simple"} { "Only letters" };
+        " "
+        a href={(SPELLING_BEE_URL) "?mode=hinted"} hx-get={(SPELLING_BEE_URL) "/input_hinted"} hx-push-url={(SPELLING_BEE_URL) "?mode=hinted"} { "Advanced hints" };
       }
     )
 }
 
-pub fn container(mode: ContainerGameMode) -> Markup {
-    let input = match mode {
-        ContainerGameMode::Simple => input_simple("", ""),
-        ContainerGameMode::Hinted => input_hinted("", "", "", ""),
-    };
+/// `input` is the form of the current mode, `hints_url` the hints page of the same puzzle.
+pub fn container(input: Markup, sections: &[SolutionSection], hints_url: &str) -> Markup {
     html!(
       div {
-        h2 { "Spelling bee" };
         (switch())
         (input)
-        (solution(vec![]))
+        (screenshot_form(""))
+        (solution(sections))
+        (hints_link(hints_url, false))
+        " "
+        a href={(SPELLING_BEE_URL) "/history"} { "History" };
+        " "
+        a href=(ROOMS_URL) { "Solve together" };
+        " "
+        a href=(PLAY_URL) { "Play" };
       }
     )
 }
@@ -1,4 +1,23 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ContainerGameMode {
     Simple,
     Hinted,
 }
+
+impl ContainerGameMode {
+    /// Value of the `mode` query parameter, anything unknown is simple.
+    pub fn from_query(mode: Option<&str>) -> Self {
+        if mode.is_some_and(|m| m == "hinted") {
+            Self::Hinted
+        } else {
+            Self::Simple
+        }
+    }
+
+    pub fn as_query(&self) -> &'static str {
+        match self {
+            Self::Simple => "simple",
+            Self::Hinted => "hinted",
+        }
+    }
+}
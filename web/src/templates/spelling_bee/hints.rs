@@ -0,0 +1,119 @@
+use maud::{html, Markup};
+use words::spelling_bee_hints::{HintStep, SpellingBeeHints};
+
+use super::input_letters;
+use crate::templates::definitions::expandable_word;
+
+pub const HINTS_URL: &str = "/games/spelling_bee/hints";
+
+fn step_title(step: HintStep) -> &'static str {
+    match step {
+        HintStep::Lengths => "Words per length",
+        HintStep::FirstLetters => "Words per first letter",
+        HintStep::Prefixes => "Words per first two letters",
+        HintStep::Shapes => "First letter and length",
+        HintStep::Words => "Words",
+    }
+}
+
+fn counts<K: std::fmt::Display>(counts: impl IntoIterator<Item = (K, usize)>) -> Markup {
+    html!(
+      ul {
+        @for (key, count) in counts {
+          li { (key) ": " (count) };
+        }
+      }
+    )
+}
+
+fn step_hints(hints: &SpellingBeeHints, step: HintStep) -> Markup {
+    match step {
+        HintStep::Lengths => counts(
+            hints
+                .lengths
+                .iter()
+                .map(|(length, count)| (format!("{} letters", length), *count)),
+        ),
+        HintStep::FirstLetters => counts(
+            hints
+                .first_letters
+                .iter()
+                .map(|(letter, count)| (letter.to_ascii_uppercase(), *count)),
+        ),
+        HintStep::Prefixes => counts(
+            hints
+                .prefixes
+                .iter()
+                .map(|(prefix, count)| (prefix.to_uppercase(), *count)),
+        ),
+        HintStep::Shapes => html!(
+          ul {
+            @for (letter, length) in &hints.shapes {
+              li { code { (letter.to_ascii_uppercase()) ("_".repeat(length - 1)) } };
+            }
+          }
+        ),
+        HintStep::Words => html!(
+          ul {
+            @for word in &hints.words {
+              li { (expandable_word(word)) };
+            }
+          }
+        ),
+    }
+}
+
+/// Steps up to `step` are shown, `next_url` reveals the following one.
+pub fn hints(hints: &SpellingBeeHints, step: Option<HintStep>, next_url: Option<&str>) -> Markup {
+    let revealed = HintStep::ALL.into_iter().filter(|s| Some(*s) <= step);
+    html!(
+      div id="spelling-bee-hints" {
+        p {
+          (hints.words_count) " words, " (hints.pangrams_count) " pangrams, "
+          (hints.total_score) " points."
+        };
+        @for step in revealed {
+          section {
+            h3 { (step_title(step)) };
+            (step_hints(hints, step))
+          }
+        }
+        @if let (Some(next), Some(next_url)) = (HintStep::next(step), next_url) {
+          a href=(next_url)
+            hx-get=(next_url)
+            hx-target="#spelling-bee-hints"
+            hx-swap="outerHTML"
+            hx-push-url="true" { "Show " (step_title(next).to_lowercase()) };
+        }
+      }
+    )
+}
+
+/// Link from the solver, swapped out of band as the letters change.
+pub fn hints_link(hints_url: &str, oob: bool) -> Markup {
+    html!(
+      a id="spelling-bee-hints-link" href=(hints_url) hx-swap-oob=[oob.then_some("true")] {
+        "Hints without the answers"
+      };
+    )
+}
+
+/// `hints` is missing until letters are entered, `solver_url` opens the puzzle in the solver.
+pub fn hints_page(
+    letters: &str,
+    letters_error: &str,
+    hints: Option<Markup>,
+    solver_url: &str,
+) -> Markup {
+    html!(
+      h1 { "Spelling bee hints" };
+      a href=(solver_url) { "Open in solver" };
+      form method="get" action=(HINTS_URL) {
+        (input_letters(letters, letters_error))
+        button type="submit" { "Get hints" };
+      }
+      @if let Some(hints) = hints {
+        (hints)
+      }
+    )
+}
@@ -0,0 +1,99 @@
+use maud::{html, Markup};
+
+use crate::{
+    accounts::LOGIN_URL,
+    storage::{Puzzle, PuzzleId, PuzzleSummary},
+    streaks::Streaks,
+};
+
+use super::SPELLING_BEE_URL;
+
+const HISTORY_URL: &str = "/games/spelling_bee/history";
+
+/// Without an account the history is shared by everyone who is not logged in.
+pub fn history(puzzles: &[PuzzleSummary], streaks: &Streaks, is_logged_in: bool) -> Markup {
+    html!(
+      h1 { "Spelling bee history" };
+      a href=(SPELLING_BEE_URL) { "Back to solver" };
+      @if !is_logged_in {
+        p {
+          "This history is shared by everyone who is not logged in. "
+          a href=(LOGIN_URL) { "Log in" } " to keep your own."
+        };
+      }
+      p id="spelling-bee-streaks" {
+        "Current streak " (streaks.current) " days, longest " (streaks.longest) " days."
+      };
+      @if puzzles.is_empty() {
+        p { "No puzzles solved yet." };
+      } @else {
+        table id="spelling-bee-history" {
+          thead {
+            tr {
+              th { "Date" };
+              th { "Letters" };
+              th { "Mode" };
+              th { "Found" };
+            }
+          }
+          tbody {
+            @for puzzle in puzzles {
+              tr {
+                td { (puzzle.date) };
+                td {
+                  a href={(HISTORY_URL) "/" (puzzle.id)} { (puzzle.letters) };
+                };
+                td { @if puzzle.hinted { "Hinted" } @else { "Letters" } };
+                td { (puzzle.found_count) " / " (puzzle.words_count) };
+              }
+            }
+          }
+        }
+      }
+    )
+}
+
+/// `solver_url` is the permalink that opens the puzzle in the solver.
+pub fn history_puzzle(puzzle: &Puzzle, solver_url: &str) -> Markup {
+    let total_score: usize = puzzle.words.iter().map(|w| w.score).sum();
+    let found_score: usize = puzzle
+        .words
+        .iter()
+        .filter(|w| puzzle.found.contains(&w.word))
+        .map(|w| w.score)
+        .sum();
+    html!(
+      h1 { "Spelling bee " (puzzle.letters) };
+      a href=(HISTORY_URL) { "Back to history" };
+      " "
+      a href=(solver_url) { "Open in solver" };
+      p { "Solved on " (puzzle.date) ", found " (found_score) " of " (total_score) " points." };
+      @if let Some(letter_matrix) = &puzzle.letter_matrix {
+        pre { (letter_matrix) };
+      }
+      @if let Some(letter_list) = &puzzle.letter_list {
+        pre { (letter_list) };
+      }
+      ul id="spelling-bee-history-solution" {
+        @for word in &puzzle.words {
+          li { (found_word(puzzle.id, &word.word, puzzle.found.contains(&word.word))) }
+        }
+      }
+    )
+}
+
+/// Toggles the word between found and not found.
+pub fn found_word(id: PuzzleId, word: &str, found: bool) -> Markup {
+    html!(
+      form method="post"
+            action={(HISTORY_URL) "/" (id) "/found"}
+            hx-post={(HISTORY_URL) "/" (id) "/found"}
+            hx-swap="outerHTML" {
+        input type="hidden" name="word" value=(word);
+        input type="hidden" name="found" value=(!found);
+        button type="submit" {
+          @if found { s { (word) } } @else { (word) }
+        }
+      }
+    )
+}
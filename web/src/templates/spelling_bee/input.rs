@@ -1,6 +1,9 @@
 use maud::{html, Markup};
 use words::spelling_bee::LETTERS_COUNT;
 
+use super::solution_options;
+use crate::solution_options::SolutionOptions;
+
 const LETTER_INPUT_SIZE: usize = LETTERS_COUNT * 2 + 4;
 
 pub fn input_letters(letters: &str, error: &str) -> Markup {
@@ -19,15 +22,27 @@ pub fn input_letters(letters: &str, error: &str) -> Markup {
     )
 }
 
+/// Forms are solved as you type with HTMX, submitting them with the button also saves
+/// the puzzle to the history. The button posts the whole form, which is how clients
+/// without JavaScript solve.
+fn solve_button() -> Markup {
+    html!(
+      button type="submit" { "Solve and save" };
+    )
+}
+
 pub fn input_hinted(
     letters: &str,
     letters_error: &str,
     letter_matrix: &str,
     letter_list: &str,
+    options: &SolutionOptions,
 ) -> Markup {
     html!(
       form id="spelling-bee-form"
-            hx-post="/games/spelling_bee/solve_hinted"
+            method="post"
+            action="/games/spelling_bee/solve_hinted"
+            hx-get="/games/spelling_bee/solve_hinted"
             hx-trigger="keyup change check delay:250ms"
             hx-swap="outerHTML"
             hx-target="#spelling-bee-solution"{
@@ -37,29 +52,33 @@ pub fn input_hinted(
           textarea id="letter_matrix"
                 name="letter_matrix"
                 rows="10"
-                cols="60"
-                value={(letter_matrix)}  {};
+                cols="60" { (letter_matrix) };
         }
         fieldset {
           label for="letter_list" { "Two letter list" };
           textarea id="letter_list"
                 name="letter_list"
                 rows="10"
-                cols="25"
-                value={(letter_list)} {};
+                cols="25" { (letter_list) };
         }
+        (solution_options(options))
+        (solve_button())
       }
     )
 }
 
-pub fn input_simple(letters: &str, letters_error: &str) -> Markup {
+pub fn input_simple(letters: &str, letters_error: &str, options: &SolutionOptions) -> Markup {
     html!(
       form id="spelling-bee-form"
-            hx-post="/games/spelling_bee/solve_simple"
+            method="post"
+            action="/games/spelling_bee/solve_simple"
+            hx-get="/games/spelling_bee/solve_simple"
             hx-trigger="keyup change check delay:250ms"
             hx-swap="outerHTML"
             hx-target="#spelling-bee-solution" {
         (input_letters(letters, letters_error))
+        (solution_options(options))
+        (solve_button())
       }
     )
 }
@@ -1,9 +1,19 @@
 pub use container::*;
 pub use container_game_mode::*;
+pub use hints::*;
+pub use history::*;
 pub use input::*;
+pub use play::*;
+pub use rooms::*;
+pub use screenshot::*;
 pub use solution::*;
 
 mod container;
 mod container_game_mode;
+mod hints;
+mod history;
 mod input;
+mod play;
+mod rooms;
+mod screenshot;
 mod solution;
@@ -0,0 +1,76 @@
+use maud::{html, Markup};
+use words::spelling_bee_play::SpellingBeePlay;
+
+use super::input_letters;
+
+pub const PLAY_URL: &str = "/games/spelling_bee/play";
+
+/// Starts a game for some letters, the center one first.
+pub fn play_start_page(letters: &str, letters_error: &str) -> Markup {
+    html!(
+      h1 { "Play" };
+      p { "Find the words of the hive yourself. Words have four letters or more and use the center letter, the first one given." };
+      form id="play-start" method="get" action=(PLAY_URL) {
+        (input_letters(letters, letters_error))
+        button type="submit" { "Start" };
+      }
+    )
+}
+
+pub fn play_page(play: &SpellingBeePlay, message: &str) -> Markup {
+    html!(
+      h1 { "Play " (play.letters()) };
+      (play_board(play, message))
+      a href={(PLAY_URL) "?letters="} { "Play other letters" };
+    )
+}
+
+/// Whole game, swapped as one after each word or shuffle.
+/// Letter buttons fill the word input with JavaScript, typing works without it.
+pub fn play_board(play: &SpellingBeePlay, message: &str) -> Markup {
+    let outer: Vec<char> = play.outer().collect();
+    // Clockwise from the top left
+    let rows = [
+        vec![(outer[0], false), (outer[1], false)],
+        vec![(outer[5], false), (play.center(), true), (outer[2], false)],
+        vec![(outer[4], false), (outer[3], false)],
+    ];
+    html!(
+      div id="play" {
+        p id="play-rank" {
+          strong { (play.rank().name()) } ", " (play.score()) " points"
+          @if let Some((next, missing)) = play.next_rank() {
+            ", " (missing) " more to " (next.name())
+          }
+        }
+        div id="play-hive" {
+          @for row in &rows {
+            div {
+              @for (letter, center) in row {
+                button type="button" class=[center.then_some("center")] value=(letter) onclick="typeLetter(this)" {
+                  (letter.to_ascii_uppercase())
+                }
+              }
+            }
+          }
+        }
+        form id="play-guess" method="post" action={(PLAY_URL) "/guess"} hx-post={(PLAY_URL) "/guess"} hx-target="#play" hx-swap="outerHTML" {
+          input id="play-word" name="word" required autofocus autocomplete="off" oninput="this.value = onlyLetters(this.value)";
+          button type="button" onclick="deleteLetter()" { "Delete" };
+          button type="submit" { "Enter" };
+        }
+        form method="post" action={(PLAY_URL) "/shuffle"} hx-post={(PLAY_URL) "/shuffle"} hx-target="#play" hx-swap="outerHTML" {
+          button type="submit" { "Shuffle" };
+        }
+        div id="play-message" { (message) }
+        p { (play.found().len()) " of " (play.words_count()) " words found." }
+        ul id="play-found" {
+          @for found in play.found() {
+            li {
+              @if found.pangram { strong { (found.word) } } @else { (found.word) }
+            }
+          }
+        }
+      }
+    )
+}
@@ -0,0 +1,83 @@
+use maud::{html, Markup};
+
+use super::input_letters;
+use crate::rooms::{FoundWord, Room, RoomProgress};
+
+pub const ROOMS_URL: &str = "/games/spelling_bee/rooms";
+
+/// Starts a room for some letters.
+pub fn rooms_page(letters: &str, letters_error: &str) -> Markup {
+    html!(
+      h1 { "Solve together" };
+      p { "A room shows the same hive to everyone who opens its address, with the words found by each of them as they come." };
+      form id="room-form" method="post" action=(ROOMS_URL) {
+        (input_letters(letters, letters_error))
+        button type="submit" { "Open a room" };
+      }
+    )
+}
+
+/// Messages of the room socket are fragments swapped by id, as in the page.
+/// `username` is the logged in user, guests give a name with their words.
+pub fn room_page(room: &Room, progress: &RoomProgress, username: Option<&str>) -> Markup {
+    let room_url = format!("{}/{}", ROOMS_URL, room.id);
+    let (center, outer) = room.letters.split_at(1);
+    html!(
+      h1 { "Room " (room.letters) };
+      p { "Share this page to solve together. The center letter is " strong { (center.to_uppercase()) } ", the others are " (outer.to_uppercase()) "." };
+      div id="room" hx-ws={"connect:" (room_url) "/ws"} {
+        (room_score(room, progress.score, progress.found.len()))
+        // Not boosted, the socket sends it
+        form id="room-guess" method="post" action={(room_url) "/words"} hx-ws="send" hx-boost="false" {
+          fieldset {
+            @if let Some(username) = username {
+              span { "Playing as " (username) };
+            } @else {
+              label for="room-name" { "Name" };
+              input id="room-name" name="name" size="12" placeholder="Guest";
+            }
+            label for="room-word" { "Word" };
+            (word_input())
+            button type="submit" { "Send" };
+          }
+        }
+        (room_message(""))
+        ul id="room-found" {
+          @for found in &progress.found {
+            (room_found_word(found))
+          }
+        }
+      }
+      a href=(ROOMS_URL) { "Open another room" };
+    )
+}
+
+pub fn word_input() -> Markup {
+    html!(
+      input id="room-word" name="word" required autofocus autocomplete="off";
+    )
+}
+
+pub fn room_message(message: &str) -> Markup {
+    html!(
+      div id="room-message" { (message) }
+    )
+}
+
+pub fn room_score(room: &Room, score: usize, found_count: usize) -> Markup {
+    html!(
+      p id="room-score" {
+        "Score " (score) " of " (room.max_score()) ", "
+        (found_count) " of " (room.words_count()) " words found."
+      }
+    )
+}
+
+pub fn room_found_word(found: &FoundWord) -> Markup {
+    html!(
+      li {
+        @if found.pangram { strong { (found.word) } } @else { (found.word) }
+        " " small { "+" (found.score) " by " (found.finder) }
+      }
+    )
+}
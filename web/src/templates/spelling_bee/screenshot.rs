@@ -0,0 +1,30 @@
+use maud::{html, Markup};
+
+pub const SCREENSHOT_URL: &str = "/games/spelling_bee/screenshot";
+
+/// Upload of a hive screenshot, its letters fill the simple form.
+/// Without JavaScript the form is posted as is and redirected to the solution.
+pub fn screenshot_form(error: &str) -> Markup {
+    html!(
+      form id="spelling-bee-screenshot"
+            method="post"
+            action=(SCREENSHOT_URL)
+            enctype="multipart/form-data"
+            hx-post=(SCREENSHOT_URL)
+            hx-encoding="multipart/form-data"
+            hx-swap="outerHTML" {
+        fieldset {
+          label for="screenshot" { "Screenshot" };
+          input id="screenshot"
+                type="file"
+                name="screenshot"
+                accept="image/png,image/jpeg"
+                required;
+          button type="submit" { "Read letters" };
+          div.errors id="screenshot-error" {
+            (error)
+          }
+        }
+      }
+    )
+}
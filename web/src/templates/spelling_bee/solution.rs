@@ -1,11 +1,70 @@
 use maud::{html, Markup};
+use words::morphology::LemmaGroup;
 
-pub fn solution(words: Vec<String>) -> Markup {
+use crate::{
+    solution_options::{GroupBy, HideInflections, SolutionOptions, SolutionSection, SortOrder},
+    templates::definitions::expandable_word,
+};
+
+/// Words sharing a base form are listed together, inflections are marked with their kind.
+fn lemma_groups(groups: &[LemmaGroup]) -> Markup {
+    html!(
+      ul {
+        @for group in groups {
+          li {
+            @for (word, inflection) in &group.words {
+              div class=[inflection.map(|_| "inflection")] {
+                (expandable_word(word))
+                @if let Some(inflection) = inflection {
+                  small { (inflection) };
+                }
+              }
+            }
+          }
+        }
+      }
+    )
+}
+
+pub fn solution(sections: &[SolutionSection]) -> Markup {
     html!(
-      ul id="spelling-bee-solution" {
-        @for word in &words {
-          li { (word) }
+      div id="spelling-bee-solution" {
+        @for section in sections {
+          @if let Some(title) = &section.title {
+            h3 { (title) };
+          }
+          (lemma_groups(&section.groups))
+        }
+      }
+    )
+}
+
+/// Part of the solve forms, so changing them solves again with the new options.
+pub fn solution_options(options: &SolutionOptions) -> Markup {
+    html!(
+      fieldset {
+        label for="sort" { "Sort by" };
+        select id="sort" name="sort" {
+          @for sort in SortOrder::ALL {
+            option value=(sort.as_query()) selected[sort == options.sort] { (sort.label()) };
+          }
+        }
+        label for="group" { "Group by" };
+        select id="group" name="group" {
+          @for group_by in GroupBy::ALL {
+            option value=(group_by.as_query()) selected[group_by == options.group_by] {
+              (group_by.label())
+            };
+          }
+        }
+        label for="hide" { "Hide" };
+        select id="hide" name="hide" {
+          @for hide in HideInflections::ALL {
+            option value=(hide.as_query()) selected[hide == options.hide] { (hide.label()) };
+          }
         }
+        label for="contains" { "Containing" };
+        input id="contains" type="search" name="contains" value=(options.contains);
       }
     )
 }
@@ -0,0 +1,84 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{
+        header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+        HeaderValue, Method, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use words::dict_format::checksum;
+
+/// Pages show the logged in user, the browser keeps them but checks they are still the same.
+const PRIVATE_CACHE_CONTROL: &str = "private, no-cache";
+/// Fragments that are the same for everyone may be kept by shared caches too,
+/// checked every time as a reload changes them.
+const PUBLIC_CACHE_CONTROL: &str = "public, no-cache";
+
+/// Checksum of the dictionary a response was built from, added to its extensions.
+/// It is put in front of the tag, so a reload never matches a tag of the old dictionary.
+#[derive(Debug, Clone, Copy)]
+pub struct DictionaryTag(pub u64);
+
+/// Adds an `ETag` of the body and `Cache-Control: private, no-cache` to successful GET responses.
+/// Requests sending back a matching `If-None-Match` get an empty `304 Not Modified`.
+/// The tag is weak, compression changes the bytes but not what they mean.
+pub async fn etag(request: Request, next: Next) -> Response {
+    tagged(request, next, PRIVATE_CACHE_CONTROL).await
+}
+
+/// [`etag`] for responses that don't depend on the user, cached by anyone.
+/// Responses that differ for HTMX also need to say so with `Vary`.
+pub async fn public_etag(request: Request, next: Next) -> Response {
+    tagged(request, next, PUBLIC_CACHE_CONTROL).await
+}
+
+async fn tagged(request: Request, next: Next, cache_control: &'static str) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            log::error!("Unable to read response body: {}", err);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let tag = match parts.extensions.get::<DictionaryTag>() {
+        Some(DictionaryTag(dictionary)) => {
+            format!("W/\"{:016x}-{:016x}\"", dictionary, checksum(&bytes))
+        }
+        None => format!("W/\"{:016x}\"", checksum(&bytes)),
+    };
+    let tag = HeaderValue::from_str(&tag).unwrap();
+    parts.headers.insert(ETAG, tag.clone());
+    parts
+        .headers
+        .insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    if if_none_match.is_some_and(|value| matches_tag(&value, &tag)) {
+        parts.status = StatusCode::NOT_MODIFIED;
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// `If-None-Match` is a list of tags or `*`, compared weakly.
+fn matches_tag(if_none_match: &HeaderValue, tag: &HeaderValue) -> bool {
+    let Ok(value) = if_none_match.to_str() else {
+        return false;
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    let tag = opaque(tag.to_str().unwrap_or_default());
+    value
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == tag)
+}
@@ -1 +1,4 @@
+pub mod etag;
 pub mod modify_query;
+pub mod random_token;
+pub mod same_origin;
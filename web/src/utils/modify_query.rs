@@ -1,6 +1,6 @@
 use anyhow::Result;
 use axum::http::Uri;
-use url::Url;
+use url::{form_urlencoded, Url};
 
 pub fn modify_query(new_queries: Vec<(String, String)>, url: Option<String>) -> Result<Uri> {
     if let Some(url) = url {
@@ -17,15 +17,36 @@ pub fn modify_query(new_queries: Vec<(String, String)>, url: Option<String>) ->
             .extend_pairs(&new_queries);
         Ok(url.as_str().to_string().parse()?)
     } else {
-        let url = format!(
-            "/?{}",
-            new_queries
-                .iter()
-                .map(|p| format!("{}={}", p.0, p.1))
-                .fold(String::new(), |acc, q| format!("{}&{}", acc, q))
-        )
-        .replace("?&", "?");
-        log::info!("{}", url);
-        Ok(url.parse::<Uri>()?)
+        with_query("/", new_queries)
+    }
+}
+
+/// Builds a relative url, names and values are percent encoded.
+pub fn with_query(path: &str, queries: Vec<(String, String)>) -> Result<Uri> {
+    let query = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(&queries)
+        .finish();
+    let url = format!("{}?{}", path, query);
+    log::debug!("{}", url);
+    Ok(url.parse::<Uri>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_encoded() {
+        let queries = vec![
+            ("letters".to_owned(), "abcdefg".to_owned()),
+            ("contains".to_owned(), "a b&c#é".to_owned()),
+        ];
+        let uri = with_query("/games/spelling_bee", queries).unwrap();
+        assert_eq!(
+            uri,
+            "/games/spelling_bee?letters=abcdefg&contains=a+b%26c%23%C3%A9"
+        );
+        let pairs: Vec<_> = form_urlencoded::parse(uri.query().unwrap().as_bytes()).collect();
+        assert_eq!(pairs[1].1, "a b&c#é");
     }
 }
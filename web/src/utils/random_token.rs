@@ -0,0 +1,8 @@
+use rand_core::{OsRng, RngCore};
+
+/// Hex of `len` random bytes from the OS, for ids that must not be guessed.
+pub fn random_token(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
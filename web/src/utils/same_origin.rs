@@ -0,0 +1,49 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{
+        header::{HOST, ORIGIN, REFERER},
+        request::Parts,
+        HeaderMap, StatusCode,
+    },
+    response::{IntoResponse, Response},
+};
+use url::Url;
+
+/// Form posted from a page of this site, answered with `403` otherwise.
+/// Another site could log its visitors in to an account of its own, or out of theirs.
+pub struct SameOrigin;
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for SameOrigin {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if is_same_origin(&parts.headers) {
+            Ok(Self)
+        } else {
+            Err((StatusCode::FORBIDDEN, "Cross-origin form").into_response())
+        }
+    }
+}
+
+/// `Origin`, or `Referer` when a browser leaves it out, points at the host the request went to.
+pub fn is_same_origin(headers: &HeaderMap) -> bool {
+    let header = |name| headers.get(name).and_then(|h| h.to_str().ok());
+    let Some(host) = header(HOST) else {
+        return false;
+    };
+    let Some(source) = header(ORIGIN)
+        .filter(|origin| *origin != "null")
+        .or_else(|| header(REFERER))
+        .and_then(|source| Url::parse(source).ok())
+    else {
+        return false;
+    };
+    let authority = match (source.host_str(), source.port()) {
+        (Some(source_host), Some(port)) => format!("{}:{}", source_host, port),
+        (Some(source_host), None) => source_host.to_owned(),
+        (None, _) => return false,
+    };
+    authority.eq_ignore_ascii_case(host)
+}
@@ -0,0 +1,25 @@
+use std::{env, fs, path::Path};
+
+#[allow(dead_code)]
+#[path = "src/letters_mask.rs"]
+mod letters_mask;
+
+#[allow(dead_code)]
+#[path = "src/dict_format.rs"]
+mod dict_format;
+
+/// Bundled word list is encoded ahead of time, so loading it is free.
+fn main() {
+    let source = "data/words_alpha.txt";
+    println!("cargo:rerun-if-changed={}", source);
+    println!("cargo:rerun-if-changed=src/dict_format.rs");
+    println!("cargo:rerun-if-changed=src/letters_mask.rs");
+
+    let text = fs::read(source).expect("word list should be readable");
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(
+        Path::new(&out_dir).join("words_alpha.bin"),
+        dict_format::encode_text(&text),
+    )
+    .expect("encoded word list should be writable");
+}
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use std::{collections::HashMap, fs, path::Path};
+
+/// One meaning of a word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub part_of_speech: String,
+    pub text: String,
+}
+
+/// Word definitions loaded from a tab separated file with a line per meaning:
+/// `word<TAB>part of speech<TAB>definition`, lines starting with `#` are comments.
+#[derive(Debug, Default)]
+pub struct Definitions {
+    entries: HashMap<String, Vec<Definition>>,
+}
+
+impl Definitions {
+    pub fn from_tsv(text: &str) -> Self {
+        let mut entries: HashMap<String, Vec<Definition>> = HashMap::new();
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let mut parts = line.splitn(3, '\t');
+            let (Some(word), Some(part_of_speech), Some(text)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            entries
+                .entry(word.trim().to_lowercase())
+                .or_default()
+                .push(Definition {
+                    part_of_speech: part_of_speech.trim().to_owned(),
+                    text: text.trim().to_owned(),
+                });
+        }
+        Self { entries }
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("unable to read definitions {}", path.display()))?;
+        Ok(Self::from_tsv(&text))
+    }
+
+    /// Meanings in file order, empty when the word is unknown.
+    pub fn get(&self, word: &str) -> &[Definition] {
+        self.entries
+            .get(&word.to_lowercase())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Number of defined words.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Lines of the definitions file.
+pub fn to_tsv<'a>(definitions: impl IntoIterator<Item = &'a (String, Definition)>) -> String {
+    definitions
+        .into_iter()
+        .fold(String::new(), |mut text, (word, definition)| {
+            text.push_str(&format!(
+                "{}\t{}\t{}\n",
+                word, definition.part_of_speech, definition.text
+            ));
+            text
+        })
+}
+
+/// Reads a WordNet `data.noun`, `data.verb`, `data.adj` or `data.adv` file.
+/// Only lowercase single words are kept, the dictionary has nothing else.
+/// Usage examples are dropped from the glosses.
+pub fn parse_wordnet_data(text: &str) -> Vec<(String, Definition)> {
+    let mut out = vec![];
+    for line in text.lines() {
+        // License header is indented
+        if line.starts_with(' ') {
+            continue;
+        }
+        let Some((fields, gloss)) = line.split_once(" | ") else {
+            continue;
+        };
+        let fields: Vec<&str> = fields.split(' ').collect();
+        let (Some(synset_type), Some(words_count)) = (fields.get(2), fields.get(3)) else {
+            continue;
+        };
+        let part_of_speech = match *synset_type {
+            "n" => "noun",
+            "v" => "verb",
+            "a" | "s" => "adjective",
+            "r" => "adverb",
+            _ => continue,
+        };
+        let Ok(words_count) = usize::from_str_radix(words_count, 16) else {
+            continue;
+        };
+        let text = gloss
+            .split("; \"")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_owned();
+        for i in 0..words_count {
+            let Some(word) = fields.get(4 + i * 2) else {
+                break;
+            };
+            // Adjectives may carry a syntactic marker, like `galore(ip)`
+            let word = word.split('(').next().unwrap_or_default();
+            if word.is_empty() || !word.bytes().all(|b| b.is_ascii_lowercase()) {
+                continue;
+            }
+            out.push((
+                word.to_owned(),
+                Definition {
+                    part_of_speech: part_of_speech.to_owned(),
+                    text: text.clone(),
+                },
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDNET_DATA: &str = "  1 This software and database is being provided
+00001740 03 n 01 entity 0 003 ~ 00001930 n 0000 | that which is perceived or known or inferred to have its own distinct existence (living or nonliving)
+00005930 03 n 02 dwelling 0 abode 0 002 @ 00004475 n 0000 | housing that someone is living in; \"he built a modest dwelling\"
+00006024 03 n 02 New_York 0 big_apple 0 000 | the largest city in New York State
+01382086 00 s 01 galore(ip) 0 001 & 01381821 a 0000 | existing in great numbers; \"there were daffodils galore\"
+";
+
+    #[test]
+    fn it_parses_wordnet() {
+        let definitions = parse_wordnet_data(WORDNET_DATA);
+        let words: Vec<&str> = definitions.iter().map(|(w, _)| w.as_str()).collect();
+        assert_eq!(words, vec!["entity", "dwelling", "abode", "galore"]);
+        assert_eq!(
+            definitions[1].1,
+            Definition {
+                part_of_speech: "noun".to_owned(),
+                text: "housing that someone is living in".to_owned(),
+            }
+        );
+        assert_eq!(definitions[3].1.part_of_speech, "adjective");
+    }
+
+    #[test]
+    fn it_round_trips_tsv() {
+        let parsed = parse_wordnet_data(WORDNET_DATA);
+        let definitions = Definitions::from_tsv(&format!("# comment\n{}", to_tsv(&parsed)));
+        assert_eq!(definitions.len(), 4);
+        assert_eq!(definitions.get("Abode"), definitions.get("dwelling"));
+        assert_eq!(definitions.get("abode")[0].part_of_speech, "noun");
+        assert!(definitions.get("missing").is_empty());
+    }
+}
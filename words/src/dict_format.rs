@@ -0,0 +1,221 @@
+//! Binary dictionary layout. Every number is a little-endian `u32` unless noted.
+//!
+//! | section       | contents                                                              |
+//! |---------------|-----------------------------------------------------------------------|
+//! | header        | magic, version, words count, groups count, blob length, checksum(u64) |
+//! | offsets       | `words + 1` offsets of the words in the blob                          |
+//! | masks         | letters mask of every word                                            |
+//! | shortcuts     | prefix index, `-1` for prefixes without words                         |
+//! | groups        | `groups` pairs of mask and start in the group indexes, ordered by mask |
+//! | group indexes | word indexes ordered by mask, then in dictionary order                |
+//! | blob          | sorted words back to back                                             |
+//!
+//! Checksum is FNV-1a over everything after the header.
+//! Module has no dependencies besides [`LettersMask`] so the build script can include it.
+
+use std::ops::Range;
+
+use crate::letters_mask::LettersMask;
+
+pub const MAGIC: [u8; 8] = *b"WPDICT\0\0";
+pub const VERSION: u32 = 1;
+pub const HEADER_SIZE: usize = 32;
+
+pub const SHORTCUTS_DEPTH: usize = 2;
+
+const fn calc_array_size(n: usize) -> usize {
+    if n == 0 {
+        return 0;
+    }
+    26u32.pow(n as u32) as usize + calc_array_size(n - 1)
+}
+
+pub const SHORTCUTS_SIZE: usize = calc_array_size(SHORTCUTS_DEPTH);
+
+/// Every first letter takes a slot for itself followed by 26 slots for the second letter.
+pub fn calc_one_leter_i(one: u8) -> usize {
+    (one as usize - 97) * 27
+}
+
+pub fn calc_two_leter_i(first: u8, second: u8) -> usize {
+    calc_one_leter_i(first) + second as usize - 96
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header {
+    pub version: u32,
+    pub words: u32,
+    pub groups: u32,
+    pub blob_len: u32,
+    pub checksum: u64,
+}
+
+impl Header {
+    pub fn read(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_SIZE || bytes[..8] != MAGIC {
+            return None;
+        }
+        Some(Self {
+            version: read_u32(bytes, 8),
+            words: read_u32(bytes, 12),
+            groups: read_u32(bytes, 16),
+            blob_len: read_u32(bytes, 20),
+            checksum: u64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+        })
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&self.version.to_le_bytes());
+        out.extend_from_slice(&self.words.to_le_bytes());
+        out.extend_from_slice(&self.groups.to_le_bytes());
+        out.extend_from_slice(&self.blob_len.to_le_bytes());
+        out.extend_from_slice(&self.checksum.to_le_bytes());
+    }
+}
+
+/// Byte ranges of the sections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub offsets: Range<usize>,
+    pub masks: Range<usize>,
+    pub shortcuts: Range<usize>,
+    pub groups: Range<usize>,
+    pub group_indexes: Range<usize>,
+    pub blob: Range<usize>,
+}
+
+impl Layout {
+    pub fn new(header: &Header) -> Self {
+        let words = header.words as usize;
+        let offsets = HEADER_SIZE..HEADER_SIZE + (words + 1) * 4;
+        let masks = offsets.end..offsets.end + words * 4;
+        let shortcuts = masks.end..masks.end + SHORTCUTS_SIZE * 4;
+        let groups = shortcuts.end..shortcuts.end + header.groups as usize * 8;
+        let group_indexes = groups.end..groups.end + words * 4;
+        let blob = group_indexes.end..group_indexes.end + header.blob_len as usize;
+        Self {
+            offsets,
+            masks,
+            shortcuts,
+            groups,
+            group_indexes,
+            blob,
+        }
+    }
+
+    /// Total size of the encoded dictionary.
+    pub fn size(&self) -> usize {
+        self.blob.end
+    }
+}
+
+pub fn read_u32(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+}
+
+pub fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Encodes newline separated word list.
+/// Words with anything but lowercase latin letters are skipped.
+pub fn encode_text(text: &[u8]) -> Vec<u8> {
+    encode_words(
+        text.split(|&byte| byte == b'\n')
+            .map(|w| w.strip_suffix(b"\r").unwrap_or(w)),
+    )
+}
+
+/// Encodes words in any order, duplicates and words with anything but lowercase latin letters
+/// are skipped.
+pub fn encode_words<'a>(words: impl IntoIterator<Item = &'a [u8]>) -> Vec<u8> {
+    let mut words: Vec<&[u8]> = words
+        .into_iter()
+        .filter(|w| !w.is_empty() && w.iter().all(u8::is_ascii_lowercase))
+        .collect();
+    words.sort();
+    words.dedup();
+
+    let masks: Vec<LettersMask> = words.iter().map(|w| LettersMask::from_word(w)).collect();
+    let mut by_mask: Vec<(LettersMask, u32)> = masks
+        .iter()
+        .enumerate()
+        .map(|(i, mask)| (*mask, i as u32))
+        .collect();
+    by_mask.sort_unstable();
+    let mut groups: Vec<(LettersMask, u32)> = vec![];
+    for (i, (mask, _)) in by_mask.iter().enumerate() {
+        if groups.last().is_none_or(|(last, _)| last != mask) {
+            groups.push((*mask, i as u32));
+        }
+    }
+
+    let header = Header {
+        version: VERSION,
+        words: words.len() as u32,
+        groups: groups.len() as u32,
+        blob_len: words.iter().map(|w| w.len() as u32).sum(),
+        checksum: 0,
+    };
+    let layout = Layout::new(&header);
+    let mut out = Vec::with_capacity(layout.size());
+    header.write(&mut out);
+
+    let mut offset = 0u32;
+    for word in words.iter() {
+        out.extend_from_slice(&offset.to_le_bytes());
+        offset += word.len() as u32;
+    }
+    out.extend_from_slice(&offset.to_le_bytes());
+    for mask in masks.iter() {
+        out.extend_from_slice(&mask.bits().to_le_bytes());
+    }
+    for shortcut in build_shortcuts(&words) {
+        out.extend_from_slice(&shortcut.to_le_bytes());
+    }
+    for (mask, start) in groups.iter() {
+        out.extend_from_slice(&mask.bits().to_le_bytes());
+        out.extend_from_slice(&start.to_le_bytes());
+    }
+    for (_, i) in by_mask.iter() {
+        out.extend_from_slice(&i.to_le_bytes());
+    }
+    for word in words.iter() {
+        out.extend_from_slice(word);
+    }
+
+    let checksum = checksum(&out[HEADER_SIZE..]);
+    out[24..32].copy_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+/// Start of the first word for every one and two letter prefix of the sorted words.
+fn build_shortcuts(words: &[&[u8]]) -> [i32; SHORTCUTS_SIZE] {
+    let mut shortcuts = [-1; SHORTCUTS_SIZE];
+
+    let mut latest_first = None;
+    let mut latest_second = None;
+
+    for (i, word) in words.iter().enumerate() {
+        let first = word.first().unwrap().to_ascii_lowercase();
+        if latest_first != Some(first) {
+            shortcuts[calc_one_leter_i(first)] = i as i32;
+            latest_first = Some(first);
+            latest_second = None;
+        }
+        let second = word.get(1).map(|c| c.to_ascii_lowercase());
+        match (second, latest_second) {
+            (None, _) => latest_second = None,
+            (Some(curr), Some(latest)) if curr == latest => {}
+            (Some(second), _) => {
+                shortcuts[calc_two_leter_i(first, second)] = i as i32;
+                latest_second = Some(second);
+            }
+        }
+    }
+
+    shortcuts
+}
@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use std::{collections::HashMap, fs, path::Path};
+
+/// Word counts from a corpus, loaded from a file with a `word count` line per word separated
+/// by spaces or a tab, lines starting with `#` are comments. Lists like Google's `count_1w.txt`
+/// can be used as they are.
+#[derive(Debug, Default)]
+pub struct Frequencies {
+    counts: HashMap<String, u64>,
+}
+
+impl Frequencies {
+    pub fn from_text(text: &str) -> Self {
+        let mut counts = HashMap::new();
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let mut parts = line.split_whitespace();
+            let (Some(word), Some(count)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            let Ok(count) = count.parse::<u64>() else {
+                continue;
+            };
+            *counts.entry(word.to_lowercase()).or_default() += count;
+        }
+        Self { counts }
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("unable to read word frequencies {}", path.display()))?;
+        Ok(Self::from_text(&text))
+    }
+
+    /// Zero when the word is unknown.
+    pub fn get(&self, word: &str) -> u64 {
+        self.counts.get(word).copied().unwrap_or_default()
+    }
+
+    /// Number of counted words.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_counts() {
+        let frequencies =
+            Frequencies::from_text("# comment\nthe\t23135851162\nOf 13151942776\nof 1\nbroken\n");
+        assert_eq!(frequencies.len(), 2);
+        assert_eq!(frequencies.get("the"), 23135851162);
+        assert_eq!(frequencies.get("of"), 13151942777);
+        assert_eq!(frequencies.get("broken"), 0);
+    }
+}
@@ -1 +1,4 @@
 pub mod spelling_bee;
+pub mod spelling_bee_generator;
+pub mod spelling_bee_hints;
+pub mod spelling_bee_play;
@@ -1,13 +1,125 @@
-use crate::{WordsDict, WordsShortcuts};
+use crate::{LettersMask, WordsDict, WordsShortcuts};
 use anyhow::{bail, Result};
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 const MIN_LENGTH: usize = 3;
 const MAX_LENGTH: usize = 10;
 pub const LETTERS_COUNT: usize = 7;
+const PANGRAM_BONUS: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpellingBeeError {
+    LettersCount,
+    InvalidLetter(char),
+}
+
+impl SpellingBeeError {
+    /// Stable identifier for API consumers.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SpellingBeeError::LettersCount => "letters_count",
+            SpellingBeeError::InvalidLetter(_) => "invalid_letter",
+        }
+    }
+}
+
+impl fmt::Display for SpellingBeeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpellingBeeError::LettersCount => {
+                write!(f, "letters must have {} unique characters", LETTERS_COUNT)
+            }
+            SpellingBeeError::InvalidLetter(c) => write!(f, "'{}' is not a latin letter", c),
+        }
+    }
+}
+
+impl std::error::Error for SpellingBeeError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolvedWord {
+    pub word: String,
+    pub score: usize,
+    pub pangram: bool,
+}
 
 pub trait SpellingBee {
+    fn letters(&self) -> &[u8; LETTERS_COUNT];
+
     fn scan_dict(&self, dict: &WordsDict, shortcuts: &WordsShortcuts) -> Vec<String>;
+
+    /// Pangram uses every letter of the hive at least once.
+    fn is_pangram(&self, word: &str) -> bool {
+        self.letters()
+            .iter()
+            .all(|letter| word.as_bytes().contains(letter))
+    }
+
+    /// Four letter words are worth one point, longer ones a point per letter.
+    /// Pangrams get a bonus on top.
+    fn score(&self, word: &str) -> usize {
+        let base = if word.len() <= MIN_LENGTH + 1 {
+            1
+        } else {
+            word.len()
+        };
+        if self.is_pangram(word) {
+            base + PANGRAM_BONUS
+        } else {
+            base
+        }
+    }
+
+    /// Scans the dictionary and scores every found word.
+    fn solve(&self, dict: &WordsDict, shortcuts: &WordsShortcuts) -> Vec<SolvedWord> {
+        self.scan_dict(dict, shortcuts)
+            .into_iter()
+            .map(|word| SolvedWord {
+                score: self.score(&word),
+                pangram: self.is_pangram(&word),
+                word,
+            })
+            .collect()
+    }
+}
+
+/// Lowercases letters and drops any whitespace between them.
+pub fn normalize_letters(letters: &str) -> String {
+    letters
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join("")
+}
+
+fn fits_length(word: &[u8]) -> bool {
+    MIN_LENGTH < word.len() && word.len() <= MAX_LENGTH
+}
+
+/// Indexes of the words made only of the hive letters and using the required one,
+/// in dictionary order.
+fn hive_indexes(dict: &WordsDict, letters: &[u8], required_letter: u8) -> Vec<usize> {
+    let required = LettersMask::from_letter(required_letter);
+    let others = LettersMask::from_word(letters).without(required);
+    let mut indexes: Vec<usize> = others
+        .subsets()
+        .flat_map(|subset| dict.indexes_with_mask(subset.with(required)))
+        .collect();
+    indexes.sort_unstable();
+    indexes
+}
+
+fn parse_letters(letters: &str) -> Result<[u8; LETTERS_COUNT]> {
+    if let Some(invalid) = letters.chars().find(|c| !c.is_ascii_lowercase()) {
+        bail!(SpellingBeeError::InvalidLetter(invalid));
+    }
+    let bytes = letters.as_bytes();
+    if bytes.len() != LETTERS_COUNT
+        || LettersMask::from_word(bytes).bits().count_ones() as usize != LETTERS_COUNT
+    {
+        bail!(SpellingBeeError::LettersCount);
+    }
+    Ok(bytes.try_into().unwrap())
 }
 
 #[derive(Debug)]
@@ -20,45 +132,26 @@ impl SpellingBeeSimpleParams {
     /// First letter is the required one.
     /// Count of letters must be equal to `LETTERS_COUNT`.
     pub fn new(letters: &str) -> Result<Self> {
-        let mut bytes: Vec<u8> = letters.as_bytes().into();
-        bytes.dedup();
-        if bytes.len() != LETTERS_COUNT {
-            bail!("letters must have {} unique characters", LETTERS_COUNT);
-        }
-        let required_letter = *bytes.first().unwrap();
-        let letters = bytes.try_into().unwrap();
+        let letters = parse_letters(letters)?;
         Ok(Self {
             letters,
-            required_letter,
+            required_letter: letters[0],
         })
     }
 }
 
 impl SpellingBee for SpellingBeeSimpleParams {
-    fn scan_dict(&self, dict: &WordsDict, shortcuts: &WordsShortcuts) -> Vec<String> {
-        self.letters
-            .iter()
-            .fold(Vec::new(), |mut res: Vec<String>, start_letter| {
-                let Some(range) = shortcuts.search_range(&[*start_letter]) else {
-                    return res;
-                };
-                'word_loop: for word in dict.iter_range(range) {
-                    if MIN_LENGTH >= word.len() || word.len() > MAX_LENGTH {
-                        continue;
-                    }
-                    let mut contains_required = false;
-                    for letter in word.iter() {
-                        if !self.letters.contains(letter) {
-                            continue 'word_loop;
-                        }
-                        contains_required = contains_required || self.required_letter == *letter;
-                    }
-                    if contains_required {
-                        res.push(String::from_utf8_lossy(word).to_string());
-                    }
-                }
-                res
-            })
+    fn letters(&self) -> &[u8; LETTERS_COUNT] {
+        &self.letters
+    }
+
+    fn scan_dict(&self, dict: &WordsDict, _shortcuts: &WordsShortcuts) -> Vec<String> {
+        hive_indexes(dict, &self.letters, self.required_letter)
+            .into_iter()
+            .filter_map(|i| dict.get(i))
+            .filter(|word| fits_length(word))
+            .map(|word| String::from_utf8_lossy(word).to_string())
+            .collect()
     }
 }
 
@@ -76,46 +169,98 @@ impl SpellingBeeHintedParams {
         letters_len: Vec<(u8, Vec<usize>)>,
         start_letters: Vec<[u8; 2]>,
     ) -> Result<Self> {
-        let mut bytes: Vec<u8> = letters.as_bytes().into();
-        bytes.dedup();
-        if bytes.len() != LETTERS_COUNT {
-            bail!("letters must have {} unique characters", LETTERS_COUNT);
-        }
-        let required_letter = *bytes.first().unwrap();
-        let letters = bytes.try_into().unwrap();
+        let letters = parse_letters(letters)?;
         Ok(Self {
             letters,
-            required_letter,
+            required_letter: letters[0],
             letters_len: letters_len.into_iter().collect(),
             start_letters,
         })
     }
+
+    /// Builds params from the raw hint texts as they are copied from the puzzle page.
+    /// `letters` are expected to be normalized already.
+    pub fn from_hints(
+        letters: &str,
+        letter_matrix: Option<&str>,
+        letter_list: Option<&str>,
+    ) -> Result<Self> {
+        let allowed_bytes = letters.as_bytes();
+        let letters_len = letter_matrix
+            .map(|lm| Self::parse_letter_matrix(lm, allowed_bytes))
+            .unwrap_or_default();
+        let start_letters = letter_list
+            .map(|ll| Self::parse_letter_list(ll, allowed_bytes))
+            .unwrap_or_default();
+        Self::new(letters, letters_len, start_letters)
+    }
+
+    /// Each line of the matrix starts with a letter followed by counts of words
+    /// for lengths starting from 4, `-` marks that there are none.
+    pub fn parse_letter_matrix(letter_matrix: &str, allowed_bytes: &[u8]) -> Vec<(u8, Vec<usize>)> {
+        let letter_matrix = letter_matrix.to_lowercase();
+        let mut out: Vec<(u8, Vec<usize>)> = vec![];
+        for entry in letter_matrix.split('\n') {
+            let bytes = entry.as_bytes();
+            if let Some(first_byte) = bytes.first() {
+                if !allowed_bytes.contains(first_byte) {
+                    continue;
+                }
+                let mut avail_lens = vec![];
+                let parts = entry.split_whitespace().collect::<Vec<&str>>();
+                for i in 1..6 {
+                    if parts.get(i).is_some_and(|&e| e != "-") {
+                        avail_lens.push(i + 3);
+                    }
+                }
+
+                out.push((*first_byte, avail_lens));
+            }
+        }
+        out
+    }
+
+    /// Picks every pair of allowed letters from the two letter list, anything else is a separator.
+    pub fn parse_letter_list(letter_list: &str, allowed_bytes: &[u8]) -> Vec<[u8; 2]> {
+        let bytes = letter_list.to_lowercase().into_bytes();
+        let mut out = vec![];
+        if bytes.len() < 2 {
+            return out;
+        }
+        let mut i = 0;
+        while i < bytes.len() - 1 {
+            if allowed_bytes.contains(&bytes[i]) && allowed_bytes.contains(&bytes[i + 1]) {
+                out.push([bytes[i], bytes[i + 1]]);
+                i += 2;
+                continue;
+            }
+
+            i += 1;
+        }
+        out
+    }
 }
 
 impl<'a> SpellingBeeHintedParams {
+    fn accepts_length(&self, word: &[u8], words_len: Option<&Vec<usize>>) -> bool {
+        fits_length(word)
+            && (self.letters_len.is_empty()
+                || words_len.is_some_and(|acceptable_len| acceptable_len.contains(&word.len())))
+    }
+
     fn scan_words(
         &self,
         res: &mut Vec<String>,
-        words: impl Iterator<Item = &'a &'a [u8]>,
+        words: impl Iterator<Item = (&'a [u8], LettersMask)>,
         words_len: Option<&Vec<usize>>,
     ) {
-        'word_loop: for word in words {
-            if !self.letters_len.is_empty()
-                && words_len.is_some_and(|acceptable_len| !acceptable_len.contains(&word.len()))
+        let hive = LettersMask::from_word(&self.letters);
+        let required = LettersMask::from_letter(self.required_letter);
+        for (word, mask) in words {
+            if self.accepts_length(word, words_len)
+                && mask.is_subset_of(hive)
+                && mask.contains(required)
             {
-                continue;
-            }
-            if MIN_LENGTH >= word.len() || word.len() > MAX_LENGTH {
-                continue;
-            }
-            let mut contains_required = false;
-            for letter in word.iter() {
-                if !self.letters.contains(letter) {
-                    continue 'word_loop;
-                }
-                contains_required = contains_required || self.required_letter == *letter;
-            }
-            if contains_required {
                 res.push(String::from_utf8_lossy(word).to_string());
             }
         }
@@ -123,6 +268,10 @@ impl<'a> SpellingBeeHintedParams {
 }
 
 impl SpellingBee for SpellingBeeHintedParams {
+    fn letters(&self) -> &[u8; LETTERS_COUNT] {
+        &self.letters
+    }
+
     fn scan_dict(&self, dict: &WordsDict, shortcuts: &WordsShortcuts) -> Vec<String> {
         if !self.start_letters.is_empty() {
             return self.start_letters.iter().fold(
@@ -137,30 +286,27 @@ impl SpellingBee for SpellingBeeHintedParams {
                     let Some(range) = shortcuts.search_range(start_letters) else {
                         return res;
                     };
-                    let words = dict.iter_range(range);
+                    let words = dict.iter_range_masked(range);
                     self.scan_words(&mut res, words, words_len);
                     res
                 },
             );
         }
-        self.letters
-            .iter()
-            .fold(Vec::new(), |mut res: Vec<String>, start_letter| {
-                let words_len = self.letters_len.get(start_letter);
-                if !self.letters_len.is_empty() && words_len.is_none() {
-                    return res;
-                }
-                let Some(range) = shortcuts.search_range(&[*start_letter]) else {
-                    return res;
-                };
-                let words = dict.iter_range(range);
-                self.scan_words(&mut res, words, words_len);
-                res
+        hive_indexes(dict, &self.letters, self.required_letter)
+            .into_iter()
+            .filter_map(|i| dict.get(i))
+            .filter(|word| {
+                let words_len = word.first().and_then(|first| self.letters_len.get(first));
+                self.accepts_length(word, words_len)
             })
+            .map(|word| String::from_utf8_lossy(word).to_string())
+            .collect()
     }
 }
 
 #[cfg(test)]
+// Two letter hints are spelled out as the pairs they are
+#[allow(clippy::byte_char_slices)]
 mod tests {
     use std::sync::LazyLock;
 
@@ -178,6 +324,18 @@ mod tests {
         assert_eq!(words.len(), 51);
     }
 
+    #[test]
+    fn it_finds_same_as_full_scan() {
+        let game = SpellingBeeSimpleParams::new("zwieslt").unwrap();
+        let expected: Vec<String> = DICT
+            .iter()
+            .filter(|w| fits_length(w))
+            .filter(|w| w.iter().all(|l| game.letters.contains(l)) && w.contains(&b'z'))
+            .map(|w| String::from_utf8_lossy(w).to_string())
+            .collect();
+        assert_eq!(game.scan_dict(&DICT, &SHORTCUTS), expected);
+    }
+
     #[bench]
     fn bench_it_finds_with_simple(b: &mut Bencher) {
         let game = SpellingBeeSimpleParams::new("zwieslt").unwrap();
@@ -253,6 +411,48 @@ mod tests {
         assert_eq!(words.len(), 4);
     }
 
+    #[test]
+    fn it_rejects_letters() {
+        let err = SpellingBeeSimpleParams::new("abc").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SpellingBeeError>(),
+            Some(&SpellingBeeError::LettersCount)
+        );
+        let err = SpellingBeeSimpleParams::new("abc1efg").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SpellingBeeError>(),
+            Some(&SpellingBeeError::InvalidLetter('1'))
+        );
+        let err = SpellingBeeSimpleParams::new("abcdefa").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<SpellingBeeError>(),
+            Some(&SpellingBeeError::LettersCount)
+        );
+    }
+
+    #[test]
+    fn it_scores_words() {
+        let game = SpellingBeeSimpleParams::new("zwieslt").unwrap();
+        assert_eq!(game.score("zest"), 1);
+        assert_eq!(game.score("zeste"), 5);
+        assert!(game.is_pangram("swizzlet"));
+        assert_eq!(game.score("swizzlet"), 8 + PANGRAM_BONUS);
+    }
+
+    #[test]
+    fn it_parses_hints() {
+        let game = SpellingBeeHintedParams::from_hints(
+            "abcdefg",
+            Some("A: 3 - 2 - -\nz: 1 1 1 1 1\nb: - - - - 1"),
+            Some("AB-2 ac-1 zz-4"),
+        )
+        .unwrap();
+        assert_eq!(game.letters_len.get(&b'a'), Some(&vec![4, 6]));
+        assert_eq!(game.letters_len.get(&b'b'), Some(&vec![8]));
+        assert_eq!(game.letters_len.get(&b'z'), None);
+        assert_eq!(game.start_letters, vec![*b"ab", *b"ac"]);
+    }
+
     #[bench]
     fn bench_it_finds_with_hinted_with_starting_and_length(b: &mut Bencher) {
         let game =
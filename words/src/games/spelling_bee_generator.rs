@@ -0,0 +1,96 @@
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+use crate::{
+    spelling_bee::{SpellingBee, SpellingBeeSimpleParams, LETTERS_COUNT},
+    LettersMask, SplitMix64, WordsDict, WordsShortcuts,
+};
+
+/// Puzzles with fewer or more words than this are tried again with other letters.
+const WORDS_COUNT: RangeInclusive<usize> = 20..=60;
+/// Pangram letter sets tried before settling for the closest one.
+const MAX_TRIES: usize = 50;
+/// Longest word the solver takes, so the pangram is part of the solution.
+const MAX_PANGRAM_LENGTH: usize = 10;
+
+/// Letters of a Spelling Bee picked by `seed`, center letter first and the others in
+/// alphabetical order. The same seed and dictionary give the same letters.
+/// `None` when the dictionary has no word with seven different letters.
+pub fn generate_letters(dict: &WordsDict, shortcuts: &WordsShortcuts, seed: u64) -> Option<String> {
+    let pangram_masks: Vec<LettersMask> = dict
+        .iter_range_masked(0..dict.size())
+        .filter(|(word, mask)| {
+            mask.len() == LETTERS_COUNT
+                && word.len() <= MAX_PANGRAM_LENGTH
+                && word.iter().all(u8::is_ascii_lowercase)
+        })
+        .map(|(_, mask)| mask)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if pangram_masks.is_empty() {
+        return None;
+    }
+
+    let mut rng = SplitMix64::new(seed);
+    let mut closest: Option<(usize, String)> = None;
+    for _ in 0..MAX_TRIES {
+        let mask = pangram_masks[rng.below(pangram_masks.len())];
+        let mut letters: Vec<u8> = (b'a'..=b'z')
+            .filter(|&letter| mask.contains(LettersMask::from_letter(letter)))
+            .collect();
+        let center = letters.remove(rng.below(letters.len()));
+        letters.insert(0, center);
+        let letters = String::from_utf8(letters).unwrap();
+
+        let params = SpellingBeeSimpleParams::new(&letters).ok()?;
+        let count = params.scan_dict(dict, shortcuts).len();
+        if WORDS_COUNT.contains(&count) {
+            return Some(letters);
+        }
+        let distance = if count < *WORDS_COUNT.start() {
+            WORDS_COUNT.start() - count
+        } else {
+            count - WORDS_COUNT.end()
+        };
+        if closest.as_ref().is_none_or(|(best, _)| distance < *best) {
+            closest = Some((distance, letters));
+        }
+    }
+    closest.map(|(_, letters)| letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::LazyLock;
+
+    static DICT: LazyLock<WordsDict> = LazyLock::new(WordsDict::load);
+
+    static SHORTCUTS: LazyLock<WordsShortcuts> = LazyLock::new(|| WordsShortcuts::new(&DICT));
+
+    #[test]
+    fn it_generates_same_letters_for_same_seed() {
+        let letters = generate_letters(&DICT, &SHORTCUTS, 20261019).unwrap();
+        assert_eq!(letters.len(), LETTERS_COUNT);
+        assert_eq!(
+            generate_letters(&DICT, &SHORTCUTS, 20261019).unwrap(),
+            letters
+        );
+        assert_ne!(
+            generate_letters(&DICT, &SHORTCUTS, 20261020).unwrap(),
+            letters
+        );
+
+        let params = SpellingBeeSimpleParams::new(&letters).unwrap();
+        let solution = params.solve(&DICT, &SHORTCUTS);
+        assert!(WORDS_COUNT.contains(&solution.len()));
+        assert!(solution.iter().any(|word| word.pangram));
+    }
+
+    #[test]
+    fn it_needs_a_pangram() {
+        let dict = WordsDict::from_text(b"abba\nbaba\n");
+        let shortcuts = WordsShortcuts::new(&dict);
+        assert_eq!(generate_letters(&dict, &shortcuts, 1), None);
+    }
+}
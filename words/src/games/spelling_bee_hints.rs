@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use super::spelling_bee::SolvedWord;
+
+/// Steps of the progressive hints, each one reveals more of the solution than the previous.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HintStep {
+    /// Number of words of each length.
+    Lengths,
+    /// Number of words starting with each letter.
+    FirstLetters,
+    /// Number of words starting with each two letters.
+    Prefixes,
+    /// First letter and length of every word.
+    Shapes,
+    Words,
+}
+
+impl HintStep {
+    pub const ALL: [Self; 5] = [
+        Self::Lengths,
+        Self::FirstLetters,
+        Self::Prefixes,
+        Self::Shapes,
+        Self::Words,
+    ];
+
+    pub fn as_query(&self) -> &'static str {
+        match self {
+            Self::Lengths => "lengths",
+            Self::FirstLetters => "first_letters",
+            Self::Prefixes => "prefixes",
+            Self::Shapes => "shapes",
+            Self::Words => "words",
+        }
+    }
+
+    pub fn from_query(step: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_query() == step)
+    }
+
+    /// First step when nothing is revealed yet, `None` after the last one.
+    pub fn next(step: Option<Self>) -> Option<Self> {
+        match step {
+            None => Some(Self::Lengths),
+            Some(step) => Self::ALL.into_iter().find(|s| *s > step),
+        }
+    }
+}
+
+/// Everything the hint steps reveal about a solution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpellingBeeHints {
+    pub words_count: usize,
+    pub pangrams_count: usize,
+    pub total_score: usize,
+    pub lengths: BTreeMap<usize, usize>,
+    pub first_letters: BTreeMap<char, usize>,
+    pub prefixes: BTreeMap<String, usize>,
+    /// First letter and length of each word, in alphabetical order.
+    pub shapes: Vec<(char, usize)>,
+    pub words: Vec<String>,
+}
+
+impl SpellingBeeHints {
+    pub fn new(solution: &[SolvedWord]) -> Self {
+        let mut words: Vec<String> = solution.iter().map(|w| w.word.clone()).collect();
+        words.sort();
+        let mut hints = Self {
+            words_count: solution.len(),
+            pangrams_count: solution.iter().filter(|w| w.pangram).count(),
+            total_score: solution.iter().map(|w| w.score).sum(),
+            ..Default::default()
+        };
+        for word in &words {
+            let Some(first) = word.chars().next() else {
+                continue;
+            };
+            *hints.lengths.entry(word.len()).or_default() += 1;
+            *hints.first_letters.entry(first).or_default() += 1;
+            *hints
+                .prefixes
+                .entry(word.chars().take(2).collect())
+                .or_default() += 1;
+            hints.shapes.push((first, word.len()));
+        }
+        hints.shapes.sort();
+        hints.words = words;
+        hints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_steps_through_hints() {
+        let steps: Vec<Option<HintStep>> =
+            std::iter::successors(Some(None), |step| HintStep::next(*step).map(Some)).collect();
+        assert_eq!(steps.len(), HintStep::ALL.len() + 1);
+        assert_eq!(HintStep::next(Some(HintStep::Words)), None);
+        assert_eq!(
+            HintStep::from_query("first_letters"),
+            Some(HintStep::FirstLetters)
+        );
+        assert_eq!(HintStep::from_query("everything"), None);
+    }
+
+    #[test]
+    fn it_counts_hints() {
+        let solved = |word: &str, score, pangram| SolvedWord {
+            word: word.to_owned(),
+            score,
+            pangram,
+        };
+        let hints = SpellingBeeHints::new(&[
+            solved("tact", 1, false),
+            solved("attic", 5, false),
+            solved("tactic", 6, false),
+            solved("acetic", 13, true),
+        ]);
+        assert_eq!((hints.words_count, hints.pangrams_count), (4, 1));
+        assert_eq!(hints.total_score, 25);
+        assert_eq!(hints.lengths, BTreeMap::from([(4, 1), (5, 1), (6, 2)]));
+        assert_eq!(hints.first_letters, BTreeMap::from([('a', 2), ('t', 2)]));
+        assert_eq!(
+            hints.prefixes,
+            BTreeMap::from([
+                ("ac".to_owned(), 1),
+                ("at".to_owned(), 1),
+                ("ta".to_owned(), 2)
+            ])
+        );
+        assert_eq!(hints.shapes, vec![('a', 5), ('a', 6), ('t', 4), ('t', 6)]);
+        assert_eq!(hints.words, vec!["acetic", "attic", "tact", "tactic"]);
+    }
+}
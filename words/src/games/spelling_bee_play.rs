@@ -0,0 +1,287 @@
+use anyhow::Result;
+use std::fmt;
+
+use crate::{
+    spelling_bee::{SolvedWord, SpellingBee, SpellingBeeSimpleParams, LETTERS_COUNT},
+    SplitMix64, WordsDict, WordsShortcuts,
+};
+
+const MIN_WORD_LENGTH: usize = 4;
+
+/// Why a word is not taken, checked in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    TooShort,
+    BadLetter(char),
+    MissingCenter,
+    NotInWordList,
+    AlreadyFound,
+}
+
+impl Rejection {
+    /// Stable identifier for API consumers.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Rejection::TooShort => "too_short",
+            Rejection::BadLetter(_) => "bad_letter",
+            Rejection::MissingCenter => "missing_center",
+            Rejection::NotInWordList => "not_in_word_list",
+            Rejection::AlreadyFound => "already_found",
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::TooShort => write!(
+                f,
+                "too short, words have {} letters or more",
+                MIN_WORD_LENGTH
+            ),
+            Rejection::BadLetter(c) => write!(f, "'{}' is not in the hive", c),
+            Rejection::MissingCenter => write!(f, "missing the center letter"),
+            Rejection::NotInWordList => write!(f, "not in the word list"),
+            Rejection::AlreadyFound => write!(f, "already found"),
+        }
+    }
+}
+
+impl std::error::Error for Rejection {}
+
+/// Ranks reached at a share of the points of the whole solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rank {
+    Beginner,
+    GoodStart,
+    MovingUp,
+    Good,
+    Solid,
+    Nice,
+    Great,
+    Amazing,
+    Genius,
+    QueenBee,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 10] = [
+        Rank::Beginner,
+        Rank::GoodStart,
+        Rank::MovingUp,
+        Rank::Good,
+        Rank::Solid,
+        Rank::Nice,
+        Rank::Great,
+        Rank::Amazing,
+        Rank::Genius,
+        Rank::QueenBee,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rank::Beginner => "Beginner",
+            Rank::GoodStart => "Good Start",
+            Rank::MovingUp => "Moving Up",
+            Rank::Good => "Good",
+            Rank::Solid => "Solid",
+            Rank::Nice => "Nice",
+            Rank::Great => "Great",
+            Rank::Amazing => "Amazing",
+            Rank::Genius => "Genius",
+            Rank::QueenBee => "Queen Bee",
+        }
+    }
+
+    fn percent(self) -> usize {
+        match self {
+            Rank::Beginner => 0,
+            Rank::GoodStart => 2,
+            Rank::MovingUp => 5,
+            Rank::Good => 8,
+            Rank::Solid => 15,
+            Rank::Nice => 25,
+            Rank::Great => 40,
+            Rank::Amazing => 50,
+            Rank::Genius => 70,
+            Rank::QueenBee => 100,
+        }
+    }
+
+    /// Points needed for the rank, rounded to the nearest.
+    pub fn min_score(self, max_score: usize) -> usize {
+        (max_score * self.percent() + 50) / 100
+    }
+
+    pub fn for_score(score: usize, max_score: usize) -> Self {
+        Self::ALL
+            .into_iter()
+            .rev()
+            .find(|rank| score >= rank.min_score(max_score))
+            .unwrap_or(Rank::Beginner)
+    }
+
+    pub fn next(self) -> Option<Self> {
+        Self::ALL.into_iter().find(|rank| *rank > self)
+    }
+}
+
+/// Spelling Bee being played: words are checked against the solution of the letters
+/// and the score adds up towards the ranks.
+#[derive(Debug, Clone)]
+pub struct SpellingBeePlay {
+    letters: [u8; LETTERS_COUNT],
+    /// Outer letters in the order they are shown, changed by shuffling.
+    outer: [u8; LETTERS_COUNT - 1],
+    solution: Vec<SolvedWord>,
+    /// Most recent first.
+    found: Vec<SolvedWord>,
+}
+
+impl SpellingBeePlay {
+    /// First letter is the center one, as for [`SpellingBeeSimpleParams`].
+    pub fn new(letters: &str, dict: &WordsDict, shortcuts: &WordsShortcuts) -> Result<Self> {
+        let solution = SpellingBeeSimpleParams::new(letters)?.solve(dict, shortcuts);
+        Self::from_solution(letters, solution)
+    }
+
+    /// `solution` is the one of the letters, solved elsewhere.
+    pub fn from_solution(letters: &str, solution: Vec<SolvedWord>) -> Result<Self> {
+        let letters = *SpellingBeeSimpleParams::new(letters)?.letters();
+        Ok(Self {
+            letters,
+            outer: letters[1..].try_into().unwrap(),
+            solution,
+            found: vec![],
+        })
+    }
+
+    /// Center letter first.
+    pub fn letters(&self) -> String {
+        String::from_utf8_lossy(&self.letters).to_string()
+    }
+
+    pub fn center(&self) -> char {
+        self.letters[0] as char
+    }
+
+    pub fn outer(&self) -> impl Iterator<Item = char> + '_ {
+        self.outer.iter().map(|&b| b as char)
+    }
+
+    pub fn found(&self) -> &[SolvedWord] {
+        &self.found
+    }
+
+    pub fn words_count(&self) -> usize {
+        self.solution.len()
+    }
+
+    pub fn score(&self) -> usize {
+        self.found.iter().map(|w| w.score).sum()
+    }
+
+    pub fn max_score(&self) -> usize {
+        self.solution.iter().map(|w| w.score).sum()
+    }
+
+    pub fn rank(&self) -> Rank {
+        Rank::for_score(self.score(), self.max_score())
+    }
+
+    /// Next rank with the points still missing for it.
+    pub fn next_rank(&self) -> Option<(Rank, usize)> {
+        let next = self.rank().next()?;
+        Some((next, next.min_score(self.max_score()) - self.score()))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.found.len() == self.solution.len()
+    }
+
+    /// Takes the word when it is in the solution and not found yet.
+    pub fn guess(&mut self, word: &str) -> Result<SolvedWord, Rejection> {
+        let word = word.trim().to_lowercase();
+        if word.len() < MIN_WORD_LENGTH {
+            return Err(Rejection::TooShort);
+        }
+        if let Some(bad) = word.chars().find(|c| !self.letters.contains(&(*c as u8))) {
+            return Err(Rejection::BadLetter(bad));
+        }
+        if !word.as_bytes().contains(&self.letters[0]) {
+            return Err(Rejection::MissingCenter);
+        }
+        if self.found.iter().any(|w| w.word == word) {
+            return Err(Rejection::AlreadyFound);
+        }
+        let Some(solved) = self.solution.iter().find(|w| w.word == word) else {
+            return Err(Rejection::NotInWordList);
+        };
+        self.found.insert(0, solved.clone());
+        Ok(solved.clone())
+    }
+
+    /// Reorders the outer letters, `seed` picks the order.
+    pub fn shuffle(&mut self, seed: u64) {
+        let current = self.outer;
+        let mut rng = SplitMix64::new(seed);
+        // A few more tries when the order comes out the same
+        for _ in 0..8 {
+            rng.shuffle(&mut self.outer);
+            if self.outer != current {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::LazyLock;
+
+    static DICT: LazyLock<WordsDict> = LazyLock::new(WordsDict::load);
+
+    static SHORTCUTS: LazyLock<WordsShortcuts> = LazyLock::new(|| WordsShortcuts::new(&DICT));
+
+    #[test]
+    fn it_checks_words() {
+        let mut play = SpellingBeePlay::new("zwieslt", &DICT, &SHORTCUTS).unwrap();
+        assert_eq!(play.guess("zit"), Err(Rejection::TooShort));
+        assert_eq!(play.guess("zebra"), Err(Rejection::BadLetter('b')));
+        assert_eq!(play.guess("wiles"), Err(Rejection::MissingCenter));
+        assert_eq!(play.guess("zzzz"), Err(Rejection::NotInWordList));
+        let liszt = play.guess(" Liszt").unwrap();
+        assert_eq!(liszt.word, "liszt");
+        assert_eq!(play.guess("liszt"), Err(Rejection::AlreadyFound));
+        assert_eq!(play.score(), liszt.score);
+        assert_eq!(play.found().len(), 1);
+    }
+
+    #[test]
+    fn it_ranks_by_share_of_points() {
+        assert_eq!(Rank::for_score(0, 200), Rank::Beginner);
+        assert_eq!(Rank::for_score(4, 200), Rank::GoodStart);
+        assert_eq!(Rank::for_score(139, 200), Rank::Amazing);
+        assert_eq!(Rank::for_score(140, 200), Rank::Genius);
+        assert_eq!(Rank::for_score(200, 200), Rank::QueenBee);
+        assert_eq!(Rank::QueenBee.next(), None);
+
+        let play = SpellingBeePlay::new("zwieslt", &DICT, &SHORTCUTS).unwrap();
+        let (next, missing) = play.next_rank().unwrap();
+        assert_eq!(next, Rank::GoodStart);
+        assert_eq!(missing, Rank::GoodStart.min_score(play.max_score()));
+    }
+
+    #[test]
+    fn it_shuffles_outer_letters() {
+        let mut play = SpellingBeePlay::new("zwieslt", &DICT, &SHORTCUTS).unwrap();
+        play.shuffle(42);
+        let outer: String = play.outer().collect();
+        assert_ne!(outer, "wieslt");
+        let mut sorted: Vec<char> = outer.chars().collect();
+        sorted.sort_unstable();
+        assert_eq!(sorted, vec!['e', 'i', 'l', 's', 't', 'w']);
+        assert_eq!(play.center(), 'z');
+    }
+}
@@ -0,0 +1,95 @@
+/// Set of latin letters packed into the lower 26 bits, `a` is the lowest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, PartialOrd, Ord)]
+pub struct LettersMask(u32);
+
+impl LettersMask {
+    pub const EMPTY: Self = Self(0);
+
+    /// Expects lowercase latin letters, anything else is ignored.
+    pub fn from_letter(letter: u8) -> Self {
+        if letter.is_ascii_lowercase() {
+            Self(1 << (letter - b'a'))
+        } else {
+            Self::EMPTY
+        }
+    }
+
+    pub fn from_word(word: &[u8]) -> Self {
+        word.iter().fold(Self::EMPTY, |mask, &letter| {
+            mask.with(Self::from_letter(letter))
+        })
+    }
+
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn with(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub fn without(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_subset_of(&self, other: Self) -> bool {
+        other.contains(*self)
+    }
+
+    /// Every subset of the mask including the empty one and the mask itself.
+    pub fn subsets(self) -> impl Iterator<Item = Self> {
+        let full = self.0;
+        let mut next = Some(full);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = (current != 0).then(|| (current - 1) & full);
+            Some(Self(current))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_from_word() {
+        let mask = LettersMask::from_word(b"abba");
+        assert_eq!(mask.bits(), 0b11);
+        assert_eq!(mask.len(), 2);
+        assert_eq!(LettersMask::from_word(b"z").bits(), 1 << 25);
+    }
+
+    #[test]
+    fn it_checks_subsets() {
+        let hive = LettersMask::from_word(b"abcdefg");
+        assert!(LettersMask::from_word(b"cafe").is_subset_of(hive));
+        assert!(!LettersMask::from_word(b"cafes").is_subset_of(hive));
+        assert!(hive.contains(LettersMask::from_letter(b'g')));
+    }
+
+    #[test]
+    fn it_lists_subsets() {
+        let subsets: Vec<LettersMask> = LettersMask::from_word(b"abc").subsets().collect();
+        assert_eq!(subsets.len(), 8);
+        assert!(subsets.contains(&LettersMask::EMPTY));
+        assert!(subsets.contains(&LettersMask::from_word(b"ac")));
+        assert_eq!(LettersMask::from_word(b"abcdefg").subsets().count(), 128);
+    }
+}
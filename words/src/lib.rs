@@ -1,9 +1,22 @@
 #![feature(test, anonymous_lifetime_in_impl_trait)]
 extern crate test;
 
+pub use definitions::{Definition, Definitions};
+pub use frequencies::Frequencies;
 pub use games::*;
+pub use letters_mask::LettersMask;
+pub use morphology::{Inflection, Lemma, Lemmatizer};
+pub use split_mix::SplitMix64;
 pub use words_dict::WordsDict;
+pub use words_overlay::WordsOverlay;
 pub use words_shortcuts::*;
+pub mod definitions;
+pub mod dict_format;
+pub mod frequencies;
 pub mod games;
+pub mod letters_mask;
+pub mod morphology;
+pub mod split_mix;
 pub mod words_dict;
+pub mod words_overlay;
 pub mod words_shortcuts;
@@ -0,0 +1,273 @@
+use std::{collections::HashMap, fmt};
+
+use crate::WordsDict;
+
+pub const EXCEPTIONS: &str = include_str!("../data/lemma_exceptions.txt");
+
+/// Shortest base a suffix rule may leave, shorter ones are mostly noise in the word list.
+const MIN_BASE_LENGTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Inflection {
+    /// Plural noun or third person verb, they share the `-s` suffix.
+    Plural,
+    Past,
+    Progressive,
+    Comparative,
+    Superlative,
+}
+
+impl Inflection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Inflection::Plural => "plural",
+            Inflection::Past => "past",
+            Inflection::Progressive => "progressive",
+            Inflection::Comparative => "comparative",
+            Inflection::Superlative => "superlative",
+        }
+    }
+
+    fn parse(kind: &str) -> Option<Self> {
+        Some(match kind {
+            "plural" => Inflection::Plural,
+            "past" => Inflection::Past,
+            "progressive" => Inflection::Progressive,
+            "comparative" => Inflection::Comparative,
+            "superlative" => Inflection::Superlative,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Inflection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lemma {
+    pub base: String,
+    pub inflection: Inflection,
+}
+
+/// Finds base forms by stripping `-s`, `-ed` and `-ing` and checking the result in the dictionary,
+/// with a list of exceptions for irregular forms.
+/// `-er` and `-est` are only taken from the exceptions, without parts of speech
+/// the rules would turn "corner" into "corn".
+#[derive(Debug, Clone)]
+pub struct Lemmatizer {
+    /// `None` marks words that only look inflected.
+    exceptions: HashMap<String, Option<Lemma>>,
+}
+
+impl Lemmatizer {
+    /// Lemmatizer with the bundled exceptions.
+    pub fn load() -> Self {
+        Self::new(EXCEPTIONS)
+    }
+
+    /// Takes `word base kind` lines, see [`EXCEPTIONS`].
+    pub fn new(exceptions: &str) -> Self {
+        let exceptions = exceptions
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let (word, base, kind) = (parts.next()?, parts.next()?, parts.next()?);
+                let lemma = Inflection::parse(kind).map(|inflection| Lemma {
+                    base: base.to_owned(),
+                    inflection,
+                });
+                Some((word.to_owned(), lemma))
+            })
+            .collect();
+        Self { exceptions }
+    }
+
+    /// Base form of an inflected word, `None` for words that are base forms themselves.
+    pub fn lemma(&self, word: &str, dict: &WordsDict) -> Option<Lemma> {
+        if let Some(exception) = self.exceptions.get(word) {
+            return exception.clone();
+        }
+        let lemma = |base: String, inflection| Some(Lemma { base, inflection });
+        if let Some(stem) = word.strip_suffix("ies") {
+            let base = format!("{}y", stem);
+            if is_base(&base, dict) {
+                return lemma(base, Inflection::Plural);
+            }
+        }
+        if let Some(stem) = word.strip_suffix("ied") {
+            let base = format!("{}y", stem);
+            if is_base(&base, dict) {
+                return lemma(base, Inflection::Past);
+            }
+        }
+        if let Some(stem) = word.strip_suffix('s') {
+            if !stem.ends_with('s') && !stem.ends_with('u') && !stem.ends_with('i') {
+                if is_base(stem, dict) {
+                    return lemma(stem.to_owned(), Inflection::Plural);
+                }
+                // boxes, matches, wishes
+                if let Some(stem) = stem.strip_suffix('e').filter(|s| {
+                    ["s", "x", "z", "ch", "sh"]
+                        .iter()
+                        .any(|end| s.ends_with(end))
+                }) {
+                    if is_base(stem, dict) {
+                        return lemma(stem.to_owned(), Inflection::Plural);
+                    }
+                }
+            }
+        }
+        for (suffix, inflection) in [("ed", Inflection::Past), ("ing", Inflection::Progressive)] {
+            let Some(stem) = word.strip_suffix(suffix) else {
+                continue;
+            };
+            if let Some(base) = verb_bases(stem).into_iter().find(|b| is_base(b, dict)) {
+                return lemma(base, inflection);
+            }
+        }
+        None
+    }
+
+    pub fn is_inflection(&self, word: &str, dict: &WordsDict) -> bool {
+        self.lemma(word, dict).is_some()
+    }
+}
+
+/// Candidates for the base of `-ed` and `-ing` forms, most likely first.
+fn verb_bases(stem: &str) -> Vec<String> {
+    let bytes = stem.as_bytes();
+    let mut bases = vec![];
+    if let [.., a, b] = bytes {
+        // clapped, stopping
+        if a == b && b"bdgmnprt".contains(b) && ends_with_cvc(&bytes[..bytes.len() - 1]) {
+            bases.push(stem[..stem.len() - 1].to_owned());
+        }
+    }
+    // A single final consonant after a short vowel would be doubled: hoped is hope, not hop
+    if ends_with_cvc(bytes) {
+        bases.push(format!("{}e", stem));
+        bases.push(stem.to_owned());
+    } else {
+        bases.push(stem.to_owned());
+        bases.push(format!("{}e", stem));
+    }
+    bases
+}
+
+fn is_vowel(byte: u8) -> bool {
+    b"aeiou".contains(&byte)
+}
+
+fn ends_with_cvc(bytes: &[u8]) -> bool {
+    match bytes {
+        [.., c1, v, c2] => !is_vowel(*c1) && is_vowel(*v) && !is_vowel(*c2) && !b"wxy".contains(c2),
+        _ => false,
+    }
+}
+
+fn is_base(base: &str, dict: &WordsDict) -> bool {
+    base.len() >= MIN_BASE_LENGTH && dict.contains(base.as_bytes())
+}
+
+/// Words sharing a base form, the base itself comes first when it is among them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LemmaGroup {
+    pub base: String,
+    pub words: Vec<(String, Option<Inflection>)>,
+}
+
+/// Groups words by base form, groups keep the order of their first word.
+pub fn group_by_lemma(words: impl IntoIterator<Item = (String, Option<Lemma>)>) -> Vec<LemmaGroup> {
+    let mut groups: Vec<LemmaGroup> = vec![];
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for (word, lemma) in words {
+        let (base, inflection) = match lemma {
+            Some(lemma) => (lemma.base, Some(lemma.inflection)),
+            None => (word.clone(), None),
+        };
+        let position = *positions.entry(base.clone()).or_insert_with(|| {
+            groups.push(LemmaGroup {
+                base,
+                words: vec![],
+            });
+            groups.len() - 1
+        });
+        let group = &mut groups[position];
+        if inflection.is_none() {
+            group.words.insert(0, (word, inflection));
+        } else {
+            group.words.push((word, inflection));
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base(lemmatizer: &Lemmatizer, dict: &WordsDict, word: &str) -> Option<(String, Inflection)> {
+        lemmatizer
+            .lemma(word, dict)
+            .map(|lemma| (lemma.base, lemma.inflection))
+    }
+
+    #[test]
+    fn it_finds_bases() {
+        let dict = WordsDict::load();
+        let lemmatizer = Lemmatizer::load();
+        let cases = [
+            ("claps", Some(("clap", Inflection::Plural))),
+            ("clapped", Some(("clap", Inflection::Past))),
+            ("clapping", Some(("clap", Inflection::Progressive))),
+            ("hoped", Some(("hope", Inflection::Past))),
+            ("hoping", Some(("hope", Inflection::Progressive))),
+            ("jumped", Some(("jump", Inflection::Past))),
+            ("added", Some(("add", Inflection::Past))),
+            ("flies", Some(("fly", Inflection::Plural))),
+            ("carried", Some(("carry", Inflection::Past))),
+            ("boxes", Some(("box", Inflection::Plural))),
+            ("horses", Some(("horse", Inflection::Plural))),
+            ("children", Some(("child", Inflection::Plural))),
+            ("went", Some(("go", Inflection::Past))),
+            ("better", Some(("good", Inflection::Comparative))),
+            ("clap", None),
+            ("glass", None),
+            ("corner", None),
+            ("seed", None),
+            ("morning", None),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(
+                base(&lemmatizer, &dict, word),
+                expected.map(|(b, i)| (b.to_owned(), i)),
+                "{}",
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn it_groups_by_base() {
+        let dict = WordsDict::load();
+        let lemmatizer = Lemmatizer::load();
+        let words = ["clapped", "claps", "clap", "lapse", "clapping"]
+            .map(|w| (w.to_owned(), lemmatizer.lemma(w, &dict)));
+        let groups = group_by_lemma(words);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].base, "clap");
+        assert_eq!(
+            groups[0]
+                .words
+                .iter()
+                .map(|(w, _)| w.as_str())
+                .collect::<Vec<_>>(),
+            vec!["clap", "clapped", "claps", "clapping"]
+        );
+        assert_eq!(groups[1].words, vec![("lapse".to_owned(), None)]);
+    }
+}
@@ -1,47 +1,473 @@
-use std::ops::Range;
+use anyhow::{bail, Context, Result};
+use memmap2::Mmap;
+use std::{
+    fmt,
+    fs::{self, File},
+    io::Read,
+    ops::{Deref, Range},
+    path::Path,
+    sync::Arc,
+};
+
+use crate::{
+    dict_format::{self, Header, Layout, HEADER_SIZE, MAGIC, SHORTCUTS_SIZE, VERSION},
+    LettersMask,
+};
 
 pub const FILE: &[u8; 3_864_811] = include_bytes!("../data/words_alpha.txt");
 
-#[derive(Debug, Clone)]
+/// [`FILE`] encoded by the build script.
+static BINARY: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/words_alpha.bin"));
+
+#[derive(Clone)]
+enum DictData {
+    Static(&'static [u8]),
+    Owned(Arc<[u8]>),
+    Mapped(Arc<Mmap>),
+}
+
+impl Deref for DictData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            DictData::Static(bytes) => bytes,
+            DictData::Owned(bytes) => bytes,
+            DictData::Mapped(mmap) => mmap,
+        }
+    }
+}
+
+/// Sorted word list in the binary format, see [`dict_format`].
+/// Words and indexes are read in place, nothing is parsed on load.
+#[derive(Clone)]
 pub struct WordsDict {
-    words: Vec<&'static [u8]>,
+    data: DictData,
+    layout: Layout,
+    checksum: u64,
+}
+
+impl fmt::Debug for WordsDict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WordsDict")
+            .field("size", &self.size())
+            .field("bytes", &self.data.len())
+            .finish()
+    }
 }
 
 impl WordsDict {
     pub fn load() -> Self {
-        let mut words: Vec<_> = FILE
-            .split(|&byte| byte == b'\n')
-            .filter(|w| !w.is_empty())
-            .collect();
-        words.sort();
+        Self::from_data(DictData::Static(BINARY), false).expect("bundled dictionary is valid")
+    }
 
-        Self { words }
+    /// Parses newline separated word list.
+    /// Words with anything but lowercase latin letters are skipped.
+    pub fn from_text(text: &[u8]) -> Self {
+        Self::from_encoded(dict_format::encode_text(text))
+    }
+
+    /// Wraps bytes fresh from the encoder, they are trusted to be valid.
+    pub(crate) fn from_encoded(bytes: Vec<u8>) -> Self {
+        Self::from_data(DictData::Owned(bytes.into()), false).expect("encoded dictionary is valid")
+    }
+
+    /// Checks the header and the checksum of a binary dictionary.
+    pub fn from_binary(bytes: Vec<u8>) -> Result<Self> {
+        Self::from_data(DictData::Owned(bytes.into()), true)
+    }
+
+    /// Reads a binary dictionary into memory, or parses a word list otherwise.
+    /// The file may be rewritten in any way afterwards, so this is the one to reload from.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        if bytes.starts_with(&MAGIC) {
+            return Self::from_binary(bytes);
+        }
+        Ok(Self::from_text(&bytes))
+    }
+
+    /// Opens a binary dictionary with mmap, or parses a word list otherwise.
+    ///
+    /// # Safety
+    ///
+    /// Mapped file must not be modified while the dictionary or its clones are in use,
+    /// only replaced by renaming another file over it, like [`WordsDict::save`] does.
+    /// Writing into it in place is undefined behavior and may kill the process with `SIGBUS`.
+    pub unsafe fn map(path: impl AsRef<Path>) -> Result<Self> {
+        let mut file = File::open(&path)?;
+        let mut magic = [0; MAGIC.len()];
+        if file.read_exact(&mut magic).is_ok() && magic == MAGIC {
+            // SAFETY: upheld by the caller
+            let mmap = unsafe { Mmap::map(&file)? };
+            return Self::from_data(DictData::Mapped(Arc::new(mmap)), true);
+        }
+        Ok(Self::from_text(&fs::read(path)?))
+    }
+
+    /// `verify` checks the checksum and every index of data that doesn't come from the encoder,
+    /// so a corrupted file is rejected here instead of panicking on lookups.
+    fn from_data(data: DictData, verify: bool) -> Result<Self> {
+        let Some(header) = Header::read(&data) else {
+            bail!("dictionary header is missing");
+        };
+        if header.version != VERSION {
+            bail!(
+                "dictionary version {} is not supported, expected {}",
+                header.version,
+                VERSION
+            );
+        }
+        let layout = Layout::new(&header);
+        if layout.size() != data.len() {
+            bail!(
+                "dictionary size is {} bytes, expected {}",
+                data.len(),
+                layout.size()
+            );
+        }
+        if verify && dict_format::checksum(&data[HEADER_SIZE..]) != header.checksum {
+            bail!("dictionary checksum does not match");
+        }
+        let dict = Self {
+            data,
+            layout,
+            checksum: header.checksum,
+        };
+        if dict.offset(dict.size()) as usize != dict.layout.blob.len() {
+            bail!("dictionary offsets do not match the words");
+        }
+        if verify {
+            dict.verify_indexes()?;
+        }
+        Ok(dict)
+    }
+
+    fn verify_indexes(&self) -> Result<()> {
+        let size = self.size();
+        let read = |at: usize| dict_format::read_u32(&self.data, at) as usize;
+        let offsets = (0..=size).map(|i| self.offset(i) as usize);
+        if self.offset(0) != 0 || !is_sorted_within(offsets, self.layout.blob.len()) {
+            bail!("dictionary offsets are out of order");
+        }
+        let group_starts = self.layout.groups.clone().step_by(8).map(|at| read(at + 4));
+        if !is_sorted_within(group_starts, size) {
+            bail!("dictionary letter groups are out of order");
+        }
+        if self
+            .layout
+            .group_indexes
+            .clone()
+            .step_by(4)
+            .any(|at| read(at) >= size)
+        {
+            bail!("dictionary letter groups point past the words");
+        }
+        let shortcuts = self.shortcuts().into_iter().filter(|&i| i != -1);
+        if shortcuts.clone().any(|i| i < 0)
+            || !is_sorted_within(shortcuts.map(|i| i as usize), size)
+        {
+            bail!("dictionary prefix index is out of order");
+        }
+        Ok(())
+    }
+
+    /// Dictionary in the binary format, ready to be written to a file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Writes the binary dictionary next to `path` and renames it over,
+    /// so dictionaries mapped from the old file keep reading it.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let Some(name) = path.file_name() else {
+            bail!("{} is not a file path", path.display());
+        };
+        let mut tmp_name = name.to_owned();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+        fs::write(&tmp_path, self.as_bytes())
+            .and_then(|_| fs::rename(&tmp_path, path))
+            .with_context(|| format!("unable to write {}", path.display()))
     }
 }
 
 impl WordsDict {
     pub fn size(&self) -> usize {
-        self.words.len()
+        self.layout.masks.len() / 4
+    }
+
+    /// Checksum of the encoded words, the same words always have the same one.
+    pub fn checksum(&self) -> u64 {
+        self.checksum
+    }
+
+    pub fn get(&self, at: usize) -> Option<&[u8]> {
+        if at >= self.size() {
+            return None;
+        }
+        let blob = &self.data[self.layout.blob.clone()];
+        Some(&blob[self.offset(at) as usize..self.offset(at + 1) as usize])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.iter_range(0..self.size())
+    }
+
+    pub fn iter_range(&self, range: Range<usize>) -> impl Iterator<Item = &[u8]> {
+        let blob = &self.data[self.layout.blob.clone()];
+        let offsets = &self.data[self.layout.offsets.start + range.start * 4
+            ..self.layout.offsets.start + (range.end + 1) * 4];
+        (0..range.len()).map(move |i| {
+            let start = dict_format::read_u32(offsets, i * 4) as usize;
+            let end = dict_format::read_u32(offsets, i * 4 + 4) as usize;
+            &blob[start..end]
+        })
+    }
+
+    /// Binary search over the sorted words.
+    pub fn contains(&self, word: &[u8]) -> bool {
+        let (mut left, mut right) = (0, self.size());
+        while left < right {
+            let mid = (left + right) / 2;
+            match self.get(mid).map(|w| w.cmp(word)) {
+                Some(std::cmp::Ordering::Less) => left = mid + 1,
+                Some(std::cmp::Ordering::Greater) => right = mid,
+                _ => return true,
+            }
+        }
+        false
+    }
+
+    pub fn mask(&self, at: usize) -> Option<LettersMask> {
+        if at >= self.size() {
+            return None;
+        }
+        let bits = dict_format::read_u32(&self.data, self.layout.masks.start + at * 4);
+        Some(LettersMask::from_bits(bits))
     }
 
-    pub fn get(&self, at: usize) -> Option<&&[u8]> {
-        self.words.get(at)
+    /// Words in the range paired with their letters.
+    pub fn iter_range_masked(
+        &self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = (&[u8], LettersMask)> {
+        let masks = &self.data
+            [self.layout.masks.start + range.start * 4..self.layout.masks.start + range.end * 4];
+        self.iter_range(range).zip(
+            masks
+                .chunks_exact(4)
+                .map(|bits| LettersMask::from_bits(u32::from_le_bytes(bits.try_into().unwrap()))),
+        )
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &&[u8]> {
-        self.words.iter()
+    /// Indexes of the words made of exactly these letters, in dictionary order.
+    pub fn indexes_with_mask(&self, mask: LettersMask) -> impl Iterator<Item = usize> + '_ {
+        let groups_count = self.layout.groups.len() / 8;
+        let group_at = |i: usize| {
+            let at = self.layout.groups.start + i * 8;
+            (
+                dict_format::read_u32(&self.data, at),
+                dict_format::read_u32(&self.data, at + 4) as usize,
+            )
+        };
+        let (mut left, mut right) = (0, groups_count);
+        while left < right {
+            let mid = (left + right) / 2;
+            if group_at(mid).0 < mask.bits() {
+                left = mid + 1;
+            } else {
+                right = mid;
+            }
+        }
+        let range = if left < groups_count && group_at(left).0 == mask.bits() {
+            let end = if left + 1 < groups_count {
+                group_at(left + 1).1
+            } else {
+                self.size()
+            };
+            group_at(left).1..end
+        } else {
+            0..0
+        };
+        range.map(|i| {
+            dict_format::read_u32(&self.data, self.layout.group_indexes.start + i * 4) as usize
+        })
     }
 
-    pub fn iter_range(&self, range: Range<usize>) -> impl Iterator<Item = &&[u8]> {
-        self.words[range].iter()
+    /// Stored prefix index, see [`crate::WordsShortcuts`].
+    pub fn shortcuts(&self) -> [i32; SHORTCUTS_SIZE] {
+        let mut shortcuts = [-1; SHORTCUTS_SIZE];
+        for (i, shortcut) in shortcuts.iter_mut().enumerate() {
+            *shortcut =
+                dict_format::read_u32(&self.data, self.layout.shortcuts.start + i * 4) as i32;
+        }
+        shortcuts
+    }
+
+    fn offset(&self, at: usize) -> u32 {
+        dict_format::read_u32(&self.data, self.layout.offsets.start + at * 4)
     }
 }
 
+/// Values never decrease and none is over `max`.
+fn is_sorted_within(mut values: impl Iterator<Item = usize>, max: usize) -> bool {
+    values
+        .try_fold(0, |last, value| {
+            (last <= value && value <= max).then_some(value)
+        })
+        .is_some()
+}
+
 impl WordsDict {
-    pub fn search_range(&self, range: Range<usize>, prefix: &[u8]) -> Vec<String> {
+    /// Words of `range` starting with `prefix`, read as they are iterated.
+    pub fn search_range<'a>(
+        &'a self,
+        range: Range<usize>,
+        prefix: &'a [u8],
+    ) -> impl Iterator<Item = &'a [u8]> + 'a {
         self.iter_range(range)
-            .filter(|w| w.starts_with(prefix))
-            .map(|b| String::from_utf8_lossy(b).to_string())
-            .collect()
+            .filter(move |w| w.starts_with(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WordsShortcuts;
+
+    /// Straightforward parse of the word list, as it was done before the binary format.
+    fn text_words(text: &[u8]) -> Vec<&[u8]> {
+        let mut words: Vec<_> = text
+            .split(|&byte| byte == b'\n')
+            .filter(|w| !w.is_empty())
+            .collect();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn bundled_matches_text() {
+        let dict = WordsDict::load();
+        let words = text_words(FILE);
+        assert_eq!(dict.size(), words.len());
+        assert!(dict.iter().eq(words.iter().copied()));
+        assert_eq!(dict.as_bytes(), WordsDict::from_text(FILE).as_bytes());
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let text = b"tea\neat\nate\nzebra\n\neat\nTea\nbe\r\n";
+        let dict = WordsDict::from_text(text);
+        let loaded = WordsDict::from_binary(dict.as_bytes().to_vec()).unwrap();
+        assert_eq!(loaded.checksum(), dict.checksum());
+        assert_ne!(loaded.checksum(), WordsDict::from_text(b"tea\n").checksum());
+        let words: Vec<&[u8]> = loaded.iter().collect();
+        assert_eq!(words, vec![&b"ate"[..], b"be", b"eat", b"tea", b"zebra"]);
+        assert_eq!(loaded.mask(0), Some(LettersMask::from_word(b"ate")));
+        assert!(loaded.contains(b"zebra") && loaded.contains(b"ate"));
+        assert!(!loaded.contains(b"bee") && !loaded.contains(b""));
+        let anagrams: Vec<usize> = loaded
+            .indexes_with_mask(LettersMask::from_word(b"tea"))
+            .collect();
+        assert_eq!(anagrams, vec![0, 2, 3]);
+        assert_eq!(
+            loaded
+                .indexes_with_mask(LettersMask::from_word(b"xyz"))
+                .count(),
+            0
+        );
+        let shortcuts = WordsShortcuts::new(&loaded);
+        assert_eq!(shortcuts.search_range(b"e"), Some(2..3));
+        assert_eq!(shortcuts.search_range(b"z"), Some(4..5));
+    }
+
+    #[test]
+    fn text_is_cleaned_up() {
+        let dict = WordsDict::from_text(b"bee\r\nbee\nBee\nbe-e\nb3e\n\nant\nant\r\n");
+        let words: Vec<&[u8]> = dict.iter().collect();
+        assert_eq!(words, vec![&b"ant"[..], b"bee"]);
+    }
+
+    #[test]
+    fn binary_rejects_corrupted() {
+        let bytes = WordsDict::from_text(b"one\ntwo\n").as_bytes().to_vec();
+
+        let mut corrupted = bytes.clone();
+        *corrupted.last_mut().unwrap() = b'x';
+        assert!(WordsDict::from_binary(corrupted).is_err());
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[8] = 0;
+        assert!(WordsDict::from_binary(wrong_version).is_err());
+
+        assert!(WordsDict::from_binary(bytes[..bytes.len() - 1].to_vec()).is_err());
+        assert!(WordsDict::from_binary(b"one\ntwo\n".to_vec()).is_err());
+    }
+
+    /// Rewrites a u32 of the encoded dictionary and fixes up the checksum,
+    /// as a buggy encoder would produce.
+    fn with_u32(bytes: &[u8], at: usize, value: u32) -> Vec<u8> {
+        let mut bytes = bytes.to_vec();
+        bytes[at..at + 4].copy_from_slice(&value.to_le_bytes());
+        let checksum = dict_format::checksum(&bytes[HEADER_SIZE..]);
+        bytes[24..32].copy_from_slice(&checksum.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn binary_rejects_bad_indexes() {
+        let dict = WordsDict::from_text(b"one\ntwo\nowe\n");
+        let bytes = dict.as_bytes();
+        let layout = dict.layout.clone();
+
+        // Second word ends before it starts
+        assert!(WordsDict::from_binary(with_u32(bytes, layout.offsets.start + 8, 1)).is_err());
+        // Offset past the words, but the last one still matches
+        assert!(WordsDict::from_binary(with_u32(bytes, layout.offsets.start + 4, 99)).is_err());
+        // Letter group past the words
+        assert!(WordsDict::from_binary(with_u32(bytes, layout.groups.start + 4, 9)).is_err());
+        assert!(WordsDict::from_binary(with_u32(bytes, layout.group_indexes.start, 3)).is_err());
+        // Prefix index past the words
+        assert!(WordsDict::from_binary(with_u32(bytes, layout.shortcuts.start, 7)).is_err());
+
+        assert!(WordsDict::from_binary(bytes.to_vec()).is_ok());
+    }
+
+    #[test]
+    fn opens_both_formats() {
+        let dir = std::env::temp_dir().join(format!("words_dict_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let text_path = dir.join("words.txt");
+        let binary_path = dir.join("words.bin");
+        fs::write(&text_path, "cab\nabc\n").unwrap();
+        fs::write(&binary_path, WordsDict::from_text(b"cab\nabc\n").as_bytes()).unwrap();
+
+        let from_text = WordsDict::open(&text_path).unwrap();
+        let from_binary = WordsDict::open(&binary_path).unwrap();
+        assert_eq!(from_text.as_bytes(), from_binary.as_bytes());
+        assert_eq!(from_binary.get(1), Some(&b"cab"[..]));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn saving_over_keeps_mapped_snapshots() {
+        let dir = std::env::temp_dir().join(format!("words_dict_save_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("words.bin");
+        WordsDict::from_text(b"old\nwords\n").save(&path).unwrap();
+        // SAFETY: the file is only replaced through `save`
+        let mapped = unsafe { WordsDict::map(&path) }.unwrap();
+
+        WordsDict::from_text(b"fresh\nnew\nwords\n")
+            .save(&path)
+            .unwrap();
+        assert!(mapped.iter().eq([&b"old"[..], b"words"]));
+        assert_eq!(WordsDict::open(&path).unwrap().size(), 3);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
     }
 }
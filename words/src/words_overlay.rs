@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+
+use crate::{dict_format, WordsDict};
+
+/// Words added to and removed from a base dictionary.
+/// Removal wins when a word is in both lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordsOverlay {
+    allow: BTreeSet<String>,
+    block: BTreeSet<String>,
+}
+
+impl WordsOverlay {
+    /// Takes newline separated lists, lines starting with `#` are comments.
+    /// Words are lowercased, anything but latin letters is skipped.
+    pub fn new(allow: &str, block: &str) -> Self {
+        Self {
+            allow: parse_list(allow),
+            block: parse_list(block),
+        }
+    }
+
+    pub fn allow(&self) -> impl Iterator<Item = &str> {
+        self.allow.iter().map(String::as_str)
+    }
+
+    pub fn block(&self) -> impl Iterator<Item = &str> {
+        self.block.iter().map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.block.is_empty()
+    }
+
+    /// Resolves the overlay into a single dictionary.
+    pub fn apply(&self, base: &WordsDict) -> WordsDict {
+        if self.is_empty() {
+            return base.clone();
+        }
+        let words = base
+            .iter()
+            .chain(self.allow.iter().map(String::as_bytes))
+            .filter(|word| !self.block.contains(&*String::from_utf8_lossy(word)));
+        WordsDict::from_encoded(dict_format::encode_words(words))
+    }
+}
+
+/// One word per line, as stored in overlay files.
+pub fn list_to_text<'a>(words: impl Iterator<Item = &'a str>) -> String {
+    words.fold(String::new(), |mut text, word| {
+        text.push_str(word);
+        text.push('\n');
+        text
+    })
+}
+
+fn parse_list(text: &str) -> BTreeSet<String> {
+    text.lines()
+        .map(|line| line.trim().to_lowercase())
+        .filter(|word| !word.is_empty() && !word.starts_with('#'))
+        .filter(|word| word.bytes().all(|b| b.is_ascii_lowercase()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_lists() {
+        let overlay = WordsOverlay::new("# comment\n Zebra \n\nno-way\nyak\n", "ant\r\n");
+        assert_eq!(overlay.allow().collect::<Vec<_>>(), vec!["yak", "zebra"]);
+        assert_eq!(overlay.block().collect::<Vec<_>>(), vec!["ant"]);
+        assert_eq!(list_to_text(overlay.allow()), "yak\nzebra\n");
+    }
+
+    #[test]
+    fn it_applies_to_base() {
+        let base = WordsDict::from_text(b"ant\nbee\ncat\n");
+        let overlay = WordsOverlay::new("dog\nbee\nemu\n", "cat\nemu\n");
+        let dict = overlay.apply(&base);
+        let words: Vec<&[u8]> = dict.iter().collect();
+        assert_eq!(words, vec![&b"ant"[..], b"bee", b"dog"]);
+    }
+
+    #[test]
+    fn empty_overlay_keeps_base() {
+        let base = WordsDict::from_text(b"ant\nbee\n");
+        let dict = WordsOverlay::default().apply(&base);
+        assert_eq!(dict.as_bytes(), base.as_bytes());
+    }
+}
@@ -1,57 +1,28 @@
 use std::ops::Range;
 
-use crate::words_dict::WordsDict;
-
-const DEPTH: usize = 2;
-
-const fn calc_array_size(n: usize) -> usize {
-    if n == 0 {
-        return 0;
-    }
-    26u32.pow(n as u32) as usize + calc_array_size(n - 1)
-}
-
-const ARRAY_SIZE: usize = calc_array_size(DEPTH);
+use crate::{
+    dict_format::{calc_one_leter_i, calc_two_leter_i, SHORTCUTS_SIZE},
+    words_dict::WordsDict,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub struct WordsShortcuts {
     size: u32,
-    shortcuts: [i32; ARRAY_SIZE],
+    shortcuts: [i32; SHORTCUTS_SIZE],
 }
 
 impl WordsShortcuts {
+    /// Prefix index is precomputed with the dictionary, see [`crate::dict_format`].
     pub fn new(dict: &WordsDict) -> Self {
-        let mut shortcuts = [-1; ARRAY_SIZE];
-        shortcuts[0] = 0;
-
-        let mut latest_first = *dict.get(0).unwrap().first().unwrap();
-        let mut latest_second = None;
-
-        for (i, word) in dict.iter().enumerate() {
-            let first = word.first().unwrap().to_ascii_lowercase();
-            if latest_first != first {
-                shortcuts[Self::calc_one_leter_i(first)] = i as i32;
-                latest_first = first;
-            }
-            let second = word.get(1).map(|c| c.to_ascii_lowercase());
-            match (second, latest_second) {
-                (None, _) => latest_second = None,
-                (Some(curr), Some(latest)) if curr == latest => {}
-                (Some(second), _) => {
-                    shortcuts[Self::calc_two_leter_i(first, second)] = i as i32;
-                    latest_second = Some(second);
-                }
-            }
-        }
-
         Self {
             size: dict.size() as u32,
-            shortcuts,
+            shortcuts: dict.shortcuts(),
         }
     }
 }
 
 impl<'a> WordsShortcuts {
+    /// Only latin letters are indexed, a prefix starting with anything else finds nothing.
     pub fn search_range(&self, prefix: &'a [u8]) -> Option<Range<usize>> {
         let mut bytes = prefix.iter();
 
@@ -60,10 +31,13 @@ impl<'a> WordsShortcuts {
         let Some(first_char) = bytes.next().map(|c| c.to_ascii_lowercase()) else {
             return Some(0..right);
         };
+        if !first_char.is_ascii_lowercase() {
+            return None;
+        }
 
         let left = self
             .shortcuts
-            .get(Self::calc_one_leter_i(first_char))
+            .get(calc_one_leter_i(first_char))
             .filter(|&c| c >= &0)
             .copied()
             .map(|c| c as usize)?;
@@ -71,7 +45,7 @@ impl<'a> WordsShortcuts {
         for offset in (first_char + 1)..=122 {
             if let Some(next_seg) = self
                 .shortcuts
-                .get(Self::calc_one_leter_i(offset))
+                .get(calc_one_leter_i(offset))
                 .filter(|&c| c >= &0)
                 .copied()
                 .map(|c| c as usize)
@@ -84,18 +58,21 @@ impl<'a> WordsShortcuts {
         let Some(second_char) = bytes.next().map(|c| c.to_ascii_lowercase()) else {
             return Some(left..right);
         };
+        if !second_char.is_ascii_lowercase() {
+            return None;
+        }
 
         let left = self
             .shortcuts
-            .get(Self::calc_two_leter_i(first_char, second_char))
+            .get(calc_two_leter_i(first_char, second_char))
             .copied()
-            .filter(|c| c > &0)
+            .filter(|c| c >= &0)
             .map(|c| c as usize)?;
 
         for offset in (second_char + 1)..=122 {
             if let Some(next_seg) = self
                 .shortcuts
-                .get(Self::calc_two_leter_i(first_char, offset))
+                .get(calc_two_leter_i(first_char, offset))
                 .filter(|&c| c >= &0)
                 .copied()
                 .map(|c| c as usize)
@@ -109,16 +86,6 @@ impl<'a> WordsShortcuts {
     }
 }
 
-impl WordsShortcuts {
-    fn calc_one_leter_i(one: u8) -> usize {
-        (one as usize - 97) * 26
-    }
-
-    fn calc_two_leter_i(first: u8, second: u8) -> usize {
-        Self::calc_one_leter_i(first) + second as usize - 96
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use std::sync::LazyLock;
@@ -169,6 +136,44 @@ mod tests {
         b.iter(|| SHORTCUTS.search_range(b"aa"));
     }
 
+    #[test]
+    fn search_custom_dict() {
+        let dict = WordsDict::from_text(b"bz\nca\ncz\nbza\nbb\nzz\n");
+        let shortcuts = WordsShortcuts::new(&dict);
+        assert_eq!(shortcuts.search_range(b"b"), Some(0..3));
+        assert_eq!(shortcuts.search_range(b"bz"), Some(1..3));
+        assert_eq!(shortcuts.search_range(b"c"), Some(3..5));
+        assert_eq!(shortcuts.search_range(b"cz"), Some(4..5));
+        assert_eq!(shortcuts.search_range(b"zz"), Some(5..6));
+        assert_eq!(shortcuts.search_range(b"a"), None);
+    }
+
+    #[test]
+    fn search_prefix_of_first_word() {
+        // The first two letter prefix starts at 0, which used to count as missing
+        let dict = WordsDict::from_text(b"ab\nabc\nb\n");
+        let shortcuts = WordsShortcuts::new(&dict);
+        assert_eq!(shortcuts.search_range(b"ab"), Some(0..2));
+        assert_eq!(shortcuts.search_range(b"a"), Some(0..2));
+    }
+
+    #[test]
+    fn search_letters_do_not_share_slots() {
+        // With 26 slots per first letter `b` took the slot of `az`
+        let dict = WordsDict::from_text(b"az\nazz\nb\nba\n");
+        let shortcuts = WordsShortcuts::new(&dict);
+        assert_eq!(shortcuts.search_range(b"az"), Some(0..2));
+        assert_eq!(shortcuts.search_range(b"b"), Some(2..4));
+        assert_eq!(shortcuts.search_range(b"ba"), Some(3..4));
+    }
+
+    #[test]
+    fn search_not_letters() {
+        assert_eq!(SHORTCUTS.search_range(b"1"), None);
+        assert_eq!(SHORTCUTS.search_range(b"a'"), None);
+        assert_eq!(SHORTCUTS.search_range("é".as_bytes()), None);
+    }
+
     #[test]
     fn search_three_letter() {
         let range = SHORTCUTS.search_range(b"aaa");